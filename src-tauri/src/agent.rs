@@ -1,6 +1,9 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
+use std::sync::Arc;
+
+use crate::error::AppResult;
+use crate::settings::{ModelRole, SettingsStore};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaRequest {
@@ -26,23 +29,26 @@ pub struct SharkNegotiation {
 
 pub struct SharkAgent {
     client: Client,
-    ollama_url: String,
+    /// The negotiation model and its endpoint are read per request, so
+    /// settings changes apply live.
+    settings: Arc<SettingsStore>,
 }
 
 impl SharkAgent {
-    pub fn new(ollama_url: Option<String>) -> Self {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
         SharkAgent {
             client: Client::new(),
-            ollama_url: ollama_url.unwrap_or_else(|| "http://localhost:11434".to_string()),
+            settings,
         }
     }
 
+    #[tracing::instrument(skip_all, fields(market_price = market_price))]
     pub async fn negotiate(
         &self,
         intent: &str,
         price_ceiling: f64,
         market_price: f64,
-    ) -> Result<SharkNegotiation, Box<dyn Error>> {
+    ) -> AppResult<SharkNegotiation> {
         let system_prompt = format!(
             r#"You are a "Shark Mode Agent" designed for aggressive, profit-maximizing negotiations in a privacy-first mesh network.
 
@@ -64,8 +70,10 @@ Intent: {}
             price_ceiling, market_price, intent
         );
 
+        let endpoint = self.settings.get().model_endpoint(ModelRole::Negotiation);
+        tracing::debug!(model = %endpoint.model, "asking the negotiation model");
         let request = OllamaRequest {
-            model: "llama2".to_string(),
+            model: endpoint.model,
             prompt: format!(
                 "Analyze this trading intent and provide your negotiation strategy: {}",
                 intent
@@ -76,7 +84,7 @@ Intent: {}
 
         let response = self
             .client
-            .post(format!("{}/api/generate", self.ollama_url))
+            .post(format!("{}/api/generate", endpoint.url))
             .json(&request)
             .send()
             .await?;
@@ -87,6 +95,7 @@ Intent: {}
         let negotiation: serde_json::Value = serde_json::from_str(&ollama_response.response)
             .unwrap_or_else(|_| {
                 // Fallback if AI doesn't return proper JSON
+                tracing::warn!("model reply was not JSON, falling back to a conservative bid");
                 serde_json::json!({
                     "recommended_bid": market_price * 0.95,
                     "strategy": "Conservative bid below market",
@@ -110,18 +119,15 @@ Intent: {}
     pub async fn verify_strategy(
         &self,
         negotiation: &SharkNegotiation,
-    ) -> Result<bool, Box<dyn Error>> {
+    ) -> AppResult<bool> {
         // Verify the AI didn't cheat the owner
         if negotiation.recommended_bid > negotiation.user_price_ceiling {
-            println!(
-                "⚠️  Agent violated price ceiling! Bid: {}, Ceiling: {}",
-                negotiation.recommended_bid, negotiation.user_price_ceiling
-            );
+            tracing::warn!(bid = negotiation.recommended_bid, "agent bid exceeds the price ceiling");
             return Ok(false);
         }
 
         if negotiation.recommended_bid <= 0.0 {
-            println!("⚠️  Invalid bid amount: {}", negotiation.recommended_bid);
+            tracing::warn!(bid = negotiation.recommended_bid, "agent returned a non-positive bid");
             return Ok(false);
         }
 
@@ -1,11 +1,17 @@
-use crate::blockchain_bridge::BlockchainBridge;
-use crate::mesh::{MeshNetwork, MeshEvent, PrivacyIntent};
+use crate::connectivity::{self, ConnectivityStatus};
+use crate::mesh::{MeshEvent, MeshNetwork};
+use crate::scheduler;
+use crate::error::AppResult;
+use crate::lifecycle::{NodeState, Subsystem, SubsystemStatus};
 use crate::AppState;
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Emitter, State};
+use std::time::{Duration, Instant};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::sync::mpsc;
-use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Semaphore};
+
+/// Answer peers' catalogue requests at most this often, so a burst of
+/// requests from several offline peers costs one broadcast.
+const CATALOGUE_SHARE_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Clone, serde::Serialize)]
 struct BootstrapStatus {
@@ -17,61 +23,234 @@ struct BootstrapStatus {
 pub struct SystemBootstrap;
 
 impl SystemBootstrap {
-    /// 1. Phase 1 (Sync): Check internet & sync native AVAX balance via Avalanche RPC
-    pub async fn phase_1_sync(bridge: &Arc<Mutex<BlockchainBridge>>, app: &AppHandle) {
+    /// Runs every bootstrap phase. Each phase records its outcome in
+    /// `state.lifecycle` rather than aborting the rest, so a failed phase
+    /// leaves the node `Degraded` and can be retried on its own later.
+    pub async fn run(state: &AppState, app: &AppHandle) {
+        // Ollama can take a while (model pull); don't hold the other phases up.
+        let ollama_state = state.clone();
+        tauri::async_runtime::spawn(async move {
+            Self::start_ollama(&ollama_state).await;
+        });
+
+        Self::phase_1_sync(state, app).await;
+        tauri::async_runtime::spawn(connectivity::run_monitor(state.bridge.clone()));
+        let update_app = app.clone();
+        tauri::async_runtime::spawn(scheduler::run(state.clone(), move |update| {
+            let _ = update_app.emit("chain-update", update);
+        }));
+        Self::phase_2_delegate(state, app).await;
+        Self::phase_3_network(state, app).await;
+    }
+
+    /// Pushes every `NodeState` change to the frontend as a `node-state` event.
+    pub fn forward_node_state(state: &AppState, app: &AppHandle) {
+        let mut rx = state.lifecycle.subscribe();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let node_state = rx.borrow_and_update().clone();
+                tracing::info!(state = ?node_state, "node state changed");
+                let _ = app.emit("node-state", node_state);
+            }
+        });
+    }
+
+    /// Pushes every connectivity transition to the frontend as a
+    /// `connectivity-changed` event.
+    pub fn forward_connectivity(state: &AppState, app: &AppHandle) {
+        let mut rx = state.bridge.connectivity.subscribe();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let status = rx.borrow_and_update().clone();
+                tracing::info!(state = ?status.state, endpoint = ?status.endpoint, "connectivity changed");
+                let _ = app.emit("connectivity-changed", status);
+            }
+        });
+    }
+
+    /// Pushes every proof job state change to the frontend as a `proof-job`
+    /// event.
+    pub fn forward_proof_jobs(state: &AppState, app: &AppHandle) {
+        let mut rx = state.proof_jobs.subscribe();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(job) => {
+                        let _ = app.emit("proof-job", job);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "proof job events dropped");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Starts (or restarts) the local Ollama service and waits for it to answer.
+    #[tracing::instrument(skip_all)]
+    pub async fn start_ollama(state: &AppState) {
+        state.lifecycle.set(Subsystem::Ollama, SubsystemStatus::Starting);
+        let ollama = &state.ollama;
+
+        tracing::info!("checking Ollama installation");
+        if !ollama.is_installed() {
+            tracing::warn!("Ollama not found; install it from https://ollama.ai (or `brew install ollama`)");
+            state.lifecycle.set(Subsystem::Ollama, SubsystemStatus::Down("Ollama is not installed".to_string()));
+            return;
+        }
+
+        if let Err(e) = ollama.initialize().await {
+            tracing::error!(error = %e, "failed to initialize Ollama");
+            state.lifecycle.set(Subsystem::Ollama, SubsystemStatus::Down(e));
+            return;
+        }
+
+        for _ in 1..=10 {
+            if ollama.health_check().await {
+                tracing::info!("Ollama service is healthy");
+                state.lifecycle.set(Subsystem::Ollama, SubsystemStatus::Up);
+                return;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+        tracing::warn!("Ollama service not responding");
+        state.lifecycle.set(Subsystem::Ollama, SubsystemStatus::Down("Ollama service not responding".to_string()));
+    }
+
+    /// 1. Phase 1 (Sync): Probe the RPC endpoints & sync native AVAX balance via Avalanche RPC
+    #[tracing::instrument(skip_all)]
+    pub async fn phase_1_sync(state: &AppState, app: &AppHandle) {
+        state.lifecycle.set(Subsystem::BridgeSync, SubsystemStatus::Starting);
         Self::emit(app, "PHASE_1_SYNC", "Checking connection...", 10);
 
-        if Self::check_connectivity().await {
+        if state.bridge.probe_connectivity().await {
             Self::emit(app, "PHASE_1_SYNC", "Online. Syncing Avalanche RPC balance...", 20);
-            let bridge_lock = bridge.lock().await;
 
-            let address = bridge_lock.get_primary_address();
+            let address = state.bridge.get_primary_address();
             Self::emit(app, "PHASE_1_SYNC", &format!("Identity: {}", address), 25);
 
             // Use the real identity (argument is ignored if identity exists)
-            if let Err(e) = bridge_lock.sync_state("ignored_override").await {
-                eprintln!("Sync failed: {}", e);
+            if let Err(e) = state.bridge.sync_state("ignored_override").await {
+                tracing::warn!(error = %e, "bridge sync failed");
                 Self::emit(app, "PHASE_1_ERROR", &format!("Sync Error: {}", e), 0);
+                state.lifecycle.set(Subsystem::BridgeSync, SubsystemStatus::Down(e.to_string()));
             } else {
                 Self::emit(app, "PHASE_1_SYNC", "Snapshot Secured via Avalanche RPC.", 30);
+                state.lifecycle.set(Subsystem::BridgeSync, SubsystemStatus::Up);
             }
         } else {
-             Self::emit(app, "PHASE_1_SYNC", "Offline Mode. Using local snapshot.", 30);
+            Self::emit(app, "PHASE_1_SYNC", "Offline Mode. Using local snapshot.", 30);
+            state.lifecycle.set(Subsystem::BridgeSync, SubsystemStatus::Down("offline — using local snapshot".to_string()));
         }
     }
 
     /// 2. Phase 2 (Delegate): Instant Session
-    pub async fn phase_2_delegate(bridge: &Arc<Mutex<BlockchainBridge>>, app: &AppHandle) {
+    #[tracing::instrument(skip_all)]
+    pub async fn phase_2_delegate(state: &AppState, app: &AppHandle) {
         Self::emit(app, "PHASE_2_DELEGATE", "Initializing Instant Session...", 40);
-        let mut bridge_lock = bridge.lock().await;
-        let session = bridge_lock.init_instant_session();
+        let session = state.bridge.init_instant_session(state.settings.get().session_expiry());
         Self::emit(app, "PHASE_2_DELEGATE", &format!("Authority Delegated: {}", session.session_id), 60);
     }
 
-    /// 3. Phase 3 (Network): Init Libp2p
-    /// Returns the initialized MeshNetwork and channels
-    pub async fn phase_3_network(app: &AppHandle) -> Result<(MeshNetwork, mpsc::UnboundedSender<PrivacyIntent>, mpsc::UnboundedReceiver<MeshEvent>, mpsc::UnboundedReceiver<PrivacyIntent>, mpsc::UnboundedSender<MeshEvent>), String> {
+    /// 3. Phase 3 (Network): Init Libp2p.
+    ///
+    /// Boots a fresh swarm, replacing (and stopping) any previous one, and
+    /// starts the tasks that drive it and forward its events to the frontend.
+    #[tracing::instrument(skip_all)]
+    pub async fn phase_3_network(state: &AppState, app: &AppHandle) {
+        state.lifecycle.set(Subsystem::Mesh, SubsystemStatus::Starting);
+        state.mesh.stop();
         Self::emit(app, "PHASE_3_NETWORK", "Booting Libp2p Swarm...", 70);
-        
-        let (event_tx, event_rx) = mpsc::unbounded_channel();
-        let (intent_tx, intent_rx) = mpsc::unbounded_channel();
 
-        match MeshNetwork::new().await {
-            Ok(mesh) => {
-                 Self::emit(app, "PHASE_3_NETWORK", &format!("PeerID Generated: {}", mesh.swarm.local_peer_id()), 85);
-                 Ok((mesh, intent_tx, event_rx, intent_rx, event_tx))
-            }
+        let mut mesh = match MeshNetwork::new(&state.settings.get()).await {
+            Ok(mesh) => mesh,
             Err(e) => {
                 Self::emit(app, "PHASE_3_ERROR", &format!("Mesh Failed: {}", e), 0);
-                Err(e.to_string())
+                tracing::error!(error = %e, "mesh bootstrap failed");
+                state.lifecycle.set(Subsystem::Mesh, SubsystemStatus::Down(e.to_string()));
+                return;
             }
-        }
-    }
+        };
+        Self::emit(app, "PHASE_3_NETWORK", &format!("PeerID Generated: {}", mesh.swarm.local_peer_id()), 85);
+        mesh.relay_bytes = state.relay_bytes.clone();
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (intent_tx, intent_rx) = mpsc::unbounded_channel();
+
+        // Start Mesh Loop (Background)
+        let lifecycle = state.lifecycle.clone();
+        let swarm_task = tokio::spawn(async move {
+            if let Err(e) = mesh.start(event_tx, intent_rx).await {
+                tracing::error!(error = %e, "mesh network stopped");
+                lifecycle.set(Subsystem::Mesh, SubsystemStatus::Down(e.to_string()));
+            }
+        });
 
-    // Helper: Check internet
-    async fn check_connectivity() -> bool {
-        // Simple check
-        tokio::net::TcpStream::connect("8.8.8.8:53").await.is_ok()
+        // Forward Mesh Events to Frontend, handling listing catalogue
+        // exchange on the way.
+        let handle_clone = app.clone();
+        let mesh_state = state.clone();
+        let forward_task = tokio::spawn(async move {
+            let mut last_shared: Option<Instant> = None;
+            // Any peer can send intents with fresh bid proofs, so at most
+            // `proof_workers` are checked at once and the rest go unverified.
+            let verify_slots = Arc::new(Semaphore::new(mesh_state.settings.get().proof_workers.max(1)));
+            while let Some(event) = event_rx.recv().await {
+                // Proof verification can take seconds (Bulletproofs, `bb`); don't hold up other events for it.
+                if let MeshEvent::IntentReceived { intent, proof_verified: None } = &event {
+                    if let Some(bid) = intent.bid_proof.clone() {
+                        let Ok(permit) = verify_slots.clone().try_acquire_owned() else {
+                            tracing::warn!("too many bid proofs being verified, treating this one as unverified");
+                            let _ = handle_clone.emit("mesh-event", MeshEvent::IntentReceived { intent: intent.clone(), proof_verified: Some(false) });
+                            continue;
+                        };
+                        let (intent, zk_handler, app) = (intent.clone(), mesh_state.zk_handler.clone(), handle_clone.clone());
+                        tokio::spawn(async move {
+                            let _permit = permit;
+                            let verified = zk_handler.verify_bid(&bid, &intent.payload).await.unwrap_or_else(|e| {
+                                tracing::warn!(error = %e, "could not verify bid proof, treating it as unverified");
+                                false
+                            });
+                            let _ = app.emit("mesh-event", MeshEvent::IntentReceived { intent, proof_verified: Some(verified) });
+                        });
+                        continue;
+                    }
+                }
+                match &event {
+                    MeshEvent::CatalogueRequested
+                        if last_shared.is_none_or(|t| t.elapsed() >= CATALOGUE_SHARE_INTERVAL) =>
+                    {
+                        match mesh_state.share_catalogue() {
+                            Ok(true) => last_shared = Some(Instant::now()),
+                            Ok(false) => {}
+                            Err(e) => tracing::warn!(error = %e, "failed to share listing catalogue"),
+                        }
+                    }
+                    MeshEvent::CatalogueReceived { snapshot } => {
+                        match mesh_state.bridge.import_catalogue_snapshot(snapshot.clone(), &mesh_state.settings.get().catalogue_peers()) {
+                            Ok(true) => tracing::info!(signer = %snapshot.signer, "cached listing catalogue from peer"),
+                            Ok(false) => {}
+                            Err(e) => tracing::warn!(error = %e, signer = %snapshot.signer, "rejected listing catalogue"),
+                        }
+                    }
+                    MeshEvent::PeerDiscovered { .. } if mesh_state.bridge.connectivity.is_offline() => {
+                        if let Err(e) = mesh_state.request_catalogue() {
+                            tracing::debug!(error = %e, "could not request listing catalogue");
+                        }
+                    }
+                    _ => {}
+                }
+                let _ = handle_clone.emit("mesh-event", event);
+            }
+        });
+
+        state.mesh.attach(intent_tx, vec![swarm_task, forward_task]);
+        state.lifecycle.set(Subsystem::Mesh, SubsystemStatus::Up);
+        tracing::info!("system bootstrap complete, mesh swarm active");
     }
 
     // Helper: Emit UI Event
@@ -81,22 +260,51 @@ impl SystemBootstrap {
             message: msg.to_string(),
             progress,
         });
-        println!("[Bootstrap] [{}] {} ({}%)", phase, msg, progress);
+        tracing::info!(phase, progress, "{}", msg);
     }
 }
 
 // 5. Security: Kill Switch Command
 #[tauri::command]
-pub async fn kill_switch(state: State<'_, Arc<Mutex<AppState>>>) -> Result<String, String> {
-    let state = state.lock().await;
-    let bridge = state.bridge.lock().await;
-    
+pub async fn kill_switch(state: State<'_, AppState>) -> AppResult<String> {
     // Shred local key
-    bridge.delete_snapshot().map_err(|e| e.to_string())?;
-    
+    state.bridge.delete_snapshot()?;
+
     // Revoke Instant Session (Mock revocation logic since strict real implementation details are complex)
     // In real world: this would revoke the session key on-chain
-    
-    println!("🚨 KILL SWITCH ACTIVATED: Session Shredded.");
+
+    tracing::warn!("kill switch activated, session shredded");
     Ok("SESSION_TERMINATED".to_string())
 }
+
+#[tauri::command]
+pub async fn get_node_state(state: State<'_, AppState>) -> AppResult<NodeState> {
+    Ok(state.lifecycle.state())
+}
+
+#[tauri::command]
+pub async fn get_connectivity(state: State<'_, AppState>) -> AppResult<ConnectivityStatus> {
+    Ok(state.bridge.connectivity.status())
+}
+
+/// Tears down the current mesh swarm (if any) and boots a fresh one.
+#[tauri::command]
+pub async fn restart_mesh(state: State<'_, AppState>, app: AppHandle) -> AppResult<NodeState> {
+    SystemBootstrap::phase_3_network(&state, &app).await;
+    Ok(state.lifecycle.state())
+}
+
+/// Re-runs the Phase 1 balance sync against the RPC.
+#[tauri::command]
+pub async fn retry_bridge_sync(state: State<'_, AppState>, app: AppHandle) -> AppResult<NodeState> {
+    SystemBootstrap::phase_1_sync(&state, &app).await;
+    Ok(state.lifecycle.state())
+}
+
+/// Stops the Ollama process we started (if any) and starts it again.
+#[tauri::command]
+pub async fn restart_ollama(state: State<'_, AppState>) -> AppResult<NodeState> {
+    state.ollama.stop_service();
+    SystemBootstrap::start_ollama(&state).await;
+    Ok(state.lifecycle.state())
+}
@@ -0,0 +1,207 @@
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::agent::SharkAgent;
+use crate::blockchain_bridge::BlockchainBridge;
+use crate::circuit_registry::CircuitRegistry;
+use crate::error::{AppError, AppResult};
+use crate::lifecycle::{Lifecycle, Subsystem, SubsystemStatus};
+use crate::matcher::MatchAgent;
+use crate::mesh::PrivacyIntent;
+use crate::ollama_manager::OllamaManager;
+use crate::proof_jobs::ProofJobs;
+use crate::settings::SettingsStore;
+use crate::zk_handler::ZKHandler;
+
+/// Service handles shared by every Tauri command. There is no global lock:
+/// each handle is independently shareable and synchronises internally, so a
+/// slow Ollama generation or RPC round-trip in one command never blocks
+/// another (including `kill_switch`). Registered before bootstrap starts, so
+/// commands that need a subsystem that isn't up yet get a typed `not_ready`
+/// error from `lifecycle` instead of a missing-state panic.
+#[derive(Clone)]
+pub struct AppState {
+    pub settings: Arc<SettingsStore>,
+    pub lifecycle: Arc<Lifecycle>,
+    pub mesh: Arc<MeshHandle>,
+    pub agent: Arc<SharkAgent>,
+    pub matcher: Arc<MatchAgent>,
+    pub zk_handler: Arc<ZKHandler>,
+    pub proof_jobs: Arc<ProofJobs>,
+    pub ollama: Arc<OllamaManager>,
+    pub bridge: Arc<BlockchainBridge>,
+    /// Outlives any one mesh instance, so relay stats survive a mesh restart.
+    pub relay_bytes: Arc<AtomicU64>,
+}
+
+impl AppState {
+    pub fn new(settings: Arc<SettingsStore>, bridge: Arc<BlockchainBridge>, data_dir: &Path) -> Self {
+        let circuits = CircuitRegistry::load(data_dir.join("circuits"));
+        let zk_handler = Arc::new(ZKHandler::new(settings.clone(), bridge.clone(), circuits));
+        AppState {
+            lifecycle: Arc::new(Lifecycle::new()),
+            mesh: Arc::new(MeshHandle::default()),
+            agent: Arc::new(SharkAgent::new(settings.clone())),
+            matcher: Arc::new(MatchAgent::new(settings.clone())),
+            proof_jobs: Arc::new(ProofJobs::new(zk_handler.clone(), bridge.clone(), settings.get().proof_workers)),
+            zk_handler,
+            ollama: Arc::new(OllamaManager::new(settings.clone())),
+            settings,
+            bridge,
+            relay_bytes: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Queues an intent for the mesh swarm task to broadcast.
+    pub fn send_to_mesh(&self, intent: PrivacyIntent) -> AppResult<()> {
+        self.lifecycle.require(Subsystem::Mesh)?;
+        let not_running = || AppError::NotReady { subsystem: Subsystem::Mesh, reason: "mesh task stopped".to_string() };
+        let tx = self.mesh.sender().ok_or_else(not_running)?;
+        tx.send(intent).map_err(|_| {
+            self.lifecycle.set(Subsystem::Mesh, SubsystemStatus::Down("mesh task stopped".to_string()));
+            not_running()
+        })
+    }
+
+    /// Asks peers for a signed listing catalogue, for matching while the RPC
+    /// is unreachable.
+    pub fn request_catalogue(&self) -> AppResult<()> {
+        self.send_to_mesh(PrivacyIntent {
+            intent_type: "catalogue_request".to_string(),
+            payload: "{}".to_string(),
+            encrypted: false,
+            relay_path: vec!["origin_node".to_string()],
+            relay_fee: None,
+            bid_proof: None,
+        })
+    }
+
+    /// Broadcasts our signed listing catalogue, if we hold one fetched from
+    /// the chain ourselves. Returns whether anything was sent.
+    pub fn share_catalogue(&self) -> AppResult<bool> {
+        let Some(snapshot) = self.bridge.signed_catalogue()? else {
+            return Ok(false);
+        };
+        self.send_to_mesh(PrivacyIntent {
+            intent_type: "catalogue_snapshot".to_string(),
+            payload: serde_json::to_string(&snapshot)?,
+            encrypted: false,
+            relay_path: vec!["origin_node".to_string()],
+            relay_fee: None,
+            bid_proof: None,
+        })?;
+        Ok(true)
+    }
+}
+
+/// The currently running mesh swarm: its intent channel plus the background
+/// tasks driving it. Replaced wholesale when the mesh is restarted.
+#[derive(Default)]
+pub struct MeshHandle {
+    intent_tx: RwLock<Option<mpsc::UnboundedSender<PrivacyIntent>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl MeshHandle {
+    /// Installs a freshly started swarm, stopping any previous one first.
+    pub fn attach(&self, intent_tx: mpsc::UnboundedSender<PrivacyIntent>, tasks: Vec<JoinHandle<()>>) {
+        self.stop();
+        *self.intent_tx.write().unwrap_or_else(PoisonError::into_inner) = Some(intent_tx);
+        *self.tasks.lock().unwrap_or_else(PoisonError::into_inner) = tasks;
+    }
+
+    pub fn stop(&self) {
+        self.intent_tx.write().unwrap_or_else(PoisonError::into_inner).take();
+        for task in self.tasks.lock().unwrap_or_else(PoisonError::into_inner).drain(..) {
+            task.abort();
+        }
+    }
+
+    fn sender(&self) -> Option<mpsc::UnboundedSender<PrivacyIntent>> {
+        self.intent_tx.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain_bridge::AssetListingView;
+    use crate::rpc_pool::RpcPool;
+    use crate::settings::Settings;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+
+    /// With the model server and the RPC both hanging mid-request, other
+    /// commands (here: the kill switch's snapshot shred, content signing and
+    /// queue/health reads) must still complete immediately.
+    #[tokio::test]
+    async fn stalled_model_and_rpc_do_not_block_other_commands() {
+        // Accepts connections and never answers.
+        let stall = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stall_url = format!("http://{}", stall.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = stall.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let tmp_dir = std::env::temp_dir().join(format!("cabalmesh_state_test_{}", std::process::id()));
+        let bridge = BlockchainBridge::with_data_dir(
+            Arc::new(RpcPool::new(vec![stall_url.clone()], Default::default())),
+            tmp_dir.clone(),
+            None,
+            None,
+            None,
+        );
+        let settings = Arc::new(SettingsStore::load(&tmp_dir));
+        settings
+            .update(Settings { ollama_url: stall_url, ..settings.get() })
+            .unwrap();
+        let state = AppState::new(settings, Arc::new(bridge), &tmp_dir);
+
+        let listing = AssetListingView {
+            id: 1,
+            seller: "0x0000000000000000000000000000000000000001".to_string(),
+            description: "Desk lamp".to_string(),
+            price_wei: "1000000000000000".to_string(),
+            price_avax: "0.001".to_string(),
+            token_id: 1,
+        };
+        let matching = {
+            let state = state.clone();
+            tokio::spawn(async move { state.matcher.match_intent("a lamp", 1.0, &[listing]).await })
+        };
+        let syncing = {
+            let state = state.clone();
+            tokio::spawn(async move { state.bridge.sync_state("").await })
+        };
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!matching.is_finished() && !syncing.is_finished(), "both calls should be stuck on the stalled server");
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            state.bridge.delete_snapshot().unwrap();
+            state.bridge.sign_content("still responsive").unwrap();
+            state.bridge.get_pending_relay_txs();
+            state.bridge.get_rpc_health();
+            assert!(state.send_to_mesh(PrivacyIntent {
+                intent_type: "trade".to_string(),
+                payload: String::new(),
+                encrypted: false,
+                relay_path: vec![],
+                relay_fee: None,
+                bid_proof: None,
+            })
+            .is_err());
+        })
+        .await
+        .expect("other commands must not wait on the stalled model/RPC calls");
+
+        matching.abort();
+        syncing.abort();
+        std::fs::remove_dir_all(&tmp_dir).ok();
+    }
+}
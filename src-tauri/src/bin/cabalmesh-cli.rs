@@ -0,0 +1,3 @@
+fn main() -> std::process::ExitCode {
+    cabalmesh_lib::run_cli()
+}
@@ -1,18 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::error::Error;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
 use std::str::FromStr;
 use chrono::{DateTime, Utc};
 // Crypto Imports
 use alloy::{
-    eips::eip2718::Encodable2718,
+    consensus::{transaction::SignerRecoverable, Transaction as _, TxEnvelope},
+    eips::{eip2718::{Decodable2718, Encodable2718}, BlockId},
     network::{EthereumWallet, TransactionBuilder},
-    primitives::{keccak256, Address, Bytes, Signature, U256},
-    providers::{Provider, ProviderBuilder},
+    primitives::{keccak256, Address, Bytes, Signature, B256, U256},
     rpc::types::TransactionRequest,
     signers::{local::PrivateKeySigner, SignerSync},
     sol,
+    sol_types::SolCall,
 };
 use keyring::Entry;
 use aes_gcm::{
@@ -20,30 +21,53 @@ use aes_gcm::{
     Aes256Gcm, Nonce, Key
 };
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-use tokio::time::{timeout, Duration};
+
+use crate::chain_backend::{AccountProof, ChainBackend, ChainReceipt};
+use crate::catalogue::{CatalogueSource, ListingCatalogue, SignedCatalogue};
+use crate::connectivity::{Connectivity, PROBE_TIMEOUT};
+use crate::error::{AppError, AppResult};
+use crate::relay_claim::SignedRelayClaim;
+use crate::revert::{decode_revert, RevertReason};
+use crate::mock_chain::{MockChain, MOCK_ESCROW_ADDRESS, MOCK_MARKETPLACE_ADDRESS, MOCK_VOUCHER_ADDRESS};
+use crate::rpc_pool::{EndpointStatus, RpcPool, RpcPoolError, RpcTimeouts};
+use crate::sealed_intent::{encode_public_key, intent_secret_key, IntentOpening, SealedIntent};
+use crate::profile::default_data_dir;
+use crate::settings::Settings;
+use crate::zk_handler::{BidProof, ZKProof, ANCHOR_MAX_AGE_SECS, PROOF_TTL_SECS};
 
 const KEYCHAIN_SERVICE: &str = "com.cabalmesh.wallet";
 const KEYCHAIN_USER: &str = "snapshot-encryption-key";
 pub const DEFAULT_AVAX_RPC_URL: &str = "https://api.avax-test.network/ext/bc/C/rpc";
+/// How many times a "confirmed" claim for a tx our RPC has never seen is
+/// re-checked before it counts against the relayer. Our endpoint may just
+/// be behind.
+const MAX_UNSEEN_CLAIM_CHECKS: u32 = 3;
+/// A relayer with at least this many rejected claims, and more rejected
+/// than verified, has its further claims ignored.
+const DISTRUSTED_AFTER_REJECTIONS: u64 = 3;
 
 sol! {
-    #[sol(rpc)]
+    #[sol(rpc, all_derives)]
     IEscrow,
     "abi/Escrow.abi.json"
 }
 
 sol! {
-    #[sol(rpc)]
+    #[sol(rpc, all_derives)]
     IMarketplace,
     "abi/Marketplace.abi.json"
 }
 
 sol! {
-    #[sol(rpc)]
+    #[sol(rpc, all_derives)]
     IVoucher,
     "abi/CabalMeshVoucher.abi.json"
 }
 
+fn encode(call: impl SolCall) -> Bytes {
+    call.abi_encode().into()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdentityRecord {
     pub alias: String,
@@ -56,6 +80,8 @@ pub struct IdentityView {
     pub alias: String,
     pub emoji: String,
     pub address: String, // 0x-prefixed EVM address
+    /// Public key for intents sealed to this identity (compressed secp256k1, hex).
+    pub intent_key: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,11 +98,15 @@ pub struct Snapshot {
     pub timestamp: DateTime<Utc>,
     pub assets: Vec<CompressedAsset>,
     pub signature: String,
+    /// The native balance's account proof, which bid proofs are made from.
+    /// `None` when the RPC doesn't serve `eth_getProof`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<AccountProof>,
 }
 
 /// A Marketplace listing, always backed by a real CabalMeshVoucher tokenId
 /// the seller owns on-chain (enforced by the contract itself at list time).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AssetListingView {
     pub id: u64,
     pub seller: String,
@@ -97,7 +127,7 @@ pub struct VoucherView {
 
 /// A Marketplace deal (real on-chain state: Active/Released/Refunded) —
 /// the real "someone is transacting on this listing" signal.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DealView {
     pub deal_id: u64,
     pub buyer: String,
@@ -141,6 +171,25 @@ pub struct QueuedTx {
     pub created_at: DateTime<Utc>,
     pub status: String, // "queued" | "confirmed" | "failed"
     pub tx_hash: Option<String>,
+    /// Decoded revert reason from the last pre-flight simulation. The tx
+    /// stays queued: balances and deal states change, and a copy already
+    /// on the mesh may still be relayed. Only a receipt marks it failed.
+    #[serde(default, alias = "failure_reason")]
+    pub revert_warning: Option<String>,
+    /// Relay peers' "confirmed" claims our RPC couldn't see yet, one per
+    /// relayer, waiting to be re-checked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unseen_claims: Vec<UnseenClaim>,
+}
+
+/// A relayer's "confirmed" claim for a queued tx that the RPC hadn't seen
+/// when it was checked. It's re-checked on each pre-flight; only after
+/// `MAX_UNSEEN_CLAIM_CHECKS` misses is it rejected, and only that relayer
+/// is charged for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnseenClaim {
+    pub relayer: Option<String>,
+    pub checks: u32,
 }
 
 /// A transaction this node successfully relayed to the chain on behalf of
@@ -157,6 +206,37 @@ pub struct RelayedTxRecord {
     pub relayed_at: DateTime<Utc>,
 }
 
+/// How a relay peer's confirmations have held up against the chain. Every
+/// `relay_confirmed` claim is checked before it's trusted; claims that don't
+/// match what actually landed on-chain count against the peer that sent them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelayReputation {
+    pub verified: u64,
+    pub rejected: u64,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl RelayReputation {
+    pub fn is_distrusted(&self) -> bool {
+        self.rejected >= DISTRUSTED_AFTER_REJECTIONS && self.rejected > self.verified
+    }
+}
+
+/// What the chain says about a relay peer's claim for one of our queued txs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayVerdict {
+    /// Mined and succeeded — the queue entry is now `confirmed`.
+    Confirmed,
+    /// Mined but reverted — the queue entry is now `failed`.
+    Reverted,
+    /// Known to the RPC but not mined yet, nobody has submitted it yet, or
+    /// a claimed confirmation the RPC can't see yet and will re-check.
+    Pending,
+    /// The claim doesn't match our signed bytes or the chain — ignored.
+    Rejected,
+}
+
 /// Result of an action that normally hits the chain directly: either it went
 /// through immediately (`Confirmed`), or the RPC was unreachable and it was
 /// signed offline and queued for mesh relay instead (`Queued`).
@@ -180,8 +260,12 @@ pub struct InstantSession {
     pub is_active: bool,
 }
 
+/// Shared by every command as `Arc<BlockchainBridge>` — all methods take
+/// `&self`. The in-memory state sits behind short-lived sync locks that are
+/// never held across an `.await`, so a slow RPC call in one command can't
+/// stall another.
 pub struct BlockchainBridge {
-    pub identities: Vec<IdentityRecord>,
+    pub identities: RwLock<Vec<IdentityRecord>>,
     pub identity_path: PathBuf,
     pub storage_path: PathBuf,
     pub chain_cache_path: PathBuf,
@@ -189,42 +273,69 @@ pub struct BlockchainBridge {
     pub relayed_history_path: PathBuf,
     pub content_store_path: PathBuf,
     pub received_content_path: PathBuf,
-    pub rpc_url: String,
+    pub relay_reputation_path: PathBuf,
+    pub listing_catalogue_path: PathBuf,
+    /// The real RPC pool, or the in-memory `MockChain` in demo mode and tests.
+    pub chain: Arc<dyn ChainBackend>,
     pub escrow_address: Option<Address>,
     pub marketplace_address: Option<Address>,
     pub voucher_address: Option<Address>,
-    pub current_session: Option<InstantSession>,
+    pub current_session: RwLock<Option<InstantSession>>,
+    /// Serialises read-modify-write cycles on the JSON stores below (relay
+    /// queue, chain cache, reputation, history, content) between concurrent commands.
+    pub store_lock: Mutex<()>,
+    /// Keychain account for the snapshot key, namespaced per data dir so
+    /// side-by-side profiles never share (or overwrite) each other's key.
+    pub keychain_user: String,
+    /// Whether the RPC is reachable, kept current by the connectivity
+    /// monitor; writes with an offline fallback skip straight to it while
+    /// this reports an outage.
+    pub connectivity: Connectivity,
 }
 
 impl BlockchainBridge {
-    pub fn new(rpc_url_override: Option<String>) -> Self {
-        let rpc_url = rpc_url_override.unwrap_or_else(|| DEFAULT_AVAX_RPC_URL.to_string());
-
-        // No fallback here: an absent/invalid address should surface as a clear
+    pub fn new(settings: &Settings, data_dir: PathBuf) -> Self {
+        if settings.demo_chain {
+            tracing::warn!("demo mode: using an in-memory simulated chain, nothing touches Avalanche");
+            return Self::with_data_dir(
+                Arc::new(MockChain::demo()),
+                data_dir,
+                Some(MOCK_ESCROW_ADDRESS),
+                Some(MOCK_MARKETPLACE_ADDRESS),
+                Some(MOCK_VOUCHER_ADDRESS),
+            );
+        }
+        // No fallback here: an absent address should surface as a clear
         // runtime error the first time a contract call is attempted, not a
         // silently-wrong placeholder.
-        let escrow_address = std::env::var("ESCROW_CONTRACT_ADDRESS")
-            .ok()
-            .filter(|s| !s.is_empty())
-            .and_then(|s| Address::from_str(&s).ok());
-
-        let marketplace_address = std::env::var("MARKETPLACE_CONTRACT_ADDRESS")
-            .ok()
-            .filter(|s| !s.is_empty())
-            .and_then(|s| Address::from_str(&s).ok());
-
-        let voucher_address = std::env::var("VOUCHER_CONTRACT_ADDRESS")
-            .ok()
-            .filter(|s| !s.is_empty())
-            .and_then(|s| Address::from_str(&s).ok());
+        Self::with_data_dir(
+            Arc::new(RpcPool::new(settings.rpc_urls.clone(), RpcTimeouts::from_env())),
+            data_dir,
+            settings.escrow_address(),
+            settings.marketplace_address(),
+            settings.voucher_address(),
+        )
+    }
 
-        let app_dir = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("cabalmesh");
+    /// Opens (or initialises) the bridge's stores under `app_dir`.
+    pub fn with_data_dir(
+        chain: Arc<dyn ChainBackend>,
+        app_dir: PathBuf,
+        escrow_address: Option<Address>,
+        marketplace_address: Option<Address>,
+        voucher_address: Option<Address>,
+    ) -> Self {
         let _ = fs::create_dir_all(&app_dir);
+        // The default data dir keeps the original account name so existing
+        // installs still find their key.
+        let keychain_user = if app_dir == default_data_dir() {
+            KEYCHAIN_USER.to_string()
+        } else {
+            format!("{}:{}", KEYCHAIN_USER, app_dir.display())
+        };
 
-        let mut bridge = Self {
-            identities: Vec::new(),
+        let bridge = Self {
+            identities: RwLock::new(Vec::new()),
             identity_path: app_dir.join("identities.json"),
             storage_path: app_dir.join("snapshot.enc"),
             chain_cache_path: app_dir.join("chain_cache.json"),
@@ -232,36 +343,47 @@ impl BlockchainBridge {
             relayed_history_path: app_dir.join("relayed_history.json"),
             content_store_path: app_dir.join("content_store.json"),
             received_content_path: app_dir.join("received_content.json"),
-            rpc_url,
+            relay_reputation_path: app_dir.join("relay_reputation.json"),
+            listing_catalogue_path: app_dir.join("listing_catalogue.json"),
+            chain,
             escrow_address,
             marketplace_address,
             voucher_address,
-            current_session: None,
+            current_session: RwLock::new(None),
+            store_lock: Mutex::new(()),
+            keychain_user,
+            connectivity: Connectivity::new(),
         };
         let _ = bridge.load_identities();
         bridge
     }
 
-    fn save_identities(&self) -> Result<(), Box<dyn Error>> {
-        fs::write(&self.identity_path, serde_json::to_string_pretty(&self.identities)?)?;
+    fn save_identities(&self, identities: &[IdentityRecord]) -> AppResult<()> {
+        fs::write(&self.identity_path, serde_json::to_string_pretty(identities)?)?;
         Ok(())
     }
 
-    pub fn load_identities(&mut self) -> Result<Vec<IdentityView>, Box<dyn Error>> {
+    /// Exclusive access to the JSON stores for one read-modify-write cycle.
+    fn store(&self) -> MutexGuard<'_, ()> {
+        self.store_lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn load_identities(&self) -> AppResult<Vec<IdentityView>> {
         if self.identity_path.exists() {
-            println!("🔑 Loading Identities from {:?}", self.identity_path);
+            tracing::debug!(path = %self.identity_path.display(), "loading identities");
             let content = fs::read_to_string(&self.identity_path)?;
 
             match serde_json::from_str::<Vec<IdentityRecord>>(&content) {
                 Ok(records) => {
-                    self.identities = records;
-                    if self.identities.is_empty() {
+                    let empty = records.is_empty();
+                    *self.identities.write().unwrap_or_else(PoisonError::into_inner) = records;
+                    if empty {
                         return self.generate_new_identity("Primary Fox".to_string(), "🦊".to_string());
                     }
                     self.get_identity_views()
                 }
                 Err(_) => {
-                    println!("⚠️ Failed to parse identity list, creating new...");
+                    tracing::warn!(path = %self.identity_path.display(), "identity list unreadable, creating a new identity");
                     return self.generate_new_identity("Glitch Fox".to_string(), "👾".to_string());
                 }
             }
@@ -270,30 +392,34 @@ impl BlockchainBridge {
         }
     }
 
-    pub fn generate_new_identity(&mut self, alias: String, emoji: String) -> Result<Vec<IdentityView>, Box<dyn Error>> {
-        println!("🆕 Generating NEW Identity '{}' [{}]...", alias, emoji);
+    pub fn generate_new_identity(&self, alias: String, emoji: String) -> AppResult<Vec<IdentityView>> {
+        tracing::info!(%alias, "generating new identity");
         let signer = PrivateKeySigner::random();
         let private_key_hex = format!("0x{}", hex::encode(signer.to_bytes()));
-        self.identities.push(IdentityRecord { alias, emoji, private_key_hex });
-        self.save_identities()?;
+        {
+            let mut identities = self.identities.write().unwrap_or_else(PoisonError::into_inner);
+            identities.push(IdentityRecord { alias, emoji, private_key_hex });
+            self.save_identities(&identities)?;
+        }
         self.get_identity_views()
     }
 
-    pub fn get_identity_views(&self) -> Result<Vec<IdentityView>, Box<dyn Error>> {
+    pub fn get_identity_views(&self) -> AppResult<Vec<IdentityView>> {
         let mut views = Vec::new();
-        for id in &self.identities {
-            let signer = PrivateKeySigner::from_str(&id.private_key_hex)?;
+        for id in self.identities.read().unwrap_or_else(PoisonError::into_inner).iter() {
+            let signer = PrivateKeySigner::from_str(&id.private_key_hex).map_err(AppError::crypto)?;
             views.push(IdentityView {
                 alias: id.alias.clone(),
                 emoji: id.emoji.clone(),
                 address: signer.address().to_string(),
+                intent_key: encode_public_key(&intent_secret_key(&signer.credential().into())?.public_key()),
             });
         }
         Ok(views)
     }
 
     pub fn get_primary_address(&self) -> String {
-        match self.identities.first() {
+        match self.identities.read().unwrap_or_else(PoisonError::into_inner).first() {
             Some(first) => match PrivateKeySigner::from_str(&first.private_key_hex) {
                 Ok(signer) => signer.address().to_string(),
                 Err(_) => "unknown".to_string(),
@@ -302,9 +428,10 @@ impl BlockchainBridge {
         }
     }
 
-    fn primary_signer(&self) -> Result<PrivateKeySigner, Box<dyn Error>> {
-        let first = self.identities.first().ok_or("No identity available")?;
-        Ok(PrivateKeySigner::from_str(&first.private_key_hex)?)
+    fn primary_signer(&self) -> AppResult<PrivateKeySigner> {
+        let identities = self.identities.read().unwrap_or_else(PoisonError::into_inner);
+        let first = identities.first().ok_or(AppError::NoIdentity)?;
+        PrivateKeySigner::from_str(&first.private_key_hex).map_err(AppError::crypto)
     }
 
     // ---- Offline signing + mesh-relay queue -------------------------------
@@ -314,7 +441,7 @@ impl BlockchainBridge {
         serde_json::from_str(&content).ok()
     }
 
-    fn save_chain_cache(&self, cache: &ChainStateCache) -> Result<(), Box<dyn Error>> {
+    fn save_chain_cache(&self, cache: &ChainStateCache) -> AppResult<()> {
         fs::write(&self.chain_cache_path, serde_json::to_string_pretty(cache)?)?;
         Ok(())
     }
@@ -326,7 +453,7 @@ impl BlockchainBridge {
             .unwrap_or_default()
     }
 
-    fn save_pending_relay_txs(&self, txs: &[QueuedTx]) -> Result<(), Box<dyn Error>> {
+    fn save_pending_relay_txs(&self, txs: &[QueuedTx]) -> AppResult<()> {
         fs::write(&self.pending_relay_path, serde_json::to_string_pretty(txs)?)?;
         Ok(())
     }
@@ -334,11 +461,11 @@ impl BlockchainBridge {
     /// Refreshes the cached nonce + gas price snapshot from the live RPC.
     /// Called opportunistically whenever we know we're online (piggybacks on
     /// `sync_state`) so a later offline attempt has something recent to sign with.
-    async fn refresh_chain_cache(&self, address: Address) -> Result<(), Box<dyn Error>> {
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let nonce = provider.get_transaction_count(address).pending().await?;
-        let gas_price = provider.get_gas_price().await?;
+    async fn refresh_chain_cache(&self, address: Address) -> AppResult<()> {
+        let nonce = self.chain.pending_nonce(address).await?;
+        let gas_price = self.chain.gas_price().await?;
 
+        let _store = self.store();
         self.save_chain_cache(&ChainStateCache {
             nonce,
             gas_price_wei: gas_price.to_string(),
@@ -351,8 +478,19 @@ impl BlockchainBridge {
     /// the cached nonce so a second queued call doesn't collide), and queues
     /// the raw signed bytes for a mesh peer with connectivity to relay.
     /// The private key never leaves this function — only the signed bytes do.
-    async fn sign_offline(&self, to: Address, calldata: Bytes, value: U256, summary: &str) -> Result<QueuedTx, Box<dyn Error>> {
-        let cache = self.load_chain_cache().ok_or("No cached chain state available — never been online yet")?;
+    async fn sign_offline(&self, to: Address, calldata: Bytes, value: U256, summary: &str) -> AppResult<QueuedTx> {
+        // Reserve the cached nonce and bump it in one step, so two concurrent
+        // offline calls never sign with the same nonce.
+        let cache = {
+            let _store = self.store();
+            let cache = self.load_chain_cache().ok_or_else(|| AppError::NotFound("cached chain state — never been online yet".to_string()))?;
+            self.save_chain_cache(&ChainStateCache {
+                nonce: cache.nonce + 1,
+                gas_price_wei: cache.gas_price_wei.clone(),
+                cached_at: cache.cached_at,
+            })?;
+            cache
+        };
         let signer = self.primary_signer()?;
         let wallet = EthereumWallet::from(signer);
 
@@ -370,18 +508,10 @@ impl BlockchainBridge {
             .with_max_fee_per_gas(buffered_gas_price)
             .with_max_priority_fee_per_gas(buffered_gas_price);
 
-        let envelope = tx.build(&wallet).await?;
+        let envelope = tx.build(&wallet).await.map_err(AppError::crypto)?;
         let raw_bytes = envelope.encoded_2718();
         let raw_tx_hex = format!("0x{}", hex::encode(&raw_bytes));
 
-        // Bump the cached nonce immediately so a second offline call (e.g. a
-        // second queued intent search) signs with the next nonce, not this one.
-        self.save_chain_cache(&ChainStateCache {
-            nonce: cache.nonce + 1,
-            gas_price_wei: cache.gas_price_wei,
-            cached_at: cache.cached_at,
-        })?;
-
         let mut suffix = [0u8; 4];
         OsRng.fill_bytes(&mut suffix);
         let id = format!("tx-{}-{}", Utc::now().timestamp_millis(), hex::encode(suffix));
@@ -393,36 +523,104 @@ impl BlockchainBridge {
             created_at: Utc::now(),
             status: "queued".to_string(),
             tx_hash: None,
+            revert_warning: None,
+            unseen_claims: Vec::new(),
         };
 
-        let mut pending = self.load_pending_relay_txs();
-        pending.push(queued.clone());
-        self.save_pending_relay_txs(&pending)?;
+        {
+            let _store = self.store();
+            let mut pending = self.load_pending_relay_txs();
+            pending.push(queued.clone());
+            self.save_pending_relay_txs(&pending)?;
+        }
 
-        println!("📡 [Bridge] Signed offline, queued for mesh relay: {} ({})", queued.id, summary);
+        tracing::info!(queue_id = %queued.id, %summary, "signed offline, queued for mesh relay");
         Ok(queued)
     }
 
     /// Broadcasts a raw signed transaction someone else queued while offline.
     /// Used by a peer with real connectivity and Relay Mode on.
-    pub async fn submit_raw_transaction(&self, raw_tx_hex: &str) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip_all, err)]
+    pub async fn submit_raw_transaction(&self, raw_tx_hex: &str) -> AppResult<String> {
         let hex_str = raw_tx_hex.trim_start_matches("0x");
         let raw_bytes = hex::decode(hex_str)?;
 
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let receipt = provider.send_raw_transaction(&raw_bytes).await?.get_receipt().await?;
+        // Don't burn the originator's nonce (or our relay bandwidth) on a
+        // transaction the chain will reject anyway.
+        self.simulate_raw(&raw_bytes).await?;
+
+        let receipt = self.chain.send_raw(raw_bytes.into()).await?;
 
-        println!("✅ [Bridge] Relayed transaction confirmed. Tx: {:?}", receipt.transaction_hash);
+        tracing::info!(tx = ?receipt.transaction_hash, "relayed transaction confirmed");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
+    /// Simulates a raw signed transaction as its recovered sender would
+    /// execute it right now.
+    async fn simulate_raw(&self, raw_bytes: &[u8]) -> AppResult<()> {
+        let envelope = TxEnvelope::decode_2718(&mut &raw_bytes[..])
+            .map_err(|e| AppError::InvalidInput(format!("undecodable raw transaction: {}", e)))?;
+        let from = envelope
+            .recover_signer()
+            .map_err(|e| AppError::InvalidInput(format!("unrecoverable signature: {}", e)))?;
+        let to = envelope
+            .to()
+            .ok_or_else(|| AppError::invalid_input("contract creation is never relayed"))?;
+
+        self.simulate("relayed transaction", from, to, envelope.input(), envelope.value()).await
+    }
+
+    /// Re-simulates every still-queued offline transaction against current
+    /// chain state and records the decoded reason on the ones that would
+    /// revert right now, so the user sees why before a relay picks them up.
+    /// They stay queued and are simulated again next time; a passing run
+    /// clears the warning. Unseen relay claims are re-checked first. Stops
+    /// quietly if no endpoint is reachable. Returns how many would revert.
+    #[tracing::instrument(skip_all)]
+    pub async fn preflight_queued_txs(&self) -> AppResult<usize> {
+        self.recheck_unseen_claims().await?;
+        let queued: Vec<QueuedTx> = self.load_pending_relay_txs().into_iter().filter(|t| t.status == "queued").collect();
+        let mut verdicts = Vec::new();
+
+        // Simulate without holding the store lock; apply the verdicts afterwards
+        // so entries queued or relayed meanwhile aren't overwritten.
+        for entry in &queued {
+            let raw_bytes = hex::decode(entry.raw_tx_hex.trim_start_matches("0x"))?;
+            match self.simulate_raw(&raw_bytes).await {
+                Ok(()) => verdicts.push((entry.id.clone(), None)),
+                Err(AppError::Reverted { reason, .. }) => {
+                    tracing::warn!(queue_id = %entry.id, %reason, "queued transaction would revert for now");
+                    verdicts.push((entry.id.clone(), Some(reason.to_string())));
+                }
+                Err(AppError::RpcUnreachable(_)) => break,
+                Err(e) => tracing::warn!(queue_id = %entry.id, error = %e, "could not pre-flight queued transaction"),
+            }
+        }
+
+        if verdicts.is_empty() {
+            return Ok(0);
+        }
+        let _store = self.store();
+        let mut pending = self.load_pending_relay_txs();
+        let mut would_revert = 0;
+        for entry in pending.iter_mut().filter(|t| t.status == "queued") {
+            if let Some((_, warning)) = verdicts.iter().find(|(id, _)| *id == entry.id) {
+                would_revert += usize::from(warning.is_some());
+                entry.revert_warning = warning.clone();
+            }
+        }
+        self.save_pending_relay_txs(&pending)?;
+        Ok(would_revert)
+    }
+
     pub fn get_pending_relay_txs(&self) -> Vec<QueuedTx> {
         self.load_pending_relay_txs()
     }
 
     /// Real, persisted credit for helping other peers: every transaction this
     /// node successfully relayed to the chain on someone else's behalf.
-    pub fn record_relayed_tx(&self, summary: &str, tx_hash: &str, reward_avax: &str) -> Result<(), Box<dyn Error>> {
+    pub fn record_relayed_tx(&self, summary: &str, tx_hash: &str, reward_avax: &str) -> AppResult<()> {
+        let _store = self.store();
         let mut history = self.load_relayed_history();
         history.push(RelayedTxRecord {
             summary: summary.to_string(),
@@ -444,38 +642,217 @@ impl BlockchainBridge {
             .unwrap_or_default()
     }
 
-    fn save_relayed_history(&self, history: &[RelayedTxRecord]) -> Result<(), Box<dyn Error>> {
+    fn save_relayed_history(&self, history: &[RelayedTxRecord]) -> AppResult<()> {
         fs::write(&self.relayed_history_path, serde_json::to_string_pretty(history)?)?;
         Ok(())
     }
 
-    pub fn mark_relay_tx_status(&self, id: &str, status: &str, tx_hash: Option<String>) -> Result<(), Box<dyn Error>> {
+    fn mark_relay_tx_status(&self, id: &str, status: &str, tx_hash: Option<String>) -> AppResult<()> {
+        let _store = self.store();
         let mut pending = self.load_pending_relay_txs();
         if let Some(entry) = pending.iter_mut().find(|t| t.id == id) {
             entry.status = status.to_string();
             entry.tx_hash = tx_hash;
+            entry.unseen_claims.clear();
+        }
+        self.save_pending_relay_txs(&pending)?;
+        Ok(())
+    }
+
+    fn load_relay_reputation(&self) -> std::collections::HashMap<String, RelayReputation> {
+        fs::read_to_string(&self.relay_reputation_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_relay_reputation(&self, scores: &std::collections::HashMap<String, RelayReputation>) -> AppResult<()> {
+        fs::write(&self.relay_reputation_path, serde_json::to_string_pretty(scores)?)?;
+        Ok(())
+    }
+
+    fn score_relayer(&self, relayer: Option<&str>, verdict: RelayVerdict) -> AppResult<()> {
+        let Some(relayer) = relayer.filter(|r| !r.is_empty()) else {
+            return Ok(());
+        };
+        let _store = self.store();
+        let mut scores = self.load_relay_reputation();
+        let entry = scores.entry(relayer.to_string()).or_default();
+        match verdict {
+            RelayVerdict::Confirmed => entry.verified += 1,
+            RelayVerdict::Rejected => entry.rejected += 1,
+            RelayVerdict::Reverted | RelayVerdict::Pending => {}
         }
+        entry.last_seen = Some(Utc::now());
+        self.save_relay_reputation(&scores)
+    }
+
+    pub fn get_relay_reputation(&self) -> std::collections::HashMap<String, RelayReputation> {
+        self.load_relay_reputation()
+    }
+
+    /// Checks a peer's `relay_confirmed` claim against the chain before the
+    /// queue entry is touched. The tx hash is recomputed from our own queued
+    /// signed bytes, so a claimed hash that doesn't match is forged outright;
+    /// otherwise the receipt decides the outcome, never the peer's `status`.
+    /// Claims from a relayer whose record `is_distrusted` are ignored.
+    /// Returns the (possibly updated) queue entry.
+    #[tracing::instrument(skip_all, fields(queue_id = %queue_id), err)]
+    pub async fn verify_relay_confirmation(
+        &self,
+        queue_id: &str,
+        claimed_status: &str,
+        claimed_tx_hash: Option<&str>,
+        relayer: Option<&str>,
+    ) -> AppResult<QueuedTx> {
+        let distrusted = relayer.and_then(|r| self.load_relay_reputation().remove(r)).is_some_and(|r| r.is_distrusted());
+        if distrusted {
+            tracing::warn!(%queue_id, ?relayer, "ignoring relay confirmation from a distrusted relayer");
+            return self.queued_tx(queue_id);
+        }
+        self.judge_relay_claim(queue_id, claimed_status, claimed_tx_hash, relayer).await
+    }
+
+    fn queued_tx(&self, queue_id: &str) -> AppResult<QueuedTx> {
+        self.load_pending_relay_txs()
+            .into_iter()
+            .find(|t| t.id == queue_id)
+            .ok_or_else(|| AppError::NotFound(format!("relay queue entry {}", queue_id)))
+    }
+
+    async fn judge_relay_claim(
+        &self,
+        queue_id: &str,
+        claimed_status: &str,
+        claimed_tx_hash: Option<&str>,
+        relayer: Option<&str>,
+    ) -> AppResult<QueuedTx> {
+        let entry = self.queued_tx(queue_id)?;
+
+        let raw_bytes = hex::decode(entry.raw_tx_hex.trim_start_matches("0x"))?;
+        let expected_hash = keccak256(&raw_bytes);
+
+        let verdict = match claimed_tx_hash.map(B256::from_str) {
+            Some(Ok(claimed)) if claimed != expected_hash => RelayVerdict::Rejected,
+            Some(Err(_)) => RelayVerdict::Rejected,
+            _ => match self.check_relayed_tx_on_chain(expected_hash, &raw_bytes).await? {
+                Some(verdict) => verdict,
+                // An honest "couldn't relay it" leaves the entry queued for
+                // another peer; a "confirmed" our RPC can't see yet is held
+                // for re-checking rather than judged on one lookup.
+                None if claimed_status == "confirmed" => self.note_unseen_claim(queue_id, relayer)?,
+                None => RelayVerdict::Pending,
+            },
+        };
+
+        match verdict {
+            RelayVerdict::Confirmed => self.mark_relay_tx_status(queue_id, "confirmed", Some(expected_hash.to_string()))?,
+            RelayVerdict::Reverted => self.mark_relay_tx_status(queue_id, "failed", Some(expected_hash.to_string()))?,
+            RelayVerdict::Pending => {}
+            RelayVerdict::Rejected => {
+                tracing::warn!(%queue_id, ?relayer, "rejected relay confirmation");
+            }
+        }
+        self.score_relayer(relayer, verdict)?;
+        self.queued_tx(queue_id)
+    }
+
+    /// `None` if the RPC has never seen the tx.
+    async fn check_relayed_tx_on_chain(&self, tx_hash: B256, raw_bytes: &[u8]) -> AppResult<Option<RelayVerdict>> {
+        let Some(tx_bytes) = self.chain.raw_transaction(tx_hash).await? else {
+            return Ok(None);
+        };
+        if tx_bytes.as_ref() != raw_bytes {
+            return Ok(Some(RelayVerdict::Rejected));
+        }
+
+        Ok(Some(match self.chain.receipt_status(tx_hash).await? {
+            Some(true) => RelayVerdict::Confirmed,
+            Some(false) => RelayVerdict::Reverted,
+            None => RelayVerdict::Pending,
+        }))
+    }
+
+    /// Counts one more miss for `relayer`'s "confirmed" claim the RPC can't
+    /// see; other relayers' claims for the same tx are counted separately.
+    /// The claim stays `Pending` until it has missed
+    /// `MAX_UNSEEN_CLAIM_CHECKS` times, then it's `Rejected` and dropped.
+    fn note_unseen_claim(&self, queue_id: &str, relayer: Option<&str>) -> AppResult<RelayVerdict> {
+        let _store = self.store();
+        let mut pending = self.load_pending_relay_txs();
+        let Some(entry) = pending.iter_mut().find(|t| t.id == queue_id) else {
+            return Ok(RelayVerdict::Pending);
+        };
+        let index = match entry.unseen_claims.iter().position(|c| c.relayer.as_deref() == relayer) {
+            Some(index) => index,
+            None => {
+                entry.unseen_claims.push(UnseenClaim { relayer: relayer.map(str::to_string), checks: 0 });
+                entry.unseen_claims.len() - 1
+            }
+        };
+        let claim = &mut entry.unseen_claims[index];
+        claim.checks += 1;
+        let verdict = if claim.checks >= MAX_UNSEEN_CLAIM_CHECKS {
+            entry.unseen_claims.remove(index);
+            RelayVerdict::Rejected
+        } else {
+            tracing::info!(%queue_id, checks = claim.checks, "relay claim not on chain yet, will re-check");
+            RelayVerdict::Pending
+        };
         self.save_pending_relay_txs(&pending)?;
+        Ok(verdict)
+    }
+
+    /// Re-checks every unseen "confirmed" claim on a queued tx, on behalf
+    /// of the relayer that made it.
+    async fn recheck_unseen_claims(&self) -> AppResult<()> {
+        let claimed: Vec<(String, Option<String>)> = self
+            .load_pending_relay_txs()
+            .into_iter()
+            .filter(|t| t.status == "queued")
+            .flat_map(|t| t.unseen_claims.into_iter().map(move |claim| (t.id.clone(), claim.relayer)))
+            .collect();
+        for (queue_id, relayer) in claimed {
+            match self.judge_relay_claim(&queue_id, "confirmed", None, relayer.as_deref()).await {
+                Ok(_) => {}
+                Err(AppError::RpcUnreachable(_)) => break,
+                Err(e) => tracing::warn!(%queue_id, error = %e, "could not re-check relay claim"),
+            }
+        }
         Ok(())
     }
 
     /// Syncs the native AVAX balance for the primary identity and saves an encrypted snapshot.
-    pub async fn sync_state(&self, wallet_address_override: &str) -> Result<Snapshot, Box<dyn Error>> {
+    #[tracing::instrument(skip_all, err)]
+    pub async fn sync_state(&self, wallet_address_override: &str) -> AppResult<Snapshot> {
         let primary = self.get_primary_address();
         let target = if primary != "unknown" { primary } else { wallet_address_override.to_string() };
         let address = Address::from_str(&target)?;
 
-        println!("🔄 [Bridge] Fetching native AVAX balance from {}", self.rpc_url);
+        tracing::debug!(rpc = %self.chain.primary_url(), "fetching native AVAX balance");
 
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let balance_wei: U256 = provider.get_balance(address).await?;
+        let (balance_wei, anchor) = match self.chain.account_proof(address).await {
+            Ok(proof) => {
+                proof.verify().map_err(|e| AppError::RpcRejected(format!("invalid account proof from the RPC: {}", e)))?;
+                (proof.balance, Some(proof))
+            }
+            // Not every RPC serves eth_getProof; sync anyway, just without an anchor.
+            Err(RpcPoolError::Rejected { message, .. }) => {
+                tracing::warn!(error = %message, "RPC can't prove the balance; snapshot is unanchored");
+                (self.chain.balance(address).await?, None)
+            }
+            Err(e) => return Err(e.into()),
+        };
 
-        println!("✅ [Bridge] Fetched balance for {}", target);
+        tracing::info!(address = %target, "fetched balance");
 
         // Best-effort: refresh the offline-signing cache while we know we're online.
         // Never let this fail the whole sync if the RPC is flaky for just this call.
         if let Err(e) = self.refresh_chain_cache(address).await {
-            eprintln!("⚠️  Failed to refresh chain state cache: {}", e);
+            tracing::warn!(error = %e, "failed to refresh chain state cache");
+        }
+        if let Err(e) = self.preflight_queued_txs().await {
+            tracing::warn!(error = %e, "failed to pre-flight queued transactions");
         }
 
         let snapshot = Snapshot {
@@ -488,6 +865,7 @@ impl BlockchainBridge {
                 proof: None,
             }],
             signature: "verified_by_avalanche_rpc".to_string(),
+            anchor,
         };
 
         self.save_snapshot_encrypted(&snapshot)?;
@@ -495,17 +873,64 @@ impl BlockchainBridge {
         Ok(snapshot)
     }
 
-    fn get_snapshot_key(&self) -> Result<Key<Aes256Gcm>, Box<dyn Error>> {
-        let entry = Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER)?;
+    /// The primary account's balance proof to make a bid proof from: the
+    /// latest snapshot's anchor, or a fresh `sync_state`'s if that one is
+    /// missing, for another account, or too old to stay acceptable for a
+    /// proof's whole lifetime.
+    pub async fn anchored_balance(&self) -> AppResult<AccountProof> {
+        let primary = self.primary_signer()?.address();
+        let mut anchor = self.get_latest_snapshot().ok().and_then(|s| s.anchor).filter(|a| a.address == primary);
+        if let Some(existing) = &anchor {
+            let age = self.block_age(existing.block_hash).await?;
+            if age.is_none_or(|age| age + PROOF_TTL_SECS > ANCHOR_MAX_AGE_SECS) {
+                anchor = None;
+            }
+        }
+        let anchor = match anchor {
+            Some(anchor) => anchor,
+            None => self.sync_state("").await?.anchor.ok_or_else(|| {
+                AppError::RpcRejected("the RPC doesn't serve eth_getProof, so the balance can't be anchored".to_string())
+            })?,
+        };
+        anchor.verify().map_err(AppError::crypto)?;
+        Ok(anchor)
+    }
+
+    /// `address`'s account at the block with `block_hash`, with its proof
+    /// checked against that block's state root; `None` if the chain doesn't
+    /// know the block.
+    pub async fn account_proof_at(&self, address: Address, block_hash: B256) -> AppResult<Option<AccountProof>> {
+        let Some(proof) = self.chain.account_proof_at(address, block_hash).await? else {
+            return Ok(None);
+        };
+        if proof.address != address || proof.block_hash != block_hash {
+            return Err(AppError::RpcRejected("the RPC proved a different account or block".to_string()));
+        }
+        proof.verify().map_err(|e| AppError::RpcRejected(format!("invalid account proof from the RPC: {}", e)))?;
+        Ok(Some(proof))
+    }
+
+    /// Seconds from the block with `block_hash` to the chain head, on the
+    /// chain's own clock; `None` if the chain doesn't know that block.
+    pub async fn block_age(&self, block_hash: B256) -> AppResult<Option<u64>> {
+        let Some(timestamp) = self.chain.block_timestamp(BlockId::hash(block_hash)).await? else {
+            return Ok(None);
+        };
+        let head = self.chain.block_timestamp(BlockId::latest()).await?.unwrap_or(timestamp);
+        Ok(Some(head.saturating_sub(timestamp)))
+    }
+
+    fn get_snapshot_key(&self) -> AppResult<Key<Aes256Gcm>> {
+        let entry = Entry::new(KEYCHAIN_SERVICE, &self.keychain_user)?;
 
         match entry.get_password() {
             Ok(pass) => {
-                let bytes = BASE64.decode(pass)?;
-                if bytes.len() != 32 { return Err("Invalid key length in keychain".into()); }
+                let bytes = BASE64.decode(pass).map_err(AppError::crypto)?;
+                if bytes.len() != 32 { return Err(AppError::crypto("invalid key length in keychain")); }
                 Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
             }
             Err(_) => {
-                println!("🔐 Generating new encryption key in Keychain...");
+                tracing::info!("generating new snapshot encryption key in keychain");
                 let mut key_bytes = [0u8; 32];
                 OsRng.fill_bytes(&mut key_bytes);
                 let encoded = BASE64.encode(key_bytes);
@@ -515,7 +940,7 @@ impl BlockchainBridge {
         }
     }
 
-    fn save_snapshot_encrypted(&self, snapshot: &Snapshot) -> Result<(), Box<dyn Error>> {
+    fn save_snapshot_encrypted(&self, snapshot: &Snapshot) -> AppResult<()> {
         let json = serde_json::to_vec(&snapshot)?;
 
         let key = self.get_snapshot_key()?;
@@ -523,23 +948,23 @@ impl BlockchainBridge {
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng); // 96-bits; unique per message
 
         let ciphertext = cipher.encrypt(&nonce, json.as_ref())
-            .map_err(|_| "Encryption failed")?;
+            .map_err(|_| AppError::crypto("encryption failed"))?;
 
         // Prepend nonce to ciphertext for storage
         let mut final_data = nonce.to_vec();
         final_data.extend_from_slice(&ciphertext);
 
         fs::write(&self.storage_path, final_data)?;
-        println!("💾 [Bridge] Snapshot ENCRYPTED and saved via Keychain Key.");
+        tracing::debug!(path = %self.storage_path.display(), "snapshot encrypted and saved");
         Ok(())
     }
 
-    pub fn get_latest_snapshot(&self) -> Result<Snapshot, Box<dyn Error>> {
+    pub fn get_latest_snapshot(&self) -> AppResult<Snapshot> {
         if !self.storage_path.exists() {
-            return Err("No snapshot found".into());
+            return Err(AppError::NotFound("snapshot".to_string()));
         }
         let file_data = fs::read(&self.storage_path)?;
-        if file_data.len() < 12 { return Err("Corrupted snapshot file".into()); }
+        if file_data.len() < 12 { return Err(AppError::Storage("corrupted snapshot file".to_string())); }
 
         let (nonce_bytes, ciphertext) = file_data.split_at(12);
         let nonce = Nonce::from_slice(nonce_bytes);
@@ -548,20 +973,20 @@ impl BlockchainBridge {
         let cipher = Aes256Gcm::new(&key);
 
         let plaintext = cipher.decrypt(nonce, ciphertext)
-            .map_err(|_| "Decryption failed - Invalid Key or Corrupted Data")?;
+            .map_err(|_| AppError::crypto("decryption failed - invalid key or corrupted data"))?;
 
         let snapshot: Snapshot = serde_json::from_slice(&plaintext)?;
         Ok(snapshot)
     }
 
-    pub fn delete_snapshot(&self) -> Result<(), Box<dyn Error>> {
+    pub fn delete_snapshot(&self) -> AppResult<()> {
         if self.storage_path.exists() {
             fs::remove_file(&self.storage_path)?;
         }
         Ok(())
     }
 
-    pub fn delete_identity(&self) -> Result<(), Box<dyn Error>> {
+    pub fn delete_identity(&self) -> AppResult<()> {
         if self.identity_path.exists() {
             fs::remove_file(&self.identity_path)?;
         }
@@ -569,7 +994,7 @@ impl BlockchainBridge {
     }
 
     // ... Mock methods for sessions
-    pub fn init_instant_session(&mut self) -> InstantSession {
+    pub fn init_instant_session(&self, expiry: chrono::Duration) -> InstantSession {
         // Generate a fresh ephemeral signer for the agent
         let agent_signer = PrivateKeySigner::random();
         let authority_address = agent_signer.address().to_string();
@@ -580,103 +1005,166 @@ impl BlockchainBridge {
         let session = InstantSession {
             session_id: format!("sess_{}", Utc::now().timestamp()),
             authority: authority_address,
-            expiry: Utc::now() + chrono::Duration::hours(1),
+            expiry: Utc::now() + expiry,
             is_active: true,
         };
-        self.current_session = Some(session.clone());
+        *self.current_session.write().unwrap_or_else(PoisonError::into_inner) = Some(session.clone());
         session
     }
 
     pub fn get_status(&self) -> String {
-        match &self.current_session {
+        match &*self.current_session.read().unwrap_or_else(PoisonError::into_inner) {
             Some(s) if s.is_active => format!("Instant Session Engine: Active [Agent: {}...]", &s.authority[..6]),
             _ => "Instant Session Engine: Inactive".to_string(),
         }
     }
 
+    /// Probes the RPC pool once and records the outcome in `connectivity`.
+    /// Returns whether an endpoint answered.
+    pub async fn probe_connectivity(&self) -> bool {
+        let answered = self.chain.probe(PROBE_TIMEOUT).await;
+        let reachable = answered.is_some();
+        self.connectivity.observe(answered);
+        reachable
+    }
+
+    pub fn get_rpc_health(&self) -> Vec<EndpointStatus> {
+        self.chain.status()
+    }
+
+    /// A contract view call, decoded with the contract's ABI.
+    async fn view<C: SolCall + Send>(&self, to: Address, call: C) -> AppResult<C::Return> {
+        let output = self.chain.call(None, to, encode(call), U256::ZERO).await?;
+        C::abi_decode_returns(&output)
+            .map_err(|e| AppError::RpcRejected(format!("undecodable {} result: {}", C::SIGNATURE, e)))
+    }
+
+    /// Simulates a contract write from our primary identity with `eth_call`,
+    /// so a doomed transaction is caught — with its revert reason decoded
+    /// from our ABIs — before it costs gas.
+    async fn simulate(&self, call: &str, from: Address, to: Address, calldata: &Bytes, value: U256) -> AppResult<()> {
+        match self.chain.call(Some(from), to, calldata.clone(), value).await {
+            Ok(_) => Ok(()),
+            Err(RpcPoolError::Rejected { revert_data: Some(data), .. }) => Err(AppError::Reverted {
+                call: call.to_string(),
+                reason: decode_revert(&data),
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The online attempt for a write that can fall back to offline signing.
+    /// Doesn't touch the RPC while the connectivity monitor reports an
+    /// outage, and counts an unreachable send as a failed probe.
+    async fn send_or_unreachable(
+        &self,
+        call: &str,
+        signer: PrivateKeySigner,
+        to: Address,
+        calldata: Bytes,
+        value: U256,
+    ) -> AppResult<ChainReceipt> {
+        if self.connectivity.is_offline() {
+            return Err(AppError::RpcUnreachable("connectivity monitor reports offline".to_string()));
+        }
+        let result = self.send_contract_tx(call, signer, to, calldata, value, self.chain.timeouts().write).await;
+        if matches!(result, Err(AppError::RpcUnreachable(_))) {
+            self.connectivity.observe(None);
+        }
+        result
+    }
+
+    /// Pre-flight simulation, then send + receipt on the healthiest endpoint.
+    /// A receipt that still reports a revert (state changed after the
+    /// simulation) is surfaced as `AppError::Reverted` too.
+    async fn send_contract_tx(
+        &self,
+        call: &str,
+        signer: PrivateKeySigner,
+        to: Address,
+        calldata: Bytes,
+        value: U256,
+        limit: std::time::Duration,
+    ) -> AppResult<ChainReceipt> {
+        self.simulate(call, signer.address(), to, &calldata, value).await?;
+
+        let receipt = self.chain.send(signer, to, calldata, value, limit).await?;
+
+        if !receipt.status {
+            return Err(AppError::Reverted {
+                call: call.to_string(),
+                reason: RevertReason::Unknown { data: String::new() },
+            });
+        }
+        Ok(receipt)
+    }
+
     /// Creates an on-chain escrow deal, locking `amount_wei` for `payee`.
-    /// If the RPC can't be reached within a few seconds, falls back to
-    /// signing the transaction offline and queuing it for mesh relay.
-    pub async fn create_escrow(&self, payee: &str, amount_wei: U256, expiry_unix: u64) -> Result<TxResult, Box<dyn Error>> {
+    /// If no RPC endpoint answers, falls back to signing the transaction
+    /// offline and queuing it for mesh relay.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_escrow(&self, payee: &str, amount_wei: U256, expiry_unix: u64) -> AppResult<TxResult> {
         let signer = self.primary_signer()?;
-        let escrow_address = self.escrow_address.ok_or("ESCROW_CONTRACT_ADDRESS not configured")?;
+        let escrow_address = self.escrow_address.ok_or(AppError::NotConfigured("ESCROW_CONTRACT_ADDRESS"))?;
         let payee_addr = Address::from_str(payee)?;
 
         // Build calldata once — reused for both the online path and the offline fallback.
-        let unsigned_provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let unsigned_contract = IEscrow::new(escrow_address, unsigned_provider);
-        let calldata = unsigned_contract.createEscrow(payee_addr, U256::from(expiry_unix)).calldata().clone();
-
-        let provider = ProviderBuilder::new()
-            .wallet(signer)
-            .connect_http(self.rpc_url.parse()?);
-        let contract = IEscrow::new(escrow_address, provider);
-
-        // Errors are mapped to a plain (Send-safe) String inside this block — `Box<dyn
-        // Error>` isn't Send, and holding one alive across the offline-fallback `.await`
-        // below would make the whole Tauri command future non-Send.
-        let online_result = timeout(Duration::from_secs(6), async {
-            let pending = contract
-                .createEscrow(payee_addr, U256::from(expiry_unix))
-                .value(amount_wei)
-                .send()
-                .await
-                .map_err(|e| e.to_string())?;
-            pending.get_receipt().await.map_err(|e| e.to_string())
-        }).await;
+        let calldata = encode(IEscrow::createEscrowCall { payee: payee_addr, expiry: U256::from(expiry_unix) });
+
+        let online_result = self
+            .send_or_unreachable("create_escrow", signer, escrow_address, calldata.clone(), amount_wei)
+            .await;
 
         let receipt = match online_result {
-            Ok(Ok(receipt)) => receipt,
-            Ok(Err(e)) => return Err(e.into()),
-            Err(_timed_out) => {
-                println!("⚠️  [Bridge] RPC unreachable — signing create_escrow offline for mesh relay.");
+            Ok(receipt) => receipt,
+            Err(AppError::RpcUnreachable(e)) => {
+                tracing::warn!(error = %e, "RPC unreachable, signing create_escrow offline for mesh relay");
                 let queued = self.sign_offline(escrow_address, calldata, amount_wei, "Create escrow").await?;
                 return Ok(TxResult::Queued { queue_id: queued.id });
             }
+            Err(e) => return Err(e),
         };
 
         let escrow_id = receipt
-            .logs()
-            .iter()
-            .find_map(|log| log.log_decode::<IEscrow::EscrowCreated>().ok())
-            .map(|l| l.inner.data.escrowId.to::<u64>())
-            .ok_or("EscrowCreated event not found in receipt")?;
+            .event::<IEscrow::EscrowCreated>()
+            .map(|e| e.escrowId.to::<u64>())
+            .ok_or_else(|| AppError::NotFound("EscrowCreated event in receipt".to_string()))?;
 
-        println!("✅ [Bridge] Escrow {} created. Tx: {:?}", escrow_id, receipt.transaction_hash);
+        tracing::info!(escrow_id, tx = ?receipt.transaction_hash, "escrow created");
         Ok(TxResult::Confirmed { id: escrow_id })
     }
 
-    pub async fn release_escrow(&self, escrow_id: u64) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn release_escrow(&self, escrow_id: u64) -> AppResult<String> {
         let signer = self.primary_signer()?;
-        let escrow_address = self.escrow_address.ok_or("ESCROW_CONTRACT_ADDRESS not configured")?;
+        let escrow_address = self.escrow_address.ok_or(AppError::NotConfigured("ESCROW_CONTRACT_ADDRESS"))?;
 
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IEscrow::new(escrow_address, provider);
-
-        let receipt = contract.release(U256::from(escrow_id)).send().await?.get_receipt().await?;
-        println!("✅ [Bridge] Escrow {} released. Tx: {:?}", escrow_id, receipt.transaction_hash);
+        let calldata = encode(IEscrow::releaseCall { escrowId: U256::from(escrow_id) });
+        let receipt = self
+            .send_contract_tx("release_escrow", signer, escrow_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
+            .await?;
+        tracing::info!(tx = ?receipt.transaction_hash, "escrow released");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
-    pub async fn refund_escrow(&self, escrow_id: u64) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn refund_escrow(&self, escrow_id: u64) -> AppResult<String> {
         let signer = self.primary_signer()?;
-        let escrow_address = self.escrow_address.ok_or("ESCROW_CONTRACT_ADDRESS not configured")?;
+        let escrow_address = self.escrow_address.ok_or(AppError::NotConfigured("ESCROW_CONTRACT_ADDRESS"))?;
 
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IEscrow::new(escrow_address, provider);
-
-        let receipt = contract.refund(U256::from(escrow_id)).send().await?.get_receipt().await?;
-        println!("✅ [Bridge] Escrow {} refunded. Tx: {:?}", escrow_id, receipt.transaction_hash);
+        let calldata = encode(IEscrow::refundCall { escrowId: U256::from(escrow_id) });
+        let receipt = self
+            .send_contract_tx("refund_escrow", signer, escrow_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
+            .await?;
+        tracing::info!(tx = ?receipt.transaction_hash, "escrow refunded");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
     /// Reads the on-chain state of a deal (no signer required).
-    pub async fn get_escrow_status(&self, escrow_id: u64) -> Result<serde_json::Value, Box<dyn Error>> {
-        let escrow_address = self.escrow_address.ok_or("ESCROW_CONTRACT_ADDRESS not configured")?;
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let contract = IEscrow::new(escrow_address, provider);
+    pub async fn get_escrow_status(&self, escrow_id: u64) -> AppResult<serde_json::Value> {
+        let escrow_address = self.escrow_address.ok_or(AppError::NotConfigured("ESCROW_CONTRACT_ADDRESS"))?;
 
-        let deal = contract.getDeal(U256::from(escrow_id)).call().await?;
+        let deal = self.view(escrow_address, IEscrow::getDealCall { escrowId: U256::from(escrow_id) }).await?;
 
         Ok(serde_json::json!({
             "depositor": deal.depositor.to_string(),
@@ -690,89 +1178,75 @@ impl BlockchainBridge {
     /// Mints a new voucher NFT to the primary identity. This mint call is
     /// itself the proof-of-possession step: only the real key-holder can
     /// mint a token into their own name.
-    pub async fn mint_voucher(&self, voucher_type: &str, description: &str) -> Result<u64, Box<dyn Error>> {
+    #[tracing::instrument(skip(self, description), err)]
+    pub async fn mint_voucher(&self, voucher_type: &str, description: &str) -> AppResult<u64> {
         let signer = self.primary_signer()?;
-        let voucher_address = self.voucher_address.ok_or("VOUCHER_CONTRACT_ADDRESS not configured")?;
+        let voucher_address = self.voucher_address.ok_or(AppError::NotConfigured("VOUCHER_CONTRACT_ADDRESS"))?;
 
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IVoucher::new(voucher_address, provider);
-
-        let receipt = contract
-            .mintVoucher(voucher_type.to_string(), description.to_string())
-            .send()
-            .await?
-            .get_receipt()
+        let calldata = encode(IVoucher::mintVoucherCall {
+            voucherType: voucher_type.to_string(),
+            description: description.to_string(),
+        });
+        let receipt = self
+            .send_contract_tx("mint_voucher", signer, voucher_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
             .await?;
 
         let token_id = receipt
-            .logs()
-            .iter()
-            .find_map(|log| log.log_decode::<IVoucher::VoucherMinted>().ok())
-            .map(|l| l.inner.data.tokenId.to::<u64>())
-            .ok_or("VoucherMinted event not found in receipt")?;
+            .event::<IVoucher::VoucherMinted>()
+            .map(|e| e.tokenId.to::<u64>())
+            .ok_or_else(|| AppError::NotFound("VoucherMinted event in receipt".to_string()))?;
 
-        println!("✅ [Bridge] Voucher {} minted. Tx: {:?}", token_id, receipt.transaction_hash);
+        tracing::info!(token_id, tx = ?receipt.transaction_hash, "voucher minted");
         Ok(token_id)
     }
 
     /// Approves the Marketplace contract to pull a specific voucher out of
     /// the seller's wallet, required before that voucher can be listed.
-    pub async fn approve_voucher(&self, token_id: u64) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn approve_voucher(&self, token_id: u64) -> AppResult<String> {
         let signer = self.primary_signer()?;
-        let voucher_address = self.voucher_address.ok_or("VOUCHER_CONTRACT_ADDRESS not configured")?;
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
-
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IVoucher::new(voucher_address, provider);
+        let voucher_address = self.voucher_address.ok_or(AppError::NotConfigured("VOUCHER_CONTRACT_ADDRESS"))?;
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
 
-        let receipt = contract
-            .approve(marketplace_address, U256::from(token_id))
-            .send()
-            .await?
-            .get_receipt()
+        let calldata = encode(IVoucher::approveCall { to: marketplace_address, tokenId: U256::from(token_id) });
+        let receipt = self
+            .send_contract_tx("approve_voucher", signer, voucher_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
             .await?;
 
-        println!("✅ [Bridge] Voucher {} approved for Marketplace. Tx: {:?}", token_id, receipt.transaction_hash);
+        tracing::info!(tx = ?receipt.transaction_hash, "voucher approved for marketplace");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
     /// Publishes a real on-chain listing backed by an owned, approved voucher.
     /// Returns the generated listing id.
-    pub async fn create_asset_listing(&self, description: &str, price_wei: U256, token_id: u64) -> Result<u64, Box<dyn Error>> {
+    #[tracing::instrument(skip(self, description), err)]
+    pub async fn create_asset_listing(&self, description: &str, price_wei: U256, token_id: u64) -> AppResult<u64> {
         let signer = self.primary_signer()?;
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
-
-        let provider = ProviderBuilder::new()
-            .wallet(signer)
-            .connect_http(self.rpc_url.parse()?);
-
-        let contract = IMarketplace::new(marketplace_address, provider);
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
 
-        let receipt = contract
-            .createListing(description.to_string(), price_wei, U256::from(token_id))
-            .send()
-            .await?
-            .get_receipt()
+        let calldata = encode(IMarketplace::createListingCall {
+            description: description.to_string(),
+            priceWei: price_wei,
+            tokenId: U256::from(token_id),
+        });
+        let receipt = self
+            .send_contract_tx("create_asset_listing", signer, marketplace_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
             .await?;
 
         let listing_id = receipt
-            .logs()
-            .iter()
-            .find_map(|log| log.log_decode::<IMarketplace::ListingCreated>().ok())
-            .map(|l| l.inner.data.id.to::<u64>())
-            .ok_or("ListingCreated event not found in receipt")?;
+            .event::<IMarketplace::ListingCreated>()
+            .map(|e| e.id.to::<u64>())
+            .ok_or_else(|| AppError::NotFound("ListingCreated event in receipt".to_string()))?;
 
-        println!("✅ [Bridge] Listing {} created. Tx: {:?}", listing_id, receipt.transaction_hash);
+        tracing::info!(listing_id, tx = ?receipt.transaction_hash, "listing created");
         Ok(listing_id)
     }
 
     /// Reads all active listings from the Marketplace contract (no signer required).
-    pub async fn get_active_asset_listings(&self) -> Result<Vec<AssetListingView>, Box<dyn Error>> {
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let contract = IMarketplace::new(marketplace_address, provider);
+    pub async fn get_active_asset_listings(&self) -> AppResult<Vec<AssetListingView>> {
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
 
-        let result = contract.getActiveListings().call().await?;
+        let result = self.view(marketplace_address, IMarketplace::getActiveListingsCall {}).await?;
 
         let views = result
             .result
@@ -786,128 +1260,207 @@ impl BlockchainBridge {
                 price_avax: alloy::primitives::utils::format_ether(listing.priceWei),
                 token_id: listing.tokenId.to::<u64>(),
             })
-            .collect();
+            .collect::<Vec<_>>();
 
+        let catalogue = ListingCatalogue {
+            listings: views.clone(),
+            fetched_at: Utc::now(),
+            source: CatalogueSource::Rpc,
+            stale: false,
+        };
+        let _store = self.store();
+        if let Err(e) = self.save_listing_catalogue(&catalogue) {
+            tracing::warn!(error = %e, "failed to cache listing catalogue");
+        }
         Ok(views)
     }
 
+    fn load_listing_catalogue(&self) -> Option<ListingCatalogue> {
+        let content = fs::read_to_string(&self.listing_catalogue_path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_listing_catalogue(&self, catalogue: &ListingCatalogue) -> AppResult<()> {
+        fs::write(&self.listing_catalogue_path, serde_json::to_string_pretty(catalogue)?)?;
+        Ok(())
+    }
+
+    /// Active listings for matching: live from the contract when the RPC
+    /// answers, otherwise the last cached catalogue (our own fetch or a
+    /// peer's snapshot) flagged `stale`. Skips the RPC entirely while the
+    /// connectivity monitor reports an outage.
+    pub async fn get_listing_catalogue(&self) -> AppResult<ListingCatalogue> {
+        let unreachable = if self.connectivity.is_offline() {
+            AppError::RpcUnreachable("connectivity monitor reports the RPC offline".to_string())
+        } else {
+            match self.get_active_asset_listings().await {
+                Ok(listings) => {
+                    return Ok(ListingCatalogue { listings, fetched_at: Utc::now(), source: CatalogueSource::Rpc, stale: false })
+                }
+                Err(e @ AppError::RpcUnreachable(_)) => e,
+                Err(e) => return Err(e),
+            }
+        };
+        let mut cached = self.load_listing_catalogue().ok_or(unreachable)?;
+        cached.stale = true;
+        Ok(cached)
+    }
+
+    /// Our cached catalogue, signed for sharing over the mesh. Only listings
+    /// this node fetched itself are shared; relaying a peer's snapshot under
+    /// our signature would vouch for data we never saw.
+    pub fn signed_catalogue(&self) -> AppResult<Option<SignedCatalogue>> {
+        let Some(catalogue) = self.load_listing_catalogue().filter(|c| c.source == CatalogueSource::Rpc) else {
+            return Ok(None);
+        };
+        SignedCatalogue::sign(catalogue.listings, catalogue.fetched_at, &self.primary_signer()?).map(Some)
+    }
+
+    /// The primary identity's public key for sealed intents.
+    pub fn intent_public_key(&self) -> AppResult<k256::PublicKey> {
+        Ok(intent_secret_key(&self.primary_signer()?.credential().into())?.public_key())
+    }
+
+    /// Opens an intent sealed to the primary identity's intent key.
+    pub fn open_sealed_intent(&self, sealed: &SealedIntent) -> AppResult<IntentOpening> {
+        sealed.open(&intent_secret_key(&self.primary_signer()?.credential().into())?)
+    }
+
+    /// Signs a bid proof with the primary identity, the prover it must
+    /// commit to for receivers to accept it.
+    pub fn sign_bid_proof(&self, bid_amount: u64, proof: ZKProof) -> AppResult<BidProof> {
+        BidProof::sign(bid_amount, proof, &self.primary_signer()?)
+    }
+
+    /// Signs our report on a tx we relayed with the primary identity, the
+    /// relayer the requesting peer keeps our record under.
+    pub fn sign_relay_claim(&self, queue_id: &str, status: &str, tx_hash: Option<&str>) -> AppResult<SignedRelayClaim> {
+        SignedRelayClaim::sign(queue_id, status, tx_hash, &self.primary_signer()?)
+    }
+
+    /// Stores a peer's signed snapshot if it verifies, its signer is one of
+    /// `trusted` and it's newer than a cached peer snapshot. A catalogue we
+    /// fetched from the chain ourselves is never replaced; the next online
+    /// fetch replaces a peer's. Returns whether it replaced the cache.
+    pub fn import_catalogue_snapshot(&self, snapshot: SignedCatalogue, trusted: &[Address]) -> AppResult<bool> {
+        snapshot.verify()?;
+        if !trusted.contains(&Address::from_str(&snapshot.signer)?) {
+            return Err(AppError::InvalidInput(format!("catalogue snapshot signer {} is not in catalogue_peers", snapshot.signer)));
+        }
+        let _store = self.store();
+        if self
+            .load_listing_catalogue()
+            .is_some_and(|c| c.source == CatalogueSource::Rpc || c.fetched_at >= snapshot.fetched_at)
+        {
+            return Ok(false);
+        }
+        self.save_listing_catalogue(&snapshot.into_catalogue())?;
+        Ok(true)
+    }
+
     /// Atomically locks `price_wei` AVAX and pulls the seller's voucher into
     /// the Marketplace contract in a single transaction. Returns the deal id.
-    /// If the RPC can't be reached within a few seconds, falls back to
-    /// signing the transaction offline and queuing it for mesh relay.
-    pub async fn buy_listing(&self, listing_id: u64, price_wei: U256) -> Result<TxResult, Box<dyn Error>> {
+    /// If no RPC endpoint answers, falls back to signing the transaction
+    /// offline and queuing it for mesh relay.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn buy_listing(&self, listing_id: u64, price_wei: U256) -> AppResult<TxResult> {
         let signer = self.primary_signer()?;
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
-
-        let unsigned_provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let unsigned_contract = IMarketplace::new(marketplace_address, unsigned_provider);
-        let calldata = unsigned_contract.buy(U256::from(listing_id)).calldata().clone();
-
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IMarketplace::new(marketplace_address, provider);
-
-        // See create_escrow's comment above: map to String here to keep this future Send.
-        let online_result = timeout(Duration::from_secs(6), async {
-            let pending = contract
-                .buy(U256::from(listing_id))
-                .value(price_wei)
-                .send()
-                .await
-                .map_err(|e| e.to_string())?;
-            pending.get_receipt().await.map_err(|e| e.to_string())
-        }).await;
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
+
+        let calldata = encode(IMarketplace::buyCall { listingId: U256::from(listing_id) });
+
+        let online_result = self
+            .send_or_unreachable("buy_listing", signer, marketplace_address, calldata.clone(), price_wei)
+            .await;
 
         let receipt = match online_result {
-            Ok(Ok(receipt)) => receipt,
-            Ok(Err(e)) => return Err(e.into()),
-            Err(_timed_out) => {
-                println!("⚠️  [Bridge] RPC unreachable — signing buy_listing offline for mesh relay.");
+            Ok(receipt) => receipt,
+            Err(AppError::RpcUnreachable(e)) => {
+                tracing::warn!(error = %e, "RPC unreachable, signing buy_listing offline for mesh relay");
                 let queued = self.sign_offline(marketplace_address, calldata, price_wei, "Buy listing").await?;
                 return Ok(TxResult::Queued { queue_id: queued.id });
             }
+            Err(e) => return Err(e),
         };
 
         let deal_id = receipt
-            .logs()
-            .iter()
-            .find_map(|log| log.log_decode::<IMarketplace::DealCreated>().ok())
-            .map(|l| l.inner.data.dealId.to::<u64>())
-            .ok_or("DealCreated event not found in receipt")?;
+            .event::<IMarketplace::DealCreated>()
+            .map(|e| e.dealId.to::<u64>())
+            .ok_or_else(|| AppError::NotFound("DealCreated event in receipt".to_string()))?;
 
-        println!("✅ [Bridge] Deal {} created (voucher + AVAX locked). Tx: {:?}", deal_id, receipt.transaction_hash);
+        tracing::info!(deal_id, tx = ?receipt.transaction_hash, "deal created (voucher + AVAX locked)");
         Ok(TxResult::Confirmed { id: deal_id })
     }
 
     /// Releases a deal: pays the seller and transfers the voucher to the buyer.
-    pub async fn release_deal(&self, deal_id: u64) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn release_deal(&self, deal_id: u64) -> AppResult<String> {
         let signer = self.primary_signer()?;
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
-
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IMarketplace::new(marketplace_address, provider);
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
 
-        let receipt = contract.releaseDeal(U256::from(deal_id)).send().await?.get_receipt().await?;
-        println!("✅ [Bridge] Deal {} released. Tx: {:?}", deal_id, receipt.transaction_hash);
+        let calldata = encode(IMarketplace::releaseDealCall { dealId: U256::from(deal_id) });
+        let receipt = self
+            .send_contract_tx("release_deal", signer, marketplace_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
+            .await?;
+        tracing::info!(tx = ?receipt.transaction_hash, "deal released");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
     /// Refunds a deal: returns AVAX to the buyer and the voucher to the seller.
-    pub async fn refund_deal(&self, deal_id: u64) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn refund_deal(&self, deal_id: u64) -> AppResult<String> {
         let signer = self.primary_signer()?;
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
-
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IMarketplace::new(marketplace_address, provider);
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
 
-        let receipt = contract.refundDeal(U256::from(deal_id)).send().await?.get_receipt().await?;
-        println!("✅ [Bridge] Deal {} refunded. Tx: {:?}", deal_id, receipt.transaction_hash);
+        let calldata = encode(IMarketplace::refundDealCall { dealId: U256::from(deal_id) });
+        let receipt = self
+            .send_contract_tx("refund_deal", signer, marketplace_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
+            .await?;
+        tracing::info!(tx = ?receipt.transaction_hash, "deal refunded");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
     /// Burns a voucher the caller owns, claiming the service it represents.
     /// Requires real on-chain ownership (`ownerOf(tokenId) == msg.sender`).
-    pub async fn redeem_voucher(&self, token_id: u64) -> Result<String, Box<dyn Error>> {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn redeem_voucher(&self, token_id: u64) -> AppResult<String> {
         let signer = self.primary_signer()?;
-        let voucher_address = self.voucher_address.ok_or("VOUCHER_CONTRACT_ADDRESS not configured")?;
-
-        let provider = ProviderBuilder::new().wallet(signer).connect_http(self.rpc_url.parse()?);
-        let contract = IVoucher::new(voucher_address, provider);
+        let voucher_address = self.voucher_address.ok_or(AppError::NotConfigured("VOUCHER_CONTRACT_ADDRESS"))?;
 
-        let receipt = contract.redeemVoucher(U256::from(token_id)).send().await?.get_receipt().await?;
-        println!("✅ [Bridge] Voucher {} redeemed. Tx: {:?}", token_id, receipt.transaction_hash);
+        let calldata = encode(IVoucher::redeemVoucherCall { tokenId: U256::from(token_id) });
+        let receipt = self
+            .send_contract_tx("redeem_voucher", signer, voucher_address, calldata, U256::ZERO, self.chain.timeouts().confirm)
+            .await?;
+        tracing::info!(tx = ?receipt.transaction_hash, "voucher redeemed");
         Ok(format!("{:?}", receipt.transaction_hash))
     }
 
     /// Reads the current on-chain owner of a voucher (no signer required).
-    pub async fn get_voucher_owner(&self, token_id: u64) -> Result<String, Box<dyn Error>> {
-        let voucher_address = self.voucher_address.ok_or("VOUCHER_CONTRACT_ADDRESS not configured")?;
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let contract = IVoucher::new(voucher_address, provider);
+    pub async fn get_voucher_owner(&self, token_id: u64) -> AppResult<String> {
+        let voucher_address = self.voucher_address.ok_or(AppError::NotConfigured("VOUCHER_CONTRACT_ADDRESS"))?;
 
-        let owner = contract.ownerOf(U256::from(token_id)).call().await?;
+        let owner = self.view(voucher_address, IVoucher::ownerOfCall { tokenId: U256::from(token_id) }).await?;
         Ok(owner.to_string())
     }
 
     /// Lists every voucher the given address currently owns on-chain — used
     /// by the Redeem page so it only ever shows vouchers the caller really
     /// holds, never a claim it has to trust.
-    pub async fn get_owned_vouchers(&self, owner: &str) -> Result<Vec<VoucherView>, Box<dyn Error>> {
-        let voucher_address = self.voucher_address.ok_or("VOUCHER_CONTRACT_ADDRESS not configured")?;
+    pub async fn get_owned_vouchers(&self, owner: &str) -> AppResult<Vec<VoucherView>> {
+        let voucher_address = self.voucher_address.ok_or(AppError::NotConfigured("VOUCHER_CONTRACT_ADDRESS"))?;
         let owner_addr = Address::from_str(owner)?;
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let contract = IVoucher::new(voucher_address, provider);
 
-        let next_id = contract.nextTokenId().call().await?.to::<u64>();
+        let next_id = self.view(voucher_address, IVoucher::nextTokenIdCall {}).await?.to::<u64>();
 
         let mut owned = Vec::new();
         for token_id in 1..next_id {
-            let Ok(current_owner) = contract.ownerOf(U256::from(token_id)).call().await else {
+            let Ok(current_owner) = self.view(voucher_address, IVoucher::ownerOfCall { tokenId: U256::from(token_id) }).await else {
                 continue; // burned or nonexistent token
             };
             if current_owner != owner_addr {
                 continue;
             }
-            if let Ok(data) = contract.vouchers(U256::from(token_id)).call().await {
+            if let Ok(data) = self.view(voucher_address, IVoucher::vouchersCall(U256::from(token_id))).await {
                 owned.push(VoucherView {
                     token_id,
                     voucher_type: data.voucherType,
@@ -924,17 +1477,15 @@ impl BlockchainBridge {
     /// with its real on-chain status — this IS the "an agent is dealing with
     /// this listing" signal: `active` means a buyer has locked funds against
     /// a seller's voucher and it's awaiting release/refund.
-    pub async fn get_my_deals(&self, address: &str) -> Result<Vec<DealView>, Box<dyn Error>> {
-        let marketplace_address = self.marketplace_address.ok_or("MARKETPLACE_CONTRACT_ADDRESS not configured")?;
+    pub async fn get_my_deals(&self, address: &str) -> AppResult<Vec<DealView>> {
+        let marketplace_address = self.marketplace_address.ok_or(AppError::NotConfigured("MARKETPLACE_CONTRACT_ADDRESS"))?;
         let my_addr = Address::from_str(address)?;
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        let contract = IMarketplace::new(marketplace_address, provider);
 
-        let next_id = contract.nextDealId().call().await?.to::<u64>();
+        let next_id = self.view(marketplace_address, IMarketplace::nextDealIdCall {}).await?.to::<u64>();
 
         let mut deals = Vec::new();
         for deal_id in 1..next_id {
-            let Ok(deal) = contract.getDeal(U256::from(deal_id)).call().await else {
+            let Ok(deal) = self.view(marketplace_address, IMarketplace::getDealCall { dealId: U256::from(deal_id) }).await else {
                 continue;
             };
             if deal.buyer != my_addr && deal.seller != my_addr {
@@ -969,7 +1520,7 @@ impl BlockchainBridge {
             .unwrap_or_default()
     }
 
-    fn save_content_store(&self, store: &std::collections::HashMap<u64, ContentRecord>) -> Result<(), Box<dyn Error>> {
+    fn save_content_store(&self, store: &std::collections::HashMap<u64, ContentRecord>) -> AppResult<()> {
         fs::write(&self.content_store_path, serde_json::to_string_pretty(store)?)?;
         Ok(())
     }
@@ -981,16 +1532,16 @@ impl BlockchainBridge {
             .unwrap_or_default()
     }
 
-    fn save_received_content(&self, store: &std::collections::HashMap<u64, ContentRecord>) -> Result<(), Box<dyn Error>> {
+    fn save_received_content(&self, store: &std::collections::HashMap<u64, ContentRecord>) -> AppResult<()> {
         fs::write(&self.received_content_path, serde_json::to_string_pretty(store)?)?;
         Ok(())
     }
 
     /// Extracts page 1's text from a PDF's raw bytes (pure-Rust, no system
     /// dependency, no network needed).
-    pub fn extract_pdf_text(&self, pdf_bytes: Vec<u8>) -> Result<String, Box<dyn Error>> {
-        let doc = lopdf::Document::load_mem(&pdf_bytes)?;
-        let text = doc.extract_text(&[1])?;
+    pub fn extract_pdf_text(&self, pdf_bytes: Vec<u8>) -> AppResult<String> {
+        let doc = lopdf::Document::load_mem(&pdf_bytes).map_err(AppError::invalid_input)?;
+        let text = doc.extract_text(&[1]).map_err(AppError::invalid_input)?;
         Ok(text)
     }
 
@@ -998,9 +1549,9 @@ impl BlockchainBridge {
     /// verifiable commitment standing in for a literal ZK proof (no
     /// `nargo`/Noir available). `token_id` is filled in by the caller once
     /// the voucher has actually been minted.
-    pub fn sign_content(&self, text: &str) -> Result<ContentRecord, Box<dyn Error>> {
+    pub fn sign_content(&self, text: &str) -> AppResult<ContentRecord> {
         let signer = self.primary_signer()?;
-        let signature = signer.sign_message_sync(text.as_bytes())?;
+        let signature = signer.sign_message_sync(text.as_bytes()).map_err(AppError::crypto)?;
         let fingerprint = format!("0x{}", hex::encode(&keccak256(text.as_bytes())[..8]));
 
         Ok(ContentRecord {
@@ -1014,8 +1565,9 @@ impl BlockchainBridge {
 
     /// Persists a signed content record for a listing this node sold, so it
     /// can respond when the buyer's node requests delivery over the mesh.
-    pub fn store_content(&self, token_id: u64, mut record: ContentRecord) -> Result<(), Box<dyn Error>> {
+    pub fn store_content(&self, token_id: u64, mut record: ContentRecord) -> AppResult<()> {
         record.token_id = token_id;
+        let _store = self.store();
         let mut store = self.load_content_store();
         store.insert(token_id, record);
         self.save_content_store(&store)
@@ -1028,17 +1580,19 @@ impl BlockchainBridge {
     /// Verifies a delivered piece of content really was signed by the
     /// expected seller before accepting it — never trusts the mesh payload
     /// on its own.
-    pub fn receive_content(&self, token_id: u64, text: &str, signature: &str, expected_seller: &str) -> Result<bool, Box<dyn Error>> {
-        let sig = Signature::from_str(signature)?;
-        let recovered = sig.recover_address_from_msg(text.as_bytes())?;
+    #[tracing::instrument(skip(self, text, signature), err)]
+    pub fn receive_content(&self, token_id: u64, text: &str, signature: &str, expected_seller: &str) -> AppResult<bool> {
+        let sig = Signature::from_str(signature).map_err(AppError::invalid_input)?;
+        let recovered = sig.recover_address_from_msg(text.as_bytes()).map_err(AppError::invalid_input)?;
         let expected = Address::from_str(expected_seller)?;
 
         if recovered != expected {
-            println!("⚠️  Content delivery rejected: signature recovered {} but expected seller {}", recovered, expected);
+            tracing::warn!(%recovered, %expected, "content delivery rejected: signer is not the seller");
             return Ok(false);
         }
 
         let fingerprint = format!("0x{}", hex::encode(&keccak256(text.as_bytes())[..8]));
+        let _store = self.store();
         let mut store = self.load_received_content();
         store.insert(token_id, ContentRecord {
             token_id,
@@ -1070,8 +1624,8 @@ mod offline_signing_tests {
         let tmp_dir = std::env::temp_dir().join(format!("cabalmesh_test_{}", std::process::id()));
         std::fs::create_dir_all(&tmp_dir).unwrap();
 
-        let mut bridge = BlockchainBridge {
-            identities: Vec::new(),
+        let bridge = BlockchainBridge {
+            identities: RwLock::new(Vec::new()),
             identity_path: tmp_dir.join("identities.json"),
             storage_path: tmp_dir.join("snapshot.enc"),
             chain_cache_path: tmp_dir.join("chain_cache.json"),
@@ -1079,12 +1633,17 @@ mod offline_signing_tests {
             relayed_history_path: tmp_dir.join("relayed_history.json"),
             content_store_path: tmp_dir.join("content_store.json"),
             received_content_path: tmp_dir.join("received_content.json"),
+            relay_reputation_path: tmp_dir.join("relay_reputation.json"),
+            listing_catalogue_path: tmp_dir.join("listing_catalogue.json"),
             // Deliberately unreachable — proves sign_offline never touches the network.
-            rpc_url: "http://127.0.0.1:9".to_string(),
-            escrow_address: None,
+            chain: Arc::new(RpcPool::new(vec!["http://127.0.0.1:9".to_string()], Default::default())),
+            escrow_address: Some(Address::repeat_byte(0xe5)),
             marketplace_address: None,
             voucher_address: None,
-            current_session: None,
+            current_session: RwLock::new(None),
+            store_lock: Mutex::new(()),
+            keychain_user: format!("{}:{}", KEYCHAIN_USER, tmp_dir.display()),
+            connectivity: Connectivity::new(),
         };
         bridge.generate_new_identity("Test".to_string(), "🧪".to_string()).unwrap();
 
@@ -1119,6 +1678,19 @@ mod offline_signing_tests {
             .expect("second sign_offline should also succeed");
         assert_ne!(queued.raw_tx_hex, queued2.raw_tx_hex);
 
+        // Once a probe has found the RPC down, a write with an offline
+        // fallback queues straight away instead of waiting out the write timeout.
+        assert!(!bridge.probe_connectivity().await);
+        assert!(bridge.connectivity.is_offline());
+        let started = std::time::Instant::now();
+        let result = bridge
+            .create_escrow("0x0000000000000000000000000000000000000002", U256::from(1), 0)
+            .await
+            .unwrap();
+        assert!(matches!(result, TxResult::Queued { .. }));
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(bridge.get_pending_relay_txs().len(), 3);
+
         std::fs::remove_dir_all(&tmp_dir).ok();
     }
 }
@@ -1129,7 +1701,7 @@ mod content_commitment_tests {
 
     fn test_bridge(tmp_dir: &PathBuf) -> BlockchainBridge {
         BlockchainBridge {
-            identities: Vec::new(),
+            identities: RwLock::new(Vec::new()),
             identity_path: tmp_dir.join("identities.json"),
             storage_path: tmp_dir.join("snapshot.enc"),
             chain_cache_path: tmp_dir.join("chain_cache.json"),
@@ -1137,11 +1709,16 @@ mod content_commitment_tests {
             relayed_history_path: tmp_dir.join("relayed_history.json"),
             content_store_path: tmp_dir.join("content_store.json"),
             received_content_path: tmp_dir.join("received_content.json"),
-            rpc_url: "http://127.0.0.1:9".to_string(),
+            relay_reputation_path: tmp_dir.join("relay_reputation.json"),
+            listing_catalogue_path: tmp_dir.join("listing_catalogue.json"),
+            chain: Arc::new(RpcPool::new(vec!["http://127.0.0.1:9".to_string()], Default::default())),
             escrow_address: None,
             marketplace_address: None,
             voucher_address: None,
-            current_session: None,
+            current_session: RwLock::new(None),
+            store_lock: Mutex::new(()),
+            keychain_user: format!("{}:{}", KEYCHAIN_USER, tmp_dir.display()),
+            connectivity: Connectivity::new(),
         }
     }
 
@@ -1153,7 +1730,7 @@ mod content_commitment_tests {
         let tmp_dir = std::env::temp_dir().join(format!("cabalmesh_content_test_{}", std::process::id()));
         std::fs::create_dir_all(&tmp_dir).unwrap();
 
-        let mut seller_bridge = test_bridge(&tmp_dir.join("seller"));
+        let seller_bridge = test_bridge(&tmp_dir.join("seller"));
         std::fs::create_dir_all(tmp_dir.join("seller")).unwrap();
         seller_bridge.generate_new_identity("Seller".to_string(), "📚".to_string()).unwrap();
         let seller_address = seller_bridge.get_primary_address();
@@ -1164,7 +1741,7 @@ mod content_commitment_tests {
         assert!(!record.signature.is_empty());
 
         // Buyer's own bridge instance (different identity) verifies the delivered content.
-        let mut buyer_bridge = test_bridge(&tmp_dir.join("buyer"));
+        let buyer_bridge = test_bridge(&tmp_dir.join("buyer"));
         std::fs::create_dir_all(tmp_dir.join("buyer")).unwrap();
         buyer_bridge.generate_new_identity("Buyer".to_string(), "🛒".to_string()).unwrap();
 
@@ -1184,4 +1761,282 @@ mod content_commitment_tests {
 
         std::fs::remove_dir_all(&tmp_dir).ok();
     }
+
+    /// With the RPC down, matching falls back to the last catalogue a trusted
+    /// peer shared, flagged stale; untrusted signers are refused, older
+    /// snapshots never replace a newer one, our own fetch is never replaced,
+    /// and a peer's catalogue is never re-shared under our own signature.
+    #[tokio::test]
+    async fn serves_peer_catalogue_while_offline() {
+        let tmp_dir = std::env::temp_dir().join(format!("cabalmesh_catalogue_test_{}", std::process::id()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+        let bridge = test_bridge(&tmp_dir);
+        bridge.connectivity.observe(None);
+        assert!(matches!(bridge.get_listing_catalogue().await, Err(AppError::RpcUnreachable(_))));
+
+        let peer = PrivateKeySigner::random();
+        let listing = AssetListingView {
+            id: 3,
+            seller: peer.address().to_string(),
+            description: "bike".to_string(),
+            price_wei: "1000000000000000000".to_string(),
+            price_avax: "1".to_string(),
+            token_id: 9,
+        };
+        let fetched_at = Utc::now() - chrono::Duration::minutes(10);
+        let snapshot = SignedCatalogue::sign(vec![listing.clone()], fetched_at, &peer).unwrap();
+        let stranger = SignedCatalogue::sign(Vec::new(), Utc::now(), &PrivateKeySigner::random()).unwrap();
+        assert!(matches!(bridge.import_catalogue_snapshot(stranger, &[peer.address()]), Err(AppError::InvalidInput(_))));
+        assert!(bridge.import_catalogue_snapshot(snapshot, &[peer.address()]).unwrap());
+
+        let catalogue = bridge.get_listing_catalogue().await.unwrap();
+        assert!(catalogue.stale);
+        assert_eq!(catalogue.listings, vec![listing.clone()]);
+        assert_eq!(catalogue.source, CatalogueSource::Mesh { signer: peer.address().to_string() });
+
+        let older = SignedCatalogue::sign(Vec::new(), fetched_at - chrono::Duration::minutes(1), &peer).unwrap();
+        assert!(!bridge.import_catalogue_snapshot(older, &[peer.address()]).unwrap());
+        assert!(bridge.signed_catalogue().unwrap().is_none());
+
+        // Even a trusted peer's newer snapshot never replaces our own fetch.
+        let own = ListingCatalogue { listings: Vec::new(), fetched_at, source: CatalogueSource::Rpc, stale: false };
+        bridge.save_listing_catalogue(&own).unwrap();
+        let newer = SignedCatalogue::sign(vec![listing], Utc::now(), &peer).unwrap();
+        assert!(!bridge.import_catalogue_snapshot(newer, &[peer.address()]).unwrap());
+        assert_eq!(bridge.get_listing_catalogue().await.unwrap().source, CatalogueSource::Rpc);
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+    }
+}
+
+#[cfg(test)]
+mod relay_verification_tests {
+    use super::*;
+
+    /// A `relay_confirmed` claim whose tx hash doesn't match our own signed
+    /// bytes must never mark the queue entry confirmed, and must count against
+    /// the relayer that sent it. The mismatch is caught before any RPC call,
+    /// so this runs with zero network access.
+    #[tokio::test]
+    async fn rejects_forged_relay_confirmation() {
+        let tmp_dir = std::env::temp_dir().join(format!("cabalmesh_relay_test_{}", std::process::id()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let chain = Arc::new(RpcPool::new(vec!["http://127.0.0.1:9".to_string()], Default::default()));
+        let bridge = BlockchainBridge::with_data_dir(chain, tmp_dir.clone(), None, None, None);
+        bridge.generate_new_identity("Test".to_string(), "🧪".to_string()).unwrap();
+        bridge.save_chain_cache(&ChainStateCache {
+            nonce: 0,
+            gas_price_wei: "30000000000".to_string(),
+            cached_at: Utc::now(),
+        }).unwrap();
+
+        let to = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let queued = bridge
+            .sign_offline(to, Bytes::from(vec![0xde, 0xad]), U256::from(0), "test tx")
+            .await
+            .unwrap();
+
+        let forged_hash = format!("0x{}", "ab".repeat(32));
+        let entry = bridge
+            .verify_relay_confirmation(&queued.id, "confirmed", Some(&forged_hash), Some("peer-liar"))
+            .await
+            .expect("a forged hash is rejected without touching the RPC");
+
+        assert_eq!(entry.status, "queued", "a forged confirmation must not change the queue entry");
+        assert_eq!(entry.tx_hash, None);
+
+        let reputation = bridge.get_relay_reputation();
+        assert_eq!(reputation["peer-liar"].rejected, 1);
+        assert_eq!(reputation["peer-liar"].verified, 0);
+
+        // Once distrusted, the relayer's claims aren't even checked.
+        for _ in 1..DISTRUSTED_AFTER_REJECTIONS {
+            bridge.verify_relay_confirmation(&queued.id, "confirmed", Some(&forged_hash), Some("peer-liar")).await.unwrap();
+        }
+        assert!(bridge.get_relay_reputation()["peer-liar"].is_distrusted());
+        bridge.verify_relay_confirmation(&queued.id, "confirmed", Some(&forged_hash), Some("peer-liar")).await.unwrap();
+        assert_eq!(bridge.get_relay_reputation()["peer-liar"].rejected, DISTRUSTED_AFTER_REJECTIONS);
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+    }
+}
+
+#[cfg(test)]
+mod mock_chain_tests {
+    use super::*;
+    use alloy::primitives::utils::parse_ether;
+    use crate::mock_chain::funded_bridge;
+
+    fn revert_message(result: AppResult<impl std::fmt::Debug>) -> String {
+        match result {
+            Err(AppError::Reverted { reason, .. }) => reason.to_string(),
+            other => panic!("expected a revert, got {:?}", other),
+        }
+    }
+
+    async fn balance(bridge: &BlockchainBridge) -> U256 {
+        bridge.chain.balance(bridge.primary_signer().unwrap().address()).await.unwrap()
+    }
+
+    /// Mint → approve → list → buy → release moves the voucher and the
+    /// funds exactly as the contracts do, with reverts decoded on the way.
+    #[tokio::test]
+    async fn settles_a_marketplace_deal() {
+        let chain = Arc::new(MockChain::new());
+        let seller = funded_bridge(&chain, "seller", "1");
+        let buyer = funded_bridge(&chain, "buyer", "5");
+        let price = parse_ether("2").unwrap();
+
+        let token_id = seller.mint_voucher("compute", "1h GPU").await.unwrap();
+        assert_eq!(token_id, 1);
+        assert_eq!(revert_message(seller.create_asset_listing("1h GPU", price, token_id).await), "Approve marketplace first");
+        seller.approve_voucher(token_id).await.unwrap();
+        let listing_id = seller.create_asset_listing("1h GPU", price, token_id).await.unwrap();
+        assert_eq!(seller.get_active_asset_listings().await.unwrap()[0].price_avax, "2.000000000000000000");
+
+        assert_eq!(revert_message(buyer.buy_listing(listing_id, parse_ether("1").unwrap()).await), "Wrong amount");
+        let Ok(TxResult::Confirmed { id: deal_id }) = buyer.buy_listing(listing_id, price).await else {
+            panic!("buy should confirm online");
+        };
+        assert!(seller.get_active_asset_listings().await.unwrap().is_empty());
+        assert_eq!(seller.get_voucher_owner(token_id).await.unwrap(), MOCK_MARKETPLACE_ADDRESS.to_string());
+        let deals = buyer.get_my_deals(&buyer.get_primary_address()).await.unwrap();
+        assert_eq!((deals[0].status.as_str(), deals[0].role.as_str()), ("active", "buyer"));
+
+        assert_eq!(revert_message(seller.release_deal(deal_id).await), "Only buyer");
+        buyer.release_deal(deal_id).await.unwrap();
+        assert_eq!(balance(&seller).await, parse_ether("3").unwrap());
+        assert_eq!(balance(&buyer).await, parse_ether("3").unwrap());
+        assert_eq!(buyer.get_owned_vouchers(&buyer.get_primary_address()).await.unwrap()[0].voucher_type, "compute");
+
+        buyer.redeem_voucher(token_id).await.unwrap();
+        assert!(buyer.get_owned_vouchers(&buyer.get_primary_address()).await.unwrap().is_empty());
+        assert!(revert_message(buyer.get_voucher_owner(token_id).await).starts_with("ERC721NonexistentToken"));
+    }
+
+    /// During an outage `create_escrow` signs offline; a peer relays the
+    /// signed bytes once the chain is back and the relay claim verifies.
+    /// Refunds follow the Escrow rules, including expiry.
+    #[tokio::test]
+    async fn relays_an_offline_escrow_and_refunds_after_expiry() {
+        let chain = Arc::new(MockChain::new());
+        let depositor = funded_bridge(&chain, "depositor", "5");
+        let payee = funded_bridge(&chain, "payee", "1");
+        let relayer = funded_bridge(&chain, "relayer", "1");
+        let payee_address = payee.get_primary_address();
+        let amount = parse_ether("1").unwrap();
+
+        depositor.refresh_chain_cache(depositor.primary_signer().unwrap().address()).await.unwrap();
+        chain.set_reachable(false);
+        let Ok(TxResult::Queued { queue_id }) = depositor.create_escrow(&payee_address, amount, 0).await else {
+            panic!("create_escrow should queue while the chain is unreachable");
+        };
+        chain.set_reachable(true);
+
+        let queued = depositor.get_pending_relay_txs().into_iter().find(|t| t.id == queue_id).unwrap();
+        let tx_hash = relayer.submit_raw_transaction(&queued.raw_tx_hex).await.unwrap();
+        let entry = depositor.verify_relay_confirmation(&queue_id, "confirmed", Some(&tx_hash), Some("peer")).await.unwrap();
+        assert_eq!(entry.status, "confirmed");
+        assert_eq!(depositor.get_escrow_status(1).await.unwrap()["status"], 1);
+
+        assert_eq!(revert_message(payee.release_escrow(1).await), "Only depositor");
+        assert_eq!(revert_message(payee.refund_escrow(1).await), "Not authorized or not expired");
+        depositor.refund_escrow(1).await.unwrap();
+        assert_eq!(balance(&depositor).await, parse_ether("5").unwrap());
+
+        let expiry = chain.timestamp() + 60;
+        // The failed send marked the node offline; it flips back after two good probes.
+        assert!(depositor.probe_connectivity().await && depositor.probe_connectivity().await);
+        let Ok(TxResult::Confirmed { id }) = depositor.create_escrow(&payee_address, amount, expiry).await else {
+            panic!("create_escrow should confirm online");
+        };
+        chain.advance_time(120);
+        payee.refund_escrow(id).await.unwrap();
+        assert_eq!(depositor.get_escrow_status(id).await.unwrap()["status"], 3);
+        assert_eq!(balance(&depositor).await, parse_ether("5").unwrap());
+    }
+
+    /// A queued tx that would revert right now keeps its place in the queue
+    /// with a warning, and the warning clears once the chain catches up.
+    #[tokio::test]
+    async fn preflight_warns_without_failing_queued_txs() {
+        let chain = Arc::new(MockChain::new());
+        let seller = funded_bridge(&chain, "early-seller", "1");
+        let buyer = funded_bridge(&chain, "early-buyer", "5");
+        let price = parse_ether("1").unwrap();
+
+        buyer.refresh_chain_cache(buyer.primary_signer().unwrap().address()).await.unwrap();
+        chain.set_reachable(false);
+        let Ok(TxResult::Queued { queue_id }) = buyer.buy_listing(1, price).await else {
+            panic!("buy_listing should queue while the chain is unreachable");
+        };
+        chain.set_reachable(true);
+
+        assert_eq!(buyer.preflight_queued_txs().await.unwrap(), 1);
+        let entry = buyer.get_pending_relay_txs().into_iter().find(|t| t.id == queue_id).unwrap();
+        assert_eq!(entry.status, "queued");
+        assert!(entry.revert_warning.is_some());
+
+        let token_id = seller.mint_voucher("compute", "1h GPU").await.unwrap();
+        seller.approve_voucher(token_id).await.unwrap();
+        seller.create_asset_listing("1h GPU", price, token_id).await.unwrap();
+        assert_eq!(buyer.preflight_queued_txs().await.unwrap(), 0);
+        let entry = buyer.get_pending_relay_txs().into_iter().find(|t| t.id == queue_id).unwrap();
+        assert_eq!((entry.status.as_str(), entry.revert_warning), ("queued", None));
+    }
+
+    /// A "confirmed" claim our RPC can't see yet is re-checked instead of
+    /// rejected, credited once the tx shows up, and only counted against
+    /// the relayer after `MAX_UNSEEN_CLAIM_CHECKS` misses. Another peer's
+    /// claims for the same tx are never charged to it.
+    #[tokio::test]
+    async fn rechecks_relay_claims_the_rpc_has_not_seen() {
+        let chain = Arc::new(MockChain::new());
+        let depositor = funded_bridge(&chain, "lagging", "5");
+        let relayer = funded_bridge(&chain, "early-relayer", "1");
+        let payee_address = relayer.get_primary_address();
+        let amount = parse_ether("1").unwrap();
+
+        depositor.refresh_chain_cache(depositor.primary_signer().unwrap().address()).await.unwrap();
+        chain.set_reachable(false);
+        let Ok(TxResult::Queued { queue_id: early }) = depositor.create_escrow(&payee_address, amount, 0).await else {
+            panic!("create_escrow should queue while the chain is unreachable");
+        };
+        let Ok(TxResult::Queued { queue_id: never }) = depositor.create_escrow(&payee_address, amount, 0).await else {
+            panic!("create_escrow should queue while the chain is unreachable");
+        };
+        chain.set_reachable(true);
+
+        for queue_id in [&early, &never] {
+            let entry = depositor.verify_relay_confirmation(queue_id, "confirmed", None, Some("peer")).await.unwrap();
+            assert_eq!(entry.status, "queued");
+            assert_eq!(entry.unseen_claims[0].checks, 1);
+        }
+        assert_eq!(depositor.get_relay_reputation()["peer"].rejected, 0);
+        for _ in 0..MAX_UNSEEN_CLAIM_CHECKS - 1 {
+            depositor.verify_relay_confirmation(&never, "confirmed", None, Some("framer")).await.unwrap();
+        }
+        let entry = depositor.get_pending_relay_txs().into_iter().find(|t| t.id == never).unwrap();
+        assert_eq!(entry.unseen_claims.iter().map(|c| c.checks).collect::<Vec<_>>(), vec![1, 2]);
+
+        let queued = depositor.get_pending_relay_txs().into_iter().find(|t| t.id == early).unwrap();
+        relayer.submit_raw_transaction(&queued.raw_tx_hex).await.unwrap();
+        depositor.preflight_queued_txs().await.unwrap();
+        let entries = depositor.get_pending_relay_txs();
+        let early_entry = entries.iter().find(|t| t.id == early).unwrap();
+        assert_eq!(early_entry.status, "confirmed");
+        assert!(early_entry.unseen_claims.is_empty());
+        let never_entry = entries.iter().find(|t| t.id == never).unwrap();
+        assert_eq!(never_entry.unseen_claims[0].checks, 2);
+        assert_eq!(depositor.get_relay_reputation()["peer"].verified, 1);
+        assert_eq!(depositor.get_relay_reputation()["framer"].rejected, 1, "the framer's own third miss");
+        assert_eq!(depositor.get_relay_reputation()["peer"].rejected, 0);
+
+        depositor.preflight_queued_txs().await.unwrap();
+        let never_entry = depositor.get_pending_relay_txs().into_iter().find(|t| t.id == never).unwrap();
+        assert_eq!(never_entry.status, "queued");
+        assert!(never_entry.unseen_claims.is_empty());
+        assert_eq!(depositor.get_relay_reputation()["peer"].rejected, 1);
+    }
 }
@@ -0,0 +1,119 @@
+use alloy::primitives::{keccak256, Address, Signature, B256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::blockchain_bridge::AssetListingView;
+use crate::error::{AppError, AppResult};
+
+/// Snapshots claiming to be from further in the future than this are
+/// rejected, so a peer with a bad clock can't pin its catalogue as "newest".
+const MAX_CLOCK_SKEW: Duration = Duration::minutes(5);
+
+/// Where a catalogue came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CatalogueSource {
+    /// Fetched from the Marketplace contract by this node.
+    Rpc,
+    /// Taken from a peer's signed mesh snapshot. The signature only proves
+    /// which identity shared it, not that the listings are still on-chain.
+    Mesh { signer: String },
+}
+
+/// Active Marketplace listings as of `fetched_at`. Persisted after every
+/// successful fetch and served from disk while the RPC is unreachable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListingCatalogue {
+    pub listings: Vec<AssetListingView>,
+    pub fetched_at: DateTime<Utc>,
+    pub source: CatalogueSource,
+    /// True when this is the cached copy because the RPC couldn't be reached.
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// A catalogue as shared over the mesh (`catalogue_snapshot` intents),
+/// EIP-191 signed by the sharing node's primary identity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedCatalogue {
+    pub listings: Vec<AssetListingView>,
+    pub fetched_at: DateTime<Utc>,
+    pub signer: String,
+    pub signature: String,
+}
+
+impl SignedCatalogue {
+    fn digest(listings: &[AssetListingView], fetched_at: &DateTime<Utc>) -> AppResult<B256> {
+        Ok(keccak256(serde_json::to_vec(&(fetched_at, listings))?))
+    }
+
+    pub fn sign(listings: Vec<AssetListingView>, fetched_at: DateTime<Utc>, signer: &PrivateKeySigner) -> AppResult<Self> {
+        let digest = Self::digest(&listings, &fetched_at)?;
+        let signature = signer.sign_message_sync(digest.as_slice()).map_err(AppError::crypto)?;
+        Ok(SignedCatalogue {
+            listings,
+            fetched_at,
+            signer: signer.address().to_string(),
+            signature: signature.to_string(),
+        })
+    }
+
+    /// Checks the signature matches `signer` and the timestamp is plausible.
+    pub fn verify(&self) -> AppResult<()> {
+        if self.fetched_at > Utc::now() + MAX_CLOCK_SKEW {
+            return Err(AppError::invalid_input("catalogue snapshot is dated in the future"));
+        }
+        let digest = Self::digest(&self.listings, &self.fetched_at)?;
+        let signature = Signature::from_str(&self.signature).map_err(AppError::invalid_input)?;
+        let recovered = signature.recover_address_from_msg(digest.as_slice()).map_err(AppError::invalid_input)?;
+        if recovered != Address::from_str(&self.signer)? {
+            return Err(AppError::invalid_input("catalogue snapshot signature does not match its signer"));
+        }
+        Ok(())
+    }
+
+    pub fn into_catalogue(self) -> ListingCatalogue {
+        ListingCatalogue {
+            listings: self.listings,
+            fetched_at: self.fetched_at,
+            source: CatalogueSource::Mesh { signer: self.signer },
+            stale: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_signed_snapshots() {
+        let listing = AssetListingView {
+            id: 1,
+            seller: Address::repeat_byte(0x11).to_string(),
+            description: "desk lamp".to_string(),
+            price_wei: "50000000000000000".to_string(),
+            price_avax: "0.05".to_string(),
+            token_id: 7,
+        };
+        let signer = PrivateKeySigner::random();
+        let snapshot = SignedCatalogue::sign(vec![listing], Utc::now(), &signer).unwrap();
+        snapshot.verify().unwrap();
+
+        let mut tampered = snapshot.clone();
+        tampered.listings[0].price_avax = "0.0001".to_string();
+        assert!(tampered.verify().is_err());
+
+        let mut impostor = snapshot.clone();
+        impostor.signer = PrivateKeySigner::random().address().to_string();
+        assert!(impostor.verify().is_err());
+
+        let future = SignedCatalogue::sign(Vec::new(), Utc::now() + Duration::hours(1), &signer).unwrap();
+        assert!(future.verify().is_err());
+
+        let catalogue = snapshot.into_catalogue();
+        assert_eq!(catalogue.source, CatalogueSource::Mesh { signer: signer.address().to_string() });
+    }
+}
@@ -0,0 +1,253 @@
+use alloy::{
+    eips::{eip2718::Encodable2718, BlockId},
+    network::TransactionBuilder,
+    primitives::{keccak256, Address, Bytes, Log, B256, U256},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    signers::local::PrivateKeySigner,
+    sol_types::SolEvent,
+    trie::{proof::verify_proof, Nibbles, TrieAccount},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::rpc_pool::{AttemptError, EndpointStatus, RpcPool, RpcPoolError, RpcTimeouts};
+
+/// What the bridge needs from a mined transaction.
+#[derive(Debug, Clone)]
+pub struct ChainReceipt {
+    pub transaction_hash: B256,
+    pub status: bool,
+    pub logs: Vec<Log>,
+}
+
+impl ChainReceipt {
+    /// The first log that decodes as event `E`.
+    pub fn event<E: SolEvent>(&self) -> Option<E> {
+        self.logs.iter().find_map(|log| E::decode_log(log).ok()).map(|log| log.data)
+    }
+}
+
+impl From<TransactionReceipt> for ChainReceipt {
+    fn from(receipt: TransactionReceipt) -> Self {
+        ChainReceipt {
+            transaction_hash: receipt.transaction_hash,
+            status: receipt.status(),
+            logs: receipt.logs().iter().map(|l| l.inner.clone()).collect(),
+        }
+    }
+}
+
+/// An account's state at one block, with the `eth_getProof` Merkle-Patricia
+/// proof that ties it to that block's state root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountProof {
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub block_timestamp: u64,
+    pub state_root: B256,
+    pub address: Address,
+    pub balance: U256,
+    pub nonce: u64,
+    pub storage_hash: B256,
+    pub code_hash: B256,
+    /// Trie nodes from the state root down to the account's leaf.
+    pub proof: Vec<Bytes>,
+}
+
+impl AccountProof {
+    /// Checks that `proof` shows exactly this account state under
+    /// `state_root`. The block hash and state root themselves are as the
+    /// RPC reported them.
+    pub fn verify(&self) -> Result<(), String> {
+        let account = TrieAccount {
+            nonce: self.nonce,
+            balance: self.balance,
+            storage_root: self.storage_hash,
+            code_hash: self.code_hash,
+        };
+        let key = Nibbles::unpack(keccak256(self.address));
+        verify_proof(self.state_root, key, Some(alloy::rlp::encode(account)), &self.proof).map_err(|e| e.to_string())
+    }
+}
+
+/// Everything `BlockchainBridge` asks of the chain. Contract calls go
+/// through here as ABI-encoded calldata, so the bridge's encoding, offline
+/// signing and revert decoding are the same whichever backend answers.
+///
+/// Implemented by `RpcPool` for the real network and by `MockChain`, an
+/// in-memory simulation of our three contracts for demos and tests. Errors
+/// keep `RpcPoolError`'s meaning: `Unreachable` means nothing answered,
+/// `Rejected` carries revert data when the chain refused the call.
+#[async_trait]
+pub trait ChainBackend: Send + Sync {
+    async fn balance(&self, address: Address) -> Result<U256, RpcPoolError>;
+
+    /// `eth_getProof` for `address` at the latest block.
+    async fn account_proof(&self, address: Address) -> Result<AccountProof, RpcPoolError>;
+
+    /// `eth_getProof` for `address` at the block with `block_hash`, or
+    /// `None` if the chain doesn't know that block.
+    async fn account_proof_at(&self, address: Address, block_hash: B256) -> Result<Option<AccountProof>, RpcPoolError>;
+
+    /// Timestamp of a block, or `None` if the chain doesn't know it.
+    async fn block_timestamp(&self, block: BlockId) -> Result<Option<u64>, RpcPoolError>;
+
+    /// Next nonce for `address`, counting pending transactions.
+    async fn pending_nonce(&self, address: Address) -> Result<u64, RpcPoolError>;
+
+    async fn gas_price(&self) -> Result<u128, RpcPoolError>;
+
+    /// `eth_call`: executes against current state without changing it.
+    async fn call(&self, from: Option<Address>, to: Address, input: Bytes, value: U256) -> Result<Bytes, RpcPoolError>;
+
+    /// Signs with `signer`, sends, and waits for the receipt within `limit`.
+    async fn send(
+        &self,
+        signer: PrivateKeySigner,
+        to: Address,
+        input: Bytes,
+        value: U256,
+        limit: Duration,
+    ) -> Result<ChainReceipt, RpcPoolError>;
+
+    /// Broadcasts an already-signed transaction and waits for its receipt.
+    async fn send_raw(&self, raw: Bytes) -> Result<ChainReceipt, RpcPoolError>;
+
+    /// The signed bytes of a transaction the chain knows about.
+    async fn raw_transaction(&self, hash: B256) -> Result<Option<Bytes>, RpcPoolError>;
+
+    /// Whether a mined transaction succeeded; `None` if it isn't mined.
+    async fn receipt_status(&self, hash: B256) -> Result<Option<bool>, RpcPoolError>;
+
+    /// Which endpoint answered a cheap liveness check, if any.
+    async fn probe(&self, limit: Duration) -> Option<String>;
+
+    fn timeouts(&self) -> RpcTimeouts;
+
+    fn status(&self) -> Vec<EndpointStatus>;
+
+    fn primary_url(&self) -> String;
+}
+
+impl RpcPool {
+    /// The header of `block`, then `eth_getProof` pinned to its hash so the
+    /// proof and the state root are from the same block. `None` for a block
+    /// hash the endpoint doesn't know.
+    async fn account_proof_in(&self, address: Address, block: BlockId) -> Result<Option<AccountProof>, RpcPoolError> {
+        self.read(|p| async move {
+            let header = match p.get_block(block).await? {
+                Some(found) => found.header,
+                None if matches!(block, BlockId::Hash(_)) => return Ok(None),
+                None => return Err(AttemptError::endpoint_fault("no latest block".to_string())),
+            };
+            let account = p.get_proof(address, Vec::new()).block_id(BlockId::hash(header.hash)).await?;
+            Ok(Some(AccountProof {
+                block_hash: header.hash,
+                block_number: header.number,
+                block_timestamp: header.timestamp,
+                state_root: header.state_root,
+                address,
+                balance: account.balance,
+                nonce: account.nonce,
+                storage_hash: account.storage_hash,
+                code_hash: account.code_hash,
+                proof: account.account_proof,
+            }))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ChainBackend for RpcPool {
+    async fn balance(&self, address: Address) -> Result<U256, RpcPoolError> {
+        self.read(|p| async move { Ok(p.get_balance(address).await?) }).await
+    }
+
+    async fn account_proof(&self, address: Address) -> Result<AccountProof, RpcPoolError> {
+        let proof = self.account_proof_in(address, BlockId::latest()).await?;
+        proof.ok_or_else(|| RpcPoolError::Rejected { message: "no latest block".to_string(), revert_data: None })
+    }
+
+    async fn account_proof_at(&self, address: Address, block_hash: B256) -> Result<Option<AccountProof>, RpcPoolError> {
+        self.account_proof_in(address, BlockId::hash(block_hash)).await
+    }
+
+    async fn block_timestamp(&self, block: BlockId) -> Result<Option<u64>, RpcPoolError> {
+        let block = self.read(|p| async move { Ok(p.get_block(block).await?) }).await?;
+        Ok(block.map(|b| b.header.timestamp))
+    }
+
+    async fn pending_nonce(&self, address: Address) -> Result<u64, RpcPoolError> {
+        self.read(|p| async move { Ok(p.get_transaction_count(address).pending().await?) }).await
+    }
+
+    async fn gas_price(&self) -> Result<u128, RpcPoolError> {
+        self.read(|p| async move { Ok(p.get_gas_price().await?) }).await
+    }
+
+    async fn call(&self, from: Option<Address>, to: Address, input: Bytes, value: U256) -> Result<Bytes, RpcPoolError> {
+        let mut tx = TransactionRequest::default().with_to(to).with_input(input).with_value(value);
+        if let Some(from) = from {
+            tx = tx.with_from(from);
+        }
+        self.read(|p| {
+            let tx = tx.clone();
+            async move { Ok(p.call(tx).await?) }
+        })
+        .await
+    }
+
+    async fn send(
+        &self,
+        signer: PrivateKeySigner,
+        to: Address,
+        input: Bytes,
+        value: U256,
+        limit: Duration,
+    ) -> Result<ChainReceipt, RpcPoolError> {
+        let tx = TransactionRequest::default().with_to(to).with_input(input).with_value(value);
+        let receipt = self
+            .write(signer, limit, |p| async move { Ok(p.send_transaction(tx).await?.get_receipt().await?) })
+            .await?;
+        Ok(receipt.into())
+    }
+
+    async fn send_raw(&self, raw: Bytes) -> Result<ChainReceipt, RpcPoolError> {
+        let receipt = self
+            .relay(|p| {
+                let raw = raw.clone();
+                async move { Ok(p.send_raw_transaction(&raw).await?.get_receipt().await?) }
+            })
+            .await?;
+        Ok(receipt.into())
+    }
+
+    async fn raw_transaction(&self, hash: B256) -> Result<Option<Bytes>, RpcPoolError> {
+        let tx = self.read(|p| async move { Ok(p.get_transaction_by_hash(hash).await?) }).await?;
+        Ok(tx.map(|tx| tx.inner.inner().encoded_2718().into()))
+    }
+
+    async fn receipt_status(&self, hash: B256) -> Result<Option<bool>, RpcPoolError> {
+        let receipt = self.read(|p| async move { Ok(p.get_transaction_receipt(hash).await?) }).await?;
+        Ok(receipt.map(|r| r.status()))
+    }
+
+    async fn probe(&self, limit: Duration) -> Option<String> {
+        RpcPool::probe(self, limit).await
+    }
+
+    fn timeouts(&self) -> RpcTimeouts {
+        self.timeouts
+    }
+
+    fn status(&self) -> Vec<EndpointStatus> {
+        RpcPool::status(self)
+    }
+
+    fn primary_url(&self) -> String {
+        RpcPool::primary_url(self).to_string()
+    }
+}
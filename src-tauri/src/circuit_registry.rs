@@ -0,0 +1,201 @@
+use alloy::primitives::{keccak256, B256};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{PoisonError, RwLock};
+
+use crate::error::{AppError, AppResult};
+use crate::proving_backend::{CompiledCircuit, ProvingBackendKind};
+
+const REGISTRY_FILE: &str = "registry.json";
+const ARTIFACT_FILE: &str = "artifact.json";
+const VERIFICATION_KEY_FILE: &str = "vk";
+
+/// `name@` and the first 8 bytes of the source hash, in hex. Any change to
+/// the circuit's source gives it a new id.
+pub fn circuit_id(name: &str, source_hash: B256) -> String {
+    format!("{}@{}", name, hex::encode(&source_hash[..8]))
+}
+
+/// One compiled version of a circuit. Proofs name the circuit they were
+/// made with by `id`, and are only checked against the verification key
+/// pinned here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CircuitRecord {
+    pub id: String,
+    pub name: String,
+    pub backend: ProvingBackendKind,
+    /// `ProvingBackend::circuit_hash` of the source it was compiled from.
+    pub source_hash: B256,
+    /// keccak256 of the compiled program, for backends that have one.
+    pub artifact_hash: Option<B256>,
+    /// keccak256 of the verification key, pinned when first compiled.
+    pub vk_hash: B256,
+    pub compiled_at: DateTime<Utc>,
+}
+
+/// Result of `check_circuit_integrity` for one circuit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CircuitIntegrity {
+    pub id: String,
+    /// The stored program still hashes to `artifact_hash` (or there is none).
+    pub artifact_intact: bool,
+    /// The stored verification key still hashes to the pinned `vk_hash`.
+    pub verification_key_intact: bool,
+    /// The backend's circuit source on this node is still this version;
+    /// `false` for superseded circuits, whose proofs still verify.
+    pub source_current: bool,
+}
+
+/// Compiled circuits and their verification keys under `<data dir>/circuits`,
+/// one directory per circuit id, indexed by `registry.json`.
+pub struct CircuitRegistry {
+    dir: PathBuf,
+    records: RwLock<BTreeMap<String, CircuitRecord>>,
+}
+
+impl CircuitRegistry {
+    /// Opens the registry in `dir`. A missing or unreadable index starts
+    /// empty; circuits are compiled again as they're needed.
+    pub fn load(dir: PathBuf) -> Self {
+        let records = match fs::read_to_string(dir.join(REGISTRY_FILE)) {
+            Ok(raw) => serde_json::from_str::<Vec<CircuitRecord>>(&raw).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "circuit registry is unreadable, starting empty");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        CircuitRegistry {
+            dir,
+            records: RwLock::new(records.into_iter().map(|r| (r.id.clone(), r)).collect()),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<CircuitRecord> {
+        self.records.read().unwrap_or_else(PoisonError::into_inner).get(id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<CircuitRecord> {
+        self.records.read().unwrap_or_else(PoisonError::into_inner).values().cloned().collect()
+    }
+
+    /// Stores a compiled circuit. Compiling a registered circuit again must
+    /// give the verification key that was pinned for it, unless `repin`:
+    /// a different key means the toolchain changed what proofs it accepts.
+    pub fn register(
+        &self,
+        name: &str,
+        backend: ProvingBackendKind,
+        source_hash: B256,
+        compiled: CompiledCircuit,
+        repin: bool,
+    ) -> AppResult<CircuitRecord> {
+        let id = circuit_id(name, source_hash);
+        let vk_hash = keccak256(&compiled.verification_key);
+        let mut records = self.records.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(pinned) = records.get(&id) {
+            let check = self.check(pinned);
+            if pinned.vk_hash == vk_hash && check.verification_key_intact && check.artifact_intact {
+                return Ok(pinned.clone());
+            }
+            if !repin && pinned.vk_hash != vk_hash {
+                return Err(AppError::Proof(format!(
+                    "circuit {} compiled to verification key {}, but {} is pinned; recompile with repin to accept it",
+                    id, vk_hash, pinned.vk_hash
+                )));
+            }
+        }
+
+        let dir = self.dir.join(&id);
+        fs::create_dir_all(&dir)?;
+        if let Some(artifact) = &compiled.artifact {
+            fs::write(dir.join(ARTIFACT_FILE), artifact)?;
+        }
+        fs::write(dir.join(VERIFICATION_KEY_FILE), &compiled.verification_key)?;
+        let record = CircuitRecord {
+            id: id.clone(),
+            name: name.to_string(),
+            backend,
+            source_hash,
+            artifact_hash: compiled.artifact.as_ref().map(keccak256),
+            vk_hash,
+            compiled_at: Utc::now(),
+        };
+        records.insert(id, record.clone());
+        let index: Vec<&CircuitRecord> = records.values().collect();
+        fs::write(self.dir.join(REGISTRY_FILE), serde_json::to_string_pretty(&index)?)?;
+        tracing::info!(circuit = %record.id, vk_hash = %record.vk_hash, "registered circuit");
+        Ok(record)
+    }
+
+    /// The circuit's verification key, refusing one that no longer matches
+    /// its pinned hash.
+    pub fn verification_key(&self, record: &CircuitRecord) -> AppResult<Vec<u8>> {
+        let key = fs::read(self.dir.join(&record.id).join(VERIFICATION_KEY_FILE))?;
+        if keccak256(&key) != record.vk_hash {
+            return Err(AppError::Proof(format!("verification key of circuit {} doesn't match its pinned hash", record.id)));
+        }
+        Ok(key)
+    }
+
+    /// Rehashes the stored files. `source_current` is left `false`; only
+    /// the backend can tell.
+    pub fn check(&self, record: &CircuitRecord) -> CircuitIntegrity {
+        let dir = self.dir.join(&record.id);
+        let hash_of = |file: &str| fs::read(dir.join(file)).ok().map(keccak256);
+        CircuitIntegrity {
+            id: record.id.clone(),
+            artifact_intact: record.artifact_hash.is_none_or(|pinned| hash_of(ARTIFACT_FILE) == Some(pinned)),
+            verification_key_intact: hash_of(VERIFICATION_KEY_FILE) == Some(record.vk_hash),
+            source_current: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compiled(vk: &[u8]) -> CompiledCircuit {
+        CompiledCircuit { artifact: Some(b"{\"bytecode\":\"\"}".to_vec()), verification_key: vk.to_vec() }
+    }
+
+    /// Registered circuits survive a reload, keep their pinned verification
+    /// key, and tampered files are caught.
+    #[test]
+    fn pins_verification_keys_and_detects_tampering() {
+        let dir = std::env::temp_dir().join(format!("cabalmesh_circuits_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let registry = CircuitRegistry::load(dir.clone());
+        let source_hash = keccak256("circuit source");
+
+        let record = registry.register("bid", ProvingBackendKind::Nargo, source_hash, compiled(b"vk-1"), false).unwrap();
+        assert_eq!(record.id, circuit_id("bid", source_hash));
+        assert!(record.id.starts_with("bid@") && record.id.len() == "bid@".len() + 16);
+        assert_eq!(registry.register("bid", ProvingBackendKind::Nargo, source_hash, compiled(b"vk-1"), false).unwrap(), record);
+        assert!(matches!(
+            registry.register("bid", ProvingBackendKind::Nargo, source_hash, compiled(b"vk-2"), false),
+            Err(AppError::Proof(_))
+        ));
+
+        let reloaded = CircuitRegistry::load(dir.clone());
+        assert_eq!(reloaded.list(), vec![record.clone()]);
+        assert_eq!(reloaded.verification_key(&record).unwrap(), b"vk-1");
+        let check = reloaded.check(&record);
+        assert!(check.artifact_intact && check.verification_key_intact);
+
+        fs::write(dir.join(&record.id).join(VERIFICATION_KEY_FILE), b"vk-x").unwrap();
+        fs::write(dir.join(&record.id).join(ARTIFACT_FILE), b"{}").unwrap();
+        assert!(reloaded.verification_key(&record).is_err());
+        let check = reloaded.check(&record);
+        assert!(!check.artifact_intact && !check.verification_key_intact);
+
+        let repinned = reloaded.register("bid", ProvingBackendKind::Nargo, source_hash, compiled(b"vk-2"), true).unwrap();
+        assert_eq!(repinned.vk_hash, keccak256(b"vk-2"));
+        assert_eq!(reloaded.verification_key(&repinned).unwrap(), b"vk-2");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
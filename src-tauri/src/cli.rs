@@ -0,0 +1,409 @@
+use alloy::primitives::utils::parse_ether;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::blockchain_bridge::BlockchainBridge;
+use crate::error::{AppError, AppResult};
+use crate::logging;
+use crate::profile::Profile;
+use crate::settings::SettingsStore;
+
+/// Headless access to the same bridge operations the app exposes as Tauri
+/// commands, against the same profile's data dir, keychain entry and settings.
+#[derive(Debug, Parser)]
+#[command(name = "cabalmesh-cli", version, about)]
+pub struct Cli {
+    /// Named profile (same as the app's `--profile` / `CABALMESH_PROFILE`).
+    #[arg(long, global = true, env = "CABALMESH_PROFILE")]
+    profile: Option<String>,
+    /// Data directory override (same as the app's `--data-dir` / `CABALMESH_DATA_DIR`).
+    #[arg(long, global = true, env = "CABALMESH_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+    /// Print results (and errors, as `{code, message, details}`) as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Local signing identities
+    #[command(subcommand)]
+    Identity(IdentityCommand),
+    /// Voucher NFTs
+    #[command(subcommand)]
+    Voucher(VoucherCommand),
+    /// Marketplace listings
+    #[command(subcommand)]
+    Listing(ListingCommand),
+    /// Marketplace deals (escrowed purchases)
+    #[command(subcommand)]
+    Deal(DealCommand),
+    /// Direct escrows
+    #[command(subcommand)]
+    Escrow(EscrowCommand),
+    /// Offline-signed transactions waiting for a mesh relay, and relaying others'
+    #[command(subcommand)]
+    RelayQueue(RelayQueueCommand),
+    /// Signed content delivered with vouchers
+    #[command(subcommand)]
+    Content(ContentCommand),
+    /// Encrypted balance snapshot
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+    /// Write a redacted bug-report bundle (settings, RPC health, recent logs)
+    Diagnostics,
+}
+
+#[derive(Debug, Subcommand)]
+enum IdentityCommand {
+    /// List identities (the first is the primary signer)
+    List,
+    /// Generate a new identity
+    New {
+        #[arg(long)]
+        alias: String,
+        #[arg(long, default_value = "🦊")]
+        emoji: String,
+    },
+    /// Print the primary address
+    Address,
+}
+
+#[derive(Debug, Subcommand)]
+enum VoucherCommand {
+    Mint {
+        #[arg(long = "type")]
+        voucher_type: String,
+        #[arg(long)]
+        description: String,
+    },
+    /// Approve the marketplace to transfer a voucher
+    Approve { token_id: u64 },
+    Redeem { token_id: u64 },
+    Owner { token_id: u64 },
+    /// Vouchers owned by `--owner` (default: the primary address)
+    List {
+        #[arg(long)]
+        owner: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ListingCommand {
+    /// Active listings
+    List,
+    /// Active listings, falling back to the cached catalogue when the RPC is down
+    Catalogue,
+    Create {
+        #[arg(long)]
+        description: String,
+        #[arg(long)]
+        price_avax: String,
+        #[arg(long)]
+        token_id: u64,
+    },
+    Buy {
+        listing_id: u64,
+        #[arg(long)]
+        price_avax: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum DealCommand {
+    /// Deals where `--address` (default: the primary address) is buyer or seller
+    List {
+        #[arg(long)]
+        address: Option<String>,
+    },
+    Release { deal_id: u64 },
+    Refund { deal_id: u64 },
+}
+
+#[derive(Debug, Subcommand)]
+enum EscrowCommand {
+    Create {
+        #[arg(long)]
+        payee: String,
+        #[arg(long)]
+        amount_avax: String,
+        /// Unix timestamp after which the payer may refund (0: none)
+        #[arg(long, default_value_t = 0)]
+        expiry_unix: u64,
+    },
+    Release { escrow_id: u64 },
+    Refund { escrow_id: u64 },
+    Status { escrow_id: u64 },
+}
+
+#[derive(Debug, Subcommand)]
+enum RelayQueueCommand {
+    /// Our queued offline-signed transactions
+    List,
+    /// Simulate every queued transaction against the chain now
+    Preflight,
+    /// Broadcast a peer's signed transaction
+    Submit { raw_tx_hex: String },
+    /// Check a relay peer's confirmation claim against the chain
+    Verify {
+        queue_id: String,
+        #[arg(long)]
+        status: String,
+        #[arg(long)]
+        tx_hash: Option<String>,
+        #[arg(long)]
+        relayer: Option<String>,
+    },
+    /// Transactions we relayed for others
+    History,
+    /// Per-relayer confirmation record
+    Reputation,
+}
+
+#[derive(Debug, Subcommand)]
+enum ContentCommand {
+    /// Sign text with the primary identity without storing it
+    Sign { text: String },
+    /// Sign text and store it as the content delivered with `token_id`
+    Publish {
+        token_id: u64,
+        #[arg(long)]
+        text: String,
+    },
+    /// Content we published for `token_id`
+    Show { token_id: u64 },
+    /// Verify and store content received for `token_id`
+    Receive {
+        token_id: u64,
+        #[arg(long)]
+        text: String,
+        #[arg(long)]
+        signature: String,
+        #[arg(long)]
+        seller: String,
+    },
+    /// Content we received for `token_id`
+    Received { token_id: u64 },
+    /// Extract the first page of a PDF as text
+    ExtractPdf { path: PathBuf },
+}
+
+#[derive(Debug, Subcommand)]
+enum SnapshotCommand {
+    /// The last saved snapshot
+    Show,
+    /// Fetch the balance from the RPC and save a fresh snapshot
+    Sync {
+        #[arg(long, default_value = "")]
+        wallet: String,
+    },
+    Delete,
+}
+
+/// Entry point for the `cabalmesh-cli` binary.
+pub fn main() -> ExitCode {
+    // Before parsing, so `.env` feeds clap's `env =` fallbacks the same way
+    // it feeds the app's profile resolution.
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+
+    let json = cli.json;
+    let profile = Profile::resolve(cli.profile.clone(), cli.data_dir.clone());
+    // Only warnings reach stderr so `--json` output stays parseable; the
+    // profile's log files get everything, same as the app.
+    let _log_guard = profile
+        .as_ref()
+        .ok()
+        .and_then(|profile| logging::init(&profile.data_dir, tracing::Level::WARN.into()).ok());
+    let result = profile.and_then(|profile| {
+        tokio::runtime::Runtime::new()
+            .map_err(AppError::from)
+            .and_then(|rt| rt.block_on(execute(cli, profile)))
+    });
+    match result {
+        Ok(value) => {
+            print_value(&value, json);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            if json {
+                eprintln!("{}", serde_json::to_string_pretty(&e).unwrap_or_else(|_| e.to_string()));
+            } else {
+                eprintln!("error [{}]: {}", e.code(), e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn execute(cli: Cli, profile: Profile) -> AppResult<Value> {
+    let settings = SettingsStore::load(&profile.data_dir).get();
+    let bridge = BlockchainBridge::new(&settings, profile.data_dir.clone());
+
+    match cli.command {
+        Command::Identity(cmd) => match cmd {
+            IdentityCommand::List => to_json(bridge.get_identity_views()?),
+            IdentityCommand::New { alias, emoji } => to_json(bridge.generate_new_identity(alias, emoji)?),
+            IdentityCommand::Address => to_json(bridge.get_primary_address()),
+        },
+        Command::Voucher(cmd) => match cmd {
+            VoucherCommand::Mint { voucher_type, description } => {
+                to_json(bridge.mint_voucher(&voucher_type, &description).await?)
+            }
+            VoucherCommand::Approve { token_id } => to_json(bridge.approve_voucher(token_id).await?),
+            VoucherCommand::Redeem { token_id } => to_json(bridge.redeem_voucher(token_id).await?),
+            VoucherCommand::Owner { token_id } => to_json(bridge.get_voucher_owner(token_id).await?),
+            VoucherCommand::List { owner } => {
+                let owner = owner.unwrap_or_else(|| bridge.get_primary_address());
+                to_json(bridge.get_owned_vouchers(&owner).await?)
+            }
+        },
+        Command::Listing(cmd) => match cmd {
+            ListingCommand::List => to_json(bridge.get_active_asset_listings().await?),
+            ListingCommand::Catalogue => to_json(bridge.get_listing_catalogue().await?),
+            ListingCommand::Create { description, price_avax, token_id } => {
+                let price_wei = parse_ether(&price_avax).map_err(AppError::invalid_input)?;
+                to_json(bridge.create_asset_listing(&description, price_wei, token_id).await?)
+            }
+            ListingCommand::Buy { listing_id, price_avax } => {
+                let price_wei = parse_ether(&price_avax).map_err(AppError::invalid_input)?;
+                to_json(bridge.buy_listing(listing_id, price_wei).await?)
+            }
+        },
+        Command::Deal(cmd) => match cmd {
+            DealCommand::List { address } => {
+                let address = address.unwrap_or_else(|| bridge.get_primary_address());
+                to_json(bridge.get_my_deals(&address).await?)
+            }
+            DealCommand::Release { deal_id } => to_json(bridge.release_deal(deal_id).await?),
+            DealCommand::Refund { deal_id } => to_json(bridge.refund_deal(deal_id).await?),
+        },
+        Command::Escrow(cmd) => match cmd {
+            EscrowCommand::Create { payee, amount_avax, expiry_unix } => {
+                let amount_wei = parse_ether(&amount_avax).map_err(AppError::invalid_input)?;
+                to_json(bridge.create_escrow(&payee, amount_wei, expiry_unix).await?)
+            }
+            EscrowCommand::Release { escrow_id } => to_json(bridge.release_escrow(escrow_id).await?),
+            EscrowCommand::Refund { escrow_id } => to_json(bridge.refund_escrow(escrow_id).await?),
+            EscrowCommand::Status { escrow_id } => bridge.get_escrow_status(escrow_id).await,
+        },
+        Command::RelayQueue(cmd) => match cmd {
+            RelayQueueCommand::List => to_json(bridge.get_pending_relay_txs()),
+            RelayQueueCommand::Preflight => {
+                bridge.preflight_queued_txs().await?;
+                to_json(bridge.get_pending_relay_txs())
+            }
+            RelayQueueCommand::Submit { raw_tx_hex } => to_json(bridge.submit_raw_transaction(&raw_tx_hex).await?),
+            RelayQueueCommand::Verify { queue_id, status, tx_hash, relayer } => to_json(
+                bridge
+                    .verify_relay_confirmation(&queue_id, &status, tx_hash.as_deref(), relayer.as_deref())
+                    .await?,
+            ),
+            RelayQueueCommand::History => to_json(bridge.get_relayed_history()),
+            RelayQueueCommand::Reputation => to_json(bridge.get_relay_reputation()),
+        },
+        Command::Content(cmd) => match cmd {
+            ContentCommand::Sign { text } => to_json(bridge.sign_content(&text)?),
+            ContentCommand::Publish { token_id, text } => {
+                let record = bridge.sign_content(&text)?;
+                bridge.store_content(token_id, record)?;
+                to_json(bridge.get_content(token_id))
+            }
+            ContentCommand::Show { token_id } => to_json(bridge.get_content(token_id)),
+            ContentCommand::Receive { token_id, text, signature, seller } => {
+                to_json(bridge.receive_content(token_id, &text, &signature, &seller)?)
+            }
+            ContentCommand::Received { token_id } => to_json(bridge.get_received_content(token_id)),
+            ContentCommand::ExtractPdf { path } => to_json(bridge.extract_pdf_text(std::fs::read(path)?)?),
+        },
+        Command::Snapshot(cmd) => match cmd {
+            SnapshotCommand::Show => to_json(bridge.get_latest_snapshot()?),
+            SnapshotCommand::Sync { wallet } => to_json(bridge.sync_state(&wallet).await?),
+            SnapshotCommand::Delete => {
+                bridge.delete_snapshot()?;
+                Ok(Value::Null)
+            }
+        },
+        Command::Diagnostics => {
+            let context = serde_json::json!({
+                "profile": profile.label(),
+                "settings": settings,
+                "rpc_health": bridge.get_rpc_health(),
+            });
+            to_json(logging::export_diagnostics(&profile.data_dir, context)?)
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: T) -> AppResult<Value> {
+    Ok(serde_json::to_value(value)?)
+}
+
+fn print_value(value: &Value, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+        return;
+    }
+    match value {
+        Value::Null => {}
+        Value::Array(items) if items.is_empty() => println!("(none)"),
+        Value::Array(items) => {
+            for item in items {
+                println!("{}", one_line(item));
+            }
+        }
+        Value::Object(map) => {
+            for (key, v) in map {
+                println!("{}: {}", key, one_line(v));
+            }
+        }
+        other => println!("{}", one_line(other)),
+    }
+}
+
+/// Scalars bare, objects as `key=value` pairs, anything nested as compact JSON.
+fn one_line(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| match v {
+                Value::String(s) => format!("{}={}", k, s),
+                other => format!("{}={}", k, other),
+            })
+            .collect::<Vec<_>>()
+            .join("  "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn parses_global_flags_after_subcommands() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "cabalmesh-cli", "listing", "create", "--description", "Desk lamp", "--price-avax", "0.5",
+            "--token-id", "7", "--profile", "node2", "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.profile.as_deref(), Some("node2"));
+        assert!(matches!(
+            cli.command,
+            Command::Listing(ListingCommand::Create { token_id: 7, ref price_avax, .. }) if price_avax == "0.5"
+        ));
+
+        assert!(Cli::try_parse_from(["cabalmesh-cli", "relay-queue", "list"]).is_ok());
+        assert!(Cli::try_parse_from(["cabalmesh-cli", "deal", "release"]).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::blockchain_bridge::BlockchainBridge;
+
+/// Consecutive probes that must agree before a known state flips, so one
+/// dropped request doesn't send every write offline and one lucky answer
+/// doesn't bring them back.
+const OFFLINE_AFTER: u32 = 2;
+const ONLINE_AFTER: u32 = 2;
+/// Probe less often while things work, more often while waiting to reconnect.
+const PROBE_INTERVAL_ONLINE: Duration = Duration::from_secs(15);
+const PROBE_INTERVAL_OFFLINE: Duration = Duration::from_secs(5);
+/// Per-endpoint limit for a probe; well under the write timeout so the
+/// monitor notices an outage before a send would have to wait one out.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    /// Nothing probed yet; sends still try the RPC first.
+    Unknown,
+    Online,
+    Offline,
+}
+
+/// Payload of the `connectivity-changed` event and `get_connectivity` command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectivityStatus {
+    pub state: Reachability,
+    pub since: DateTime<Utc>,
+    /// The endpoint that answered the latest successful probe.
+    pub endpoint: Option<String>,
+}
+
+/// Whether the configured RPC endpoints can be reached, as judged from
+/// periodic probes and failed sends, with hysteresis. Publishes every state
+/// change on a watch channel.
+pub struct Connectivity {
+    tx: watch::Sender<ConnectivityStatus>,
+    /// Direction of the current run of observations, and its length.
+    streak: Mutex<(bool, u32)>,
+}
+
+impl Default for Connectivity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Connectivity {
+    pub fn new() -> Self {
+        let (tx, _) = watch::channel(ConnectivityStatus {
+            state: Reachability::Unknown,
+            since: Utc::now(),
+            endpoint: None,
+        });
+        Connectivity { tx, streak: Mutex::new((false, 0)) }
+    }
+
+    pub fn status(&self) -> ConnectivityStatus {
+        self.tx.borrow().clone()
+    }
+
+    /// True only once probes have confirmed an outage; `Unknown` counts as
+    /// worth trying.
+    pub fn is_offline(&self) -> bool {
+        self.tx.borrow().state == Reachability::Offline
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<ConnectivityStatus> {
+        self.tx.subscribe()
+    }
+
+    /// Records one observation: `Some(url)` if that endpoint answered, `None`
+    /// if nothing did. The first observation decides an `Unknown` state
+    /// outright; after that it takes a run of agreeing ones to flip.
+    /// Returns whether the state changed.
+    pub fn observe(&self, answered: Option<String>) -> bool {
+        let reachable = answered.is_some();
+        let run = {
+            let mut streak = self.streak.lock().unwrap_or_else(PoisonError::into_inner);
+            *streak = if streak.0 == reachable { (reachable, streak.1 + 1) } else { (reachable, 1) };
+            streak.1
+        };
+        let target = if reachable { Reachability::Online } else { Reachability::Offline };
+        let threshold = if reachable { ONLINE_AFTER } else { OFFLINE_AFTER };
+
+        self.tx.send_if_modified(|status| {
+            if answered.is_some() {
+                status.endpoint = answered;
+            }
+            if status.state == target || (status.state != Reachability::Unknown && run < threshold) {
+                return false;
+            }
+            status.state = target;
+            status.since = Utc::now();
+            true
+        })
+    }
+}
+
+/// Re-probes the bridge's RPC pool forever, feeding `bridge.connectivity`.
+/// Waits one interval first: bootstrap has just probed.
+pub async fn run_monitor(bridge: Arc<BlockchainBridge>) {
+    loop {
+        let interval = if bridge.connectivity.is_offline() { PROBE_INTERVAL_OFFLINE } else { PROBE_INTERVAL_ONLINE };
+        tokio::time::sleep(interval).await;
+        bridge.probe_connectivity().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flips_only_after_agreeing_observations() {
+        let c = Connectivity::new();
+        let url = || Some("http://rpc.example".to_string());
+        assert!(!c.is_offline(), "unknown still tries the RPC");
+
+        // The first observation settles `Unknown` immediately.
+        assert!(c.observe(None));
+        assert!(c.is_offline());
+
+        // One success isn't enough to come back; a failure resets the run.
+        assert!(!c.observe(url()));
+        assert!(!c.observe(None));
+        assert!(!c.observe(url()));
+        assert!(c.observe(url()));
+        assert_eq!(c.status().state, Reachability::Online);
+        assert_eq!(c.status().endpoint.as_deref(), Some("http://rpc.example"));
+
+        assert!(!c.observe(None));
+        assert!(c.observe(None));
+        assert!(c.is_offline());
+        assert_eq!(c.status().endpoint.as_deref(), Some("http://rpc.example"), "last good endpoint is kept");
+    }
+}
@@ -0,0 +1,415 @@
+use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::error::AppError;
+use crate::AppState;
+
+/// Frontend events a control client can `subscribe` to.
+pub const STREAMED_EVENTS: &[&str] = &["mesh-event", "bootstrap-status", "node-state", "connectivity-changed", "chain-update", "proof-job"];
+
+/// Written to the data dir on start so local clients can find the server
+/// and its token. Only readable by the current user.
+pub const CONNECTION_FILE: &str = "control_api.json";
+
+#[derive(Debug, Serialize)]
+struct ConnectionInfo<'a> {
+    url: String,
+    token: &'a str,
+}
+
+/// Starts the opt-in control API: a JSON-RPC 2.0 server over WebSocket on
+/// `127.0.0.1:<port>`, exposing the Tauri command set as methods (params by
+/// name, in the same camelCase or snake_case the frontend uses) plus
+/// `subscribe` for event notifications. Clients authenticate with a
+/// per-launch token, sent as `Authorization: Bearer <token>` or `?token=`.
+pub async fn start(app: AppHandle, port: u16, data_dir: &Path) -> Result<SocketAddr, AppError> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+    let addr = listener.local_addr()?;
+    let token = hex::encode(rand::random::<[u8; 32]>());
+    write_connection_file(
+        &data_dir.join(CONNECTION_FILE),
+        &ConnectionInfo { url: format!("ws://{}", addr), token: &token },
+    )?;
+
+    let (events_tx, _) = broadcast::channel::<(String, Value)>(256);
+    for event in STREAMED_EVENTS {
+        let tx = events_tx.clone();
+        let name = event.to_string();
+        app.listen_any(*event, move |e| {
+            let payload = serde_json::from_str(e.payload()).unwrap_or(Value::Null);
+            let _ = tx.send((name.clone(), payload));
+        });
+    }
+
+    let token: Arc<str> = token.into();
+    tauri::async_runtime::spawn(async move {
+        while let Ok((stream, peer)) = listener.accept().await {
+            let app = app.clone();
+            let token = token.clone();
+            let events = events_tx.subscribe();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = serve_connection(app, stream, &token, events).await {
+                    tracing::warn!(%peer, error = %e, "control API connection closed");
+                }
+            });
+        }
+    });
+    tracing::info!(url = %format!("ws://{}", addr), "control API listening");
+    Ok(addr)
+}
+
+fn write_connection_file(path: &Path, info: &ConnectionInfo) -> Result<(), AppError> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // `mode` only applies when the file is created; tighten an existing
+    // file before the new token goes into it.
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(serde_json::to_string_pretty(info)?.as_bytes())?;
+    Ok(())
+}
+
+async fn serve_connection(
+    app: AppHandle,
+    stream: TcpStream,
+    token: &str,
+    mut events: broadcast::Receiver<(String, Value)>,
+) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+    let ws = tokio_tungstenite::accept_hdr_async(stream, check_token(token)).await?;
+    let (mut sink, mut source) = ws.split();
+
+    // Responses and notifications from every task funnel through one writer.
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        while let Some(msg) = out_rx.recv().await {
+            if sink.send(Message::text(msg.to_string())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let subscriptions: Arc<RwLock<HashSet<String>>> = Arc::default();
+    let notifier = {
+        let out_tx = out_tx.clone();
+        let subscriptions = subscriptions.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok((event, payload)) => {
+                        if subscriptions.read().unwrap_or_else(PoisonError::into_inner).contains(&event) {
+                            let _ = out_tx.send(json!({
+                                "jsonrpc": "2.0",
+                                "method": "event",
+                                "params": { "event": event, "payload": payload },
+                            }));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    };
+
+    while let Some(msg) = source.next().await {
+        let text = match msg? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let request: RpcRequest = match serde_json::from_str(&text) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = out_tx.send(error_response(Value::Null, RpcFailure::Parse(e.to_string())));
+                continue;
+            }
+        };
+
+        if request.method == "subscribe" {
+            let reply = subscribe(&subscriptions, &request.params);
+            let _ = out_tx.send(response(request.id, reply));
+            continue;
+        }
+
+        // Each call runs on its own task, so a slow model or RPC call never
+        // holds up the rest of this client's requests.
+        let app = app.clone();
+        let out_tx = out_tx.clone();
+        tokio::spawn(async move {
+            let reply = dispatch(&app, &request.method, &request.params).await;
+            let _ = out_tx.send(response(request.id, reply));
+        });
+    }
+
+    notifier.abort();
+    writer.abort();
+    Ok(())
+}
+
+/// Handshake callback rejecting unauthenticated clients with a 401.
+#[allow(clippy::result_large_err)] // signature fixed by tungstenite's `Callback`
+fn check_token(token: &str) -> impl FnOnce(&Request, Response) -> Result<Response, ErrorResponse> + '_ {
+    move |req, resp| {
+        if authorized(req, token) {
+            Ok(resp)
+        } else {
+            let mut denied = ErrorResponse::new(Some("invalid or missing token".to_string()));
+            *denied.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(denied)
+        }
+    }
+}
+
+/// Accepts `Authorization: Bearer <token>` or a `token` query parameter
+/// (browsers can't set headers on a WebSocket handshake).
+fn authorized(req: &Request, token: &str) -> bool {
+    let from_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let from_query = req
+        .uri()
+        .query()
+        .and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("token=")));
+    [from_header, from_query].into_iter().flatten().any(|candidate| constant_time_eq(candidate, token))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+enum RpcFailure {
+    Parse(String),
+    MethodNotFound(String),
+    InvalidParams(String),
+    App(AppError),
+}
+
+impl From<AppError> for RpcFailure {
+    fn from(e: AppError) -> Self {
+        RpcFailure::App(e)
+    }
+}
+
+impl From<serde_json::Error> for RpcFailure {
+    fn from(e: serde_json::Error) -> Self {
+        RpcFailure::App(e.into())
+    }
+}
+
+fn response(id: Value, reply: Result<Value, RpcFailure>) -> Value {
+    match reply {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(failure) => error_response(id, failure),
+    }
+}
+
+/// Standard JSON-RPC codes for protocol errors; command failures use -32000
+/// with the usual `{ code, message, details }` `AppError` as `data`.
+fn error_response(id: Value, failure: RpcFailure) -> Value {
+    let (code, message, data) = match failure {
+        RpcFailure::Parse(m) => (-32700, format!("Parse error: {}", m), Value::Null),
+        RpcFailure::MethodNotFound(m) => (-32601, format!("Method not found: {}", m), Value::Null),
+        RpcFailure::InvalidParams(m) => (-32602, format!("Invalid params: {}", m), Value::Null),
+        RpcFailure::App(e) => (-32000, e.to_string(), serde_json::to_value(&e).unwrap_or(Value::Null)),
+    };
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message, "data": data } })
+}
+
+fn subscribe(subscriptions: &RwLock<HashSet<String>>, params: &Value) -> Result<Value, RpcFailure> {
+    let events: Vec<String> = arg(params, "events")?;
+    if let Some(unknown) = events.iter().find(|e| !STREAMED_EVENTS.contains(&e.as_str())) {
+        return Err(RpcFailure::InvalidParams(format!("unknown event {}", unknown)));
+    }
+    let mut subscriptions = subscriptions.write().unwrap_or_else(PoisonError::into_inner);
+    subscriptions.extend(events);
+    Ok(json!(subscriptions.iter().collect::<Vec<_>>()))
+}
+
+/// Looks up a named param by its Rust name or the camelCase name Tauri's
+/// `invoke` uses. A missing param deserialises as `null`, so optional
+/// arguments can simply be left out.
+fn arg<T: DeserializeOwned>(params: &Value, name: &str) -> Result<T, RpcFailure> {
+    let camel = snake_to_camel(name);
+    let value = params.get(name).or_else(|| params.get(&camel)).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value).map_err(|e| RpcFailure::InvalidParams(format!("{}: {}", camel, e)))
+}
+
+fn snake_to_camel(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Calls the Tauri command of the same name, so the control API can never
+/// drift from what the UI gets.
+async fn dispatch(app: &AppHandle, method: &str, p: &Value) -> Result<Value, RpcFailure> {
+    use crate::app_initializer as init;
+
+    macro_rules! call {
+        ($command:path $(, $name:literal)*) => {
+            serde_json::to_value($command($(arg(p, $name)?,)* app.state::<AppState>()).await?)?
+        };
+    }
+
+    Ok(match method {
+        "send_intent_to_mesh" => call!(crate::gui::send_intent_to_mesh, "payload", "bid_proof"),
+        "negotiate_with_shark" => call!(crate::gui::negotiate_with_shark, "intent", "price_ceiling", "market_price"),
+        "generate_zk_proof" => call!(crate::gui::generate_zk_proof, "bid_amount", "price_ceiling", "intent", "recipient_key"),
+        "submit_proof_job" => call!(crate::gui::submit_proof_job, "bid_amount", "price_ceiling", "intent", "recipient_key"),
+        "get_proof_job" => call!(crate::gui::get_proof_job, "job_id"),
+        "list_proof_jobs" => call!(crate::gui::list_proof_jobs),
+        "cancel_proof_job" => call!(crate::gui::cancel_proof_job, "job_id"),
+        "list_circuits" => call!(crate::gui::list_circuits),
+        "compile_circuit" => call!(crate::gui::compile_circuit, "backend", "repin"),
+        "check_circuit_integrity" => call!(crate::gui::check_circuit_integrity, "circuit_id"),
+        "verify_zk_proof" => call!(crate::gui::verify_zk_proof, "bid_proof", "intent"),
+        "open_sealed_intent" => call!(crate::gui::open_sealed_intent, "bid_proof"),
+        "sync_blockchain_state" => call!(crate::gui::sync_blockchain_state, "wallet"),
+        "enable_instant_session" => call!(crate::gui::enable_instant_session),
+        "create_escrow" => call!(crate::gui::create_escrow, "payee", "amount_avax", "expiry_unix"),
+        "release_escrow" => call!(crate::gui::release_escrow, "escrow_id"),
+        "refund_escrow" => call!(crate::gui::refund_escrow, "escrow_id"),
+        "get_escrow_status" => call!(crate::gui::get_escrow_status, "escrow_id"),
+        "get_bridge_status" => call!(crate::gui::get_bridge_status),
+        "get_rpc_health" => call!(crate::gui::get_rpc_health),
+        "get_profile" => serde_json::to_value(crate::gui::get_profile(app.state()).await?)?,
+        "get_settings" => call!(crate::gui::get_settings),
+        "export_diagnostics" => serde_json::to_value(crate::gui::export_diagnostics(app.state(), app.state()).await?)?,
+        "update_settings" => serde_json::to_value(
+            crate::gui::update_settings(arg(p, "settings")?, app.state(), app.clone()).await?,
+        )?,
+        "get_wallet_snapshot" => call!(crate::gui::get_wallet_snapshot),
+        "delete_wallet_snapshot" => call!(crate::gui::delete_wallet_snapshot),
+        "kill_switch" => call!(init::kill_switch),
+        "get_node_state" => call!(init::get_node_state),
+        "get_connectivity" => call!(init::get_connectivity),
+        "restart_mesh" => serde_json::to_value(init::restart_mesh(app.state(), app.clone()).await?)?,
+        "retry_bridge_sync" => serde_json::to_value(init::retry_bridge_sync(app.state(), app.clone()).await?)?,
+        "restart_ollama" => call!(init::restart_ollama),
+        "get_identity" => call!(crate::gui::get_identity),
+        "mint_voucher" => call!(crate::gui::mint_voucher, "voucher_type", "description"),
+        "approve_voucher" => call!(crate::gui::approve_voucher, "token_id"),
+        "create_asset_listing" => call!(crate::gui::create_asset_listing, "description", "price_avax", "token_id"),
+        "get_active_asset_listings" => call!(crate::gui::get_active_asset_listings),
+        "buy_listing" => call!(crate::gui::buy_listing, "listing_id", "price_avax"),
+        "submit_raw_transaction" => call!(crate::gui::submit_raw_transaction, "raw_tx_hex"),
+        "get_pending_relay_txs" => call!(crate::gui::get_pending_relay_txs),
+        "verify_relay_confirmation" => {
+            call!(crate::gui::verify_relay_confirmation, "queue_id", "status", "tx_hash", "relayer")
+        }
+        "get_relay_reputation" => call!(crate::gui::get_relay_reputation),
+        "record_relayed_tx" => call!(crate::gui::record_relayed_tx, "summary", "tx_hash", "reward_avax"),
+        "get_relayed_history" => call!(crate::gui::get_relayed_history),
+        "release_deal" => call!(crate::gui::release_deal, "deal_id"),
+        "refund_deal" => call!(crate::gui::refund_deal, "deal_id"),
+        "redeem_voucher" => call!(crate::gui::redeem_voucher, "token_id"),
+        "get_voucher_owner" => call!(crate::gui::get_voucher_owner, "token_id"),
+        "get_owned_vouchers" => call!(crate::gui::get_owned_vouchers, "owner"),
+        "get_my_deals" => call!(crate::gui::get_my_deals, "address"),
+        "get_ollama_status" => call!(crate::gui::get_ollama_status),
+        "list_ollama_models" => call!(crate::gui::list_ollama_models, "url"),
+        "extract_pdf_text" => call!(crate::gui::extract_pdf_text, "pdf_bytes"),
+        "sign_content" => call!(crate::gui::sign_content, "text"),
+        "store_content" => call!(crate::gui::store_content, "token_id", "record"),
+        "get_content" => call!(crate::gui::get_content, "token_id"),
+        "receive_content" => call!(crate::gui::receive_content, "token_id", "text", "signature", "expected_seller"),
+        "get_received_content" => call!(crate::gui::get_received_content, "token_id"),
+        "match_intent_to_listings" => call!(crate::gui::match_intent_to_listings, "intent", "price_ceiling"),
+        "get_listing_catalogue" => call!(crate::gui::get_listing_catalogue),
+        "get_relay_stats" => call!(crate::gui::get_relay_stats),
+        other => return Err(RpcFailure::MethodNotFound(other.to_string())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authenticates_and_frames_json_rpc() {
+        let token = "a".repeat(64);
+        let req = |uri: &str, auth: Option<&str>| {
+            let mut builder = Request::builder().uri(uri);
+            if let Some(auth) = auth {
+                builder = builder.header("Authorization", auth);
+            }
+            builder.body(()).unwrap()
+        };
+        assert!(authorized(&req("/", Some(&format!("Bearer {}", token))), &token));
+        assert!(authorized(&req(&format!("/?x=1&token={}", token), None), &token));
+        assert!(!authorized(&req("/", None), &token));
+        assert!(!authorized(&req("/?token=aaaa", Some("Bearer nope")), &token));
+
+        // Params resolve by Rust name or Tauri's camelCase name; missing => None.
+        let params = json!({ "priceCeiling": 1.5, "escrow_id": 7 });
+        assert_eq!(arg::<f64>(&params, "price_ceiling").unwrap(), 1.5);
+        assert_eq!(arg::<u64>(&params, "escrow_id").unwrap(), 7);
+        assert_eq!(arg::<Option<u64>>(&params, "expiry_unix").unwrap(), None);
+        assert!(matches!(arg::<String>(&params, "payee"), Err(RpcFailure::InvalidParams(_))));
+
+        let err = response(json!(3), Err(AppError::NotConfigured("ESCROW_CONTRACT_ADDRESS").into()));
+        assert_eq!(err["id"], 3);
+        assert_eq!(err["error"]["code"], -32000);
+        assert_eq!(err["error"]["data"]["code"], "not_configured");
+        let ok = response(json!("a"), Ok(json!(true)));
+        assert_eq!(ok["result"], true);
+
+        let subscriptions = RwLock::new(HashSet::new());
+        assert!(subscribe(&subscriptions, &json!({ "events": ["mesh-event"] })).is_ok());
+        assert!(subscribe(&subscriptions, &json!({ "events": ["secrets"] })).is_err());
+    }
+
+    /// A connection file left with looser permissions is tightened before
+    /// the new token is written.
+    #[cfg(unix)]
+    #[test]
+    fn connection_file_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("cabalmesh_control_api_{}.json", std::process::id()));
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_connection_file(&path, &ConnectionInfo { url: "ws://127.0.0.1:1".to_string(), token: "secret" }).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("secret"));
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,196 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt::Display;
+
+use crate::lifecycle::Subsystem;
+use crate::revert::{decode_revert, RevertReason};
+use crate::rpc_pool::RpcPoolError;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Every failure the backend reports to the frontend. Each variant has a
+/// stable machine-readable `code()` the UI can branch on; the message is for
+/// humans only and may change. Everything is owned data, so `AppError` is
+/// `Send + Sync + Clone` and can cross task/await boundaries freely, or be
+/// kept as the outcome of a background job.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AppError {
+    #[error("{0} not configured")]
+    NotConfigured(&'static str),
+    #[error("No identity available")]
+    NoIdentity,
+    /// The OS keychain refused access (locked, denied, or unavailable).
+    #[error("Identity locked: {0}")]
+    IdentityLocked(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("RPC unreachable: {0}")]
+    RpcUnreachable(String),
+    #[error("Insufficient funds: {0}")]
+    InsufficientFunds(String),
+    #[error("{call} reverted: {reason}")]
+    Reverted { call: String, reason: RevertReason },
+    #[error("RPC rejected the request: {0}")]
+    RpcRejected(String),
+    /// The subsystem a command depends on is still starting, failed to
+    /// start, or the node is shutting down.
+    #[error("{subsystem} is not ready: {reason}")]
+    NotReady { subsystem: Subsystem, reason: String },
+    #[error("Mesh error: {0}")]
+    Mesh(String),
+    /// The local Ollama model couldn't be reached or answered garbage.
+    #[error("Local model unavailable: {0}")]
+    ModelUnavailable(String),
+    #[error("Proof failed: {0}")]
+    Proof(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+}
+
+impl AppError {
+    /// Stable identifier for the frontend. Never rename an existing code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotConfigured(_) => "not_configured",
+            AppError::NoIdentity => "no_identity",
+            AppError::IdentityLocked(_) => "identity_locked",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::NotFound(_) => "not_found",
+            AppError::RpcUnreachable(_) => "rpc_unreachable",
+            AppError::InsufficientFunds(_) => "insufficient_funds",
+            AppError::Reverted { .. } => "contract_reverted",
+            AppError::RpcRejected(_) => "rpc_rejected",
+            AppError::NotReady { .. } => "not_ready",
+            AppError::Mesh(_) => "mesh_error",
+            AppError::ModelUnavailable(_) => "model_unavailable",
+            AppError::Proof(_) => "proof_failed",
+            AppError::Storage(_) => "storage_error",
+            AppError::Crypto(_) => "crypto_error",
+        }
+    }
+
+    pub fn invalid_input(e: impl Display) -> Self {
+        AppError::InvalidInput(e.to_string())
+    }
+
+    pub fn crypto(e: impl Display) -> Self {
+        AppError::Crypto(e.to_string())
+    }
+
+    pub fn mesh(e: impl Display) -> Self {
+        AppError::Mesh(e.to_string())
+    }
+}
+
+/// Serialised to the frontend as `{ code, message, details }`, where
+/// `details` carries the decoded revert reason for `contract_reverted`, the
+/// subsystem for `not_ready`, and is `null` otherwise.
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.to_string())?;
+        match self {
+            AppError::Reverted { call, reason } => {
+                s.serialize_field("details", &serde_json::json!({ "call": call, "reason": reason }))?
+            }
+            AppError::NotReady { subsystem, .. } => {
+                s.serialize_field("details", &serde_json::json!({ "subsystem": subsystem }))?
+            }
+            _ => s.serialize_field("details", &serde_json::Value::Null)?,
+        }
+        s.end()
+    }
+}
+
+impl From<RpcPoolError> for AppError {
+    fn from(e: RpcPoolError) -> Self {
+        match e {
+            RpcPoolError::Unreachable(message) => AppError::RpcUnreachable(message),
+            RpcPoolError::Rejected { revert_data: Some(data), .. } => AppError::Reverted {
+                call: "eth_call".to_string(),
+                reason: decode_revert(&data),
+            },
+            RpcPoolError::Rejected { message, .. } if message.to_lowercase().contains("insufficient funds") => {
+                AppError::InsufficientFunds(message)
+            }
+            RpcPoolError::Rejected { message, .. } => AppError::RpcRejected(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
+
+impl From<keyring::Error> for AppError {
+    fn from(e: keyring::Error) -> Self {
+        AppError::IdentityLocked(e.to_string())
+    }
+}
+
+impl From<hex::FromHexError> for AppError {
+    fn from(e: hex::FromHexError) -> Self {
+        AppError::InvalidInput(e.to_string())
+    }
+}
+
+/// Address / hash parsing (`Address::from_str` and friends).
+impl From<alloy::hex::FromHexError> for AppError {
+    fn from(e: alloy::hex::FromHexError) -> Self {
+        AppError::InvalidInput(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::ModelUnavailable(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Bytes;
+    use alloy::sol_types::{Revert, SolError};
+
+    #[test]
+    fn serialises_stable_code_and_revert_details() {
+        let err = AppError::NotConfigured("ESCROW_CONTRACT_ADDRESS");
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "not_configured");
+        assert_eq!(json["message"], "ESCROW_CONTRACT_ADDRESS not configured");
+        assert!(json["details"].is_null());
+
+        let err: AppError = RpcPoolError::Rejected {
+            message: "execution reverted".to_string(),
+            revert_data: Some(Bytes::from(Revert::from("Wrong amount").abi_encode())),
+        }
+        .into();
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "contract_reverted");
+        assert_eq!(json["details"]["reason"]["kind"], "message");
+        assert_eq!(json["details"]["reason"]["message"], "Wrong amount");
+
+        let err: AppError = RpcPoolError::Rejected {
+            message: "insufficient funds for gas * price + value".to_string(),
+            revert_data: None,
+        }
+        .into();
+        assert_eq!(err.code(), "insufficient_funds");
+
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<AppError>();
+    }
+}
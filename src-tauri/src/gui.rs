@@ -0,0 +1,769 @@
+//! The Tauri app: its commands, which the control API also dispatches to,
+//! and `run`. Only built with the `gui` feature.
+
+use crate::app_initializer::{self, SystemBootstrap};
+use crate::mesh::PrivacyIntent;
+use crate::agent::SharkNegotiation;
+use crate::matcher::MatchResult;
+use crate::zk_handler::BidProof;
+use crate::proof_jobs::{ProofJob, ProofJobId, ProofJobRequest};
+use crate::circuit_registry::{CircuitIntegrity, CircuitRecord};
+use crate::proving_backend::ProvingBackendKind;
+use crate::sealed_intent::IntentOpening;
+use crate::blockchain_bridge::{self, BlockchainBridge, AssetListingView, VoucherView, TxResult, QueuedTx};
+use crate::rpc_pool::EndpointStatus;
+use crate::error::{AppError, AppResult};
+use crate::lifecycle::Subsystem;
+use crate::profile::Profile;
+use crate::settings::{Settings, SettingsStore, SettingsUpdate};
+use crate::ollama_manager::OllamaManager;
+use crate::{catalogue, control_api, logging, AppState};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tauri::{State, Manager};
+
+#[tauri::command]
+pub(crate) async fn send_intent_to_mesh(
+    payload: String,
+    bid_proof: Option<BidProof>,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    // Check if payload is a settlement/deal/relay message (contains "type" field)
+    if let Ok(json_val) = serde_json::from_str::<serde_json::Value>(&payload) {
+        if let Some(type_field) = json_val.get("type").and_then(|v| v.as_str()) {
+            // These get their own outer intent_type so mesh.rs's receive handler can
+            // route them without inspecting the inner payload; everything else keeps
+            // the existing "settlement" wrapping behavior.
+            let intent_type = match type_field {
+                "RelayTx" => "relay_tx",
+                "RelayConfirmed" => "relay_confirmed",
+                "ContentRequest" => "content_request",
+                "ContentDelivery" => "content_delivery",
+                _ => "settlement",
+            };
+            tracing::debug!(intent_type, "sending mesh message");
+            // Relay claims are signed so the peers we relayed for can keep
+            // our record under our wallet address.
+            let payload = if intent_type == "relay_confirmed" {
+                let field = |key: &str| json_val.get(key).and_then(|v| v.as_str());
+                let claim = state.bridge.sign_relay_claim(field("queue_id").unwrap_or(""), field("status").unwrap_or("failed"), field("tx_hash"))?;
+                serde_json::to_string(&claim)?
+            } else {
+                payload.clone()
+            };
+            let intent = PrivacyIntent {
+                intent_type: intent_type.to_string(),
+                payload: payload.clone(),
+                encrypted: false,
+                relay_path: vec!["origin_node".to_string()],
+                relay_fee: None, // Settlements/relay messages don't carry relay fees
+                bid_proof: None,
+            };
+            state.send_to_mesh(intent)?;
+            return Ok(format!("{} message broadcasted: {}", intent_type, payload));
+        }
+    }
+    
+    // Regular intent message
+    let intent = PrivacyIntent {
+        intent_type: "trade".to_string(),
+        payload: payload.clone(),
+        encrypted: true,
+        relay_path: vec!["origin_node".to_string()], // Initial hop
+        relay_fee: Some(format!("{} AVAX", state.settings.get().relay_fee_avax)),
+        bid_proof,
+    };
+
+    state.send_to_mesh(intent)?;
+    Ok(format!("Intent broadcasted: {}", payload))
+}
+
+#[tauri::command]
+pub(crate) async fn negotiate_with_shark(
+    intent: String,
+    price_ceiling: f64,
+    market_price: f64,
+    state: State<'_, AppState>,
+) -> AppResult<SharkNegotiation> {
+    state.lifecycle.require(Subsystem::Ollama)?;
+    state
+        .agent
+        .negotiate(&intent, price_ceiling, market_price)
+        .await
+}
+
+/// Resolves the recipient of a bid proof's sealed intent: `recipient_key`,
+/// or else the committee key.
+fn proof_job_request(
+    bid_amount: u64,
+    price_ceiling: u64,
+    intent: String,
+    recipient_key: Option<String>,
+    state: &AppState,
+) -> AppResult<ProofJobRequest> {
+    let recipient_key = recipient_key
+        .or(state.settings.get().intent_committee_key)
+        .ok_or_else(|| AppError::InvalidInput("no recipient_key given and no intent_committee_key set".to_string()))?;
+    Ok(ProofJobRequest { bid_amount, price_ceiling, intent, recipient_key })
+}
+
+/// Proves a bid of `bid_amount` nAVAX for the intent with payload `intent`
+/// against the balance anchored by the latest snapshot (re-synced if stale).
+/// The intent is sealed to `recipient_key`, or else the committee key. Runs
+/// as a proof job and waits for it; see `submit_proof_job`.
+#[tauri::command]
+pub(crate) async fn generate_zk_proof(
+    bid_amount: u64,
+    price_ceiling: u64,
+    intent: String,
+    recipient_key: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<BidProof> {
+    let request = proof_job_request(bid_amount, price_ceiling, intent, recipient_key, &state)?;
+    state.proof_jobs.prove(request).await
+}
+
+/// Like `generate_zk_proof`, but returns the job straight away. Progress
+/// and the result arrive as `proof-job` events, or via `get_proof_job`.
+#[tauri::command]
+pub(crate) async fn submit_proof_job(
+    bid_amount: u64,
+    price_ceiling: u64,
+    intent: String,
+    recipient_key: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<ProofJob> {
+    let request = proof_job_request(bid_amount, price_ceiling, intent, recipient_key, &state)?;
+    state.proof_jobs.submit(request)
+}
+
+#[tauri::command]
+pub(crate) async fn get_proof_job(
+    job_id: ProofJobId,
+    state: State<'_, AppState>,
+) -> AppResult<ProofJob> {
+    state.proof_jobs.get(job_id)
+}
+
+#[tauri::command]
+pub(crate) async fn list_proof_jobs(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<ProofJob>> {
+    Ok(state.proof_jobs.list())
+}
+
+/// Whether the job was stopped; `false` if it had already finished.
+#[tauri::command]
+pub(crate) async fn cancel_proof_job(
+    job_id: ProofJobId,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    state.proof_jobs.cancel(job_id)
+}
+
+/// Every circuit version this node has compiled, with its pinned hashes.
+#[tauri::command]
+pub(crate) async fn list_circuits(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<CircuitRecord>> {
+    Ok(state.zk_handler.list_circuits())
+}
+
+/// Compiles the current circuit of `backend` (default: the configured
+/// one) and registers it. Fails if it no longer gives the pinned
+/// verification key, unless `repin`.
+#[tauri::command]
+pub(crate) async fn compile_circuit(
+    backend: Option<ProvingBackendKind>,
+    repin: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<CircuitRecord> {
+    let backend = backend.unwrap_or_else(|| state.zk_handler.proving_backend());
+    state.zk_handler.compile_circuit(backend, repin.unwrap_or(false)).await
+}
+
+/// Rehashes the stored artifacts of `circuit_id`, or of every circuit.
+#[tauri::command]
+pub(crate) async fn check_circuit_integrity(
+    circuit_id: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<CircuitIntegrity>> {
+    state.zk_handler.check_circuit_integrity(circuit_id.as_deref())
+}
+
+/// Whether `bid_proof` holds for an intent with payload `intent`. Errors
+/// when it can't be checked here (no `bb`), which is not the same as invalid.
+#[tauri::command]
+pub(crate) async fn verify_zk_proof(
+    bid_proof: BidProof,
+    intent: String,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    state.zk_handler.verify_bid(&bid_proof, &intent).await
+}
+
+/// The intent sealed in a bid proof, if it's sealed to our intent key and
+/// matches what the proof commits to.
+#[tauri::command]
+pub(crate) async fn open_sealed_intent(
+    bid_proof: BidProof,
+    state: State<'_, AppState>,
+) -> AppResult<IntentOpening> {
+    state.zk_handler.open_bid(&bid_proof)
+}
+
+#[tauri::command]
+pub(crate) async fn sync_blockchain_state(
+    wallet: String,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.sync_state(&wallet).await?;
+    Ok("Synced".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn enable_instant_session(
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    let session = state.bridge.init_instant_session(state.settings.get().session_expiry());
+    Ok(format!("Session Created: {}", session.session_id))
+}
+
+#[tauri::command]
+pub(crate) async fn create_escrow(
+    payee: String,
+    amount_avax: String,
+    expiry_unix: Option<u64>,
+    state: State<'_, AppState>,
+) -> AppResult<TxResult> {
+    let amount_wei = alloy::primitives::utils::parse_ether(&amount_avax).map_err(AppError::invalid_input)?;
+    state
+        .bridge
+        .create_escrow(&payee, amount_wei, expiry_unix.unwrap_or(0))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn release_escrow(
+    escrow_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.release_escrow(escrow_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn refund_escrow(
+    escrow_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.refund_escrow(escrow_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_escrow_status(
+    escrow_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<serde_json::Value> {
+    state.bridge.get_escrow_status(escrow_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_bridge_status(
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    Ok(format!("{}\n{}", state.bridge.get_status(), state.zk_handler.status().await))
+}
+
+/// Latency/error stats for every configured RPC endpoint, best first.
+#[tauri::command]
+pub(crate) async fn get_rpc_health(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<EndpointStatus>> {
+    Ok(state.bridge.get_rpc_health())
+}
+
+#[tauri::command]
+pub(crate) async fn get_profile(
+    profile: State<'_, Profile>,
+) -> AppResult<Profile> {
+    Ok(profile.inner().clone())
+}
+
+#[tauri::command]
+pub(crate) async fn get_settings(
+    state: State<'_, AppState>,
+) -> AppResult<Settings> {
+    Ok(state.settings.get())
+}
+
+/// Writes a redacted bug-report bundle (node state, settings, recent logs)
+/// into the data dir and returns its path.
+#[tauri::command]
+pub(crate) async fn export_diagnostics(
+    state: State<'_, AppState>,
+    profile: State<'_, Profile>,
+) -> AppResult<String> {
+    let context = serde_json::json!({
+        "profile": profile.label(),
+        "node_state": state.lifecycle.state(),
+        "settings": state.settings.get(),
+        "rpc_health": state.bridge.get_rpc_health(),
+    });
+    let path = logging::export_diagnostics(&profile.data_dir, context)?;
+    Ok(path.display().to_string())
+}
+
+/// Validates and saves `settings`. A changed agent model must be one its
+/// Ollama endpoint lists, when that endpoint answers. The Ollama agents,
+/// session expiry and relay fee pick changes up on their next use; a changed
+/// mesh topic or listen address restarts the mesh, and a changed `ollama_url`
+/// restarts the managed Ollama. RPC and contract changes are reported as
+/// `requires_restart`.
+#[tauri::command]
+pub(crate) async fn update_settings(
+    settings: Settings,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> AppResult<SettingsUpdate> {
+    OllamaManager::check_models(&settings, &state.settings.get()).await?;
+    let update = state.settings.update(settings)?;
+    let changed = |field: &str| update.applied.iter().any(|f| f == field);
+
+    if changed("ollama_url") {
+        let state = state.inner().clone();
+        tauri::async_runtime::spawn(async move {
+            state.ollama.stop_service();
+            SystemBootstrap::start_ollama(&state).await;
+        });
+    }
+    if changed("mesh_topic") || changed("mesh_listen_addr") {
+        SystemBootstrap::phase_3_network(&state, &app).await;
+    }
+    Ok(update)
+}
+
+#[tauri::command]
+pub(crate) async fn get_wallet_snapshot(
+    state: State<'_, AppState>,
+) -> AppResult<serde_json::Value> {
+    match state.bridge.get_latest_snapshot() {
+        Ok(snapshot) => Ok(serde_json::to_value(snapshot)?),
+        Err(_) => Ok(serde_json::Value::Null), // Return null, not empty object
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn delete_wallet_snapshot(
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    // Atomic Reset: Delete snapshot AND identity
+    let _ = state.bridge.delete_snapshot();
+    let _ = state.bridge.delete_identity();
+    Ok(())
+}
+
+use crate::blockchain_bridge::IdentityView; // Import View
+
+#[tauri::command]
+pub(crate) async fn get_identity(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<IdentityView>> { // Return full IdentityView objects
+    state.bridge.get_identity_views()
+}
+
+#[tauri::command]
+pub(crate) async fn mint_voucher(
+    voucher_type: String,
+    description: String,
+    state: State<'_, AppState>,
+) -> AppResult<u64> {
+    state.bridge.mint_voucher(&voucher_type, &description).await
+}
+
+#[tauri::command]
+pub(crate) async fn approve_voucher(
+    token_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.approve_voucher(token_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn create_asset_listing(
+    description: String,
+    price_avax: String,
+    token_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<u64> {
+    let price_wei = alloy::primitives::utils::parse_ether(&price_avax).map_err(AppError::invalid_input)?;
+    state.bridge.create_asset_listing(&description, price_wei, token_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_active_asset_listings(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<AssetListingView>> {
+    state.bridge.get_active_asset_listings().await
+}
+
+#[tauri::command]
+pub(crate) async fn buy_listing(
+    listing_id: u64,
+    price_avax: String,
+    state: State<'_, AppState>,
+) -> AppResult<TxResult> {
+    let price_wei = alloy::primitives::utils::parse_ether(&price_avax).map_err(AppError::invalid_input)?;
+    state.bridge.buy_listing(listing_id, price_wei).await
+}
+
+#[tauri::command]
+pub(crate) async fn submit_raw_transaction(
+    raw_tx_hex: String,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.submit_raw_transaction(&raw_tx_hex).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_pending_relay_txs(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<QueuedTx>> {
+    Ok(state.bridge.get_pending_relay_txs())
+}
+
+/// Only ever marks a queued tx confirmed/failed after checking the relay
+/// peer's claim against the chain — `relay_confirmed` mesh messages are
+/// unauthenticated claims, never trusted on their own.
+#[tauri::command]
+pub(crate) async fn verify_relay_confirmation(
+    queue_id: String,
+    status: String,
+    tx_hash: Option<String>,
+    relayer: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<QueuedTx> {
+    state
+        .bridge
+        .verify_relay_confirmation(&queue_id, &status, tx_hash.as_deref(), relayer.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn get_relay_reputation(
+    state: State<'_, AppState>,
+) -> AppResult<std::collections::HashMap<String, blockchain_bridge::RelayReputation>> {
+    Ok(state.bridge.get_relay_reputation())
+}
+
+#[tauri::command]
+pub(crate) async fn record_relayed_tx(
+    summary: String,
+    tx_hash: String,
+    reward_avax: String,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    state.bridge.record_relayed_tx(&summary, &tx_hash, &reward_avax)
+}
+
+#[tauri::command]
+pub(crate) async fn get_relayed_history(
+    state: State<'_, AppState>,
+) -> AppResult<Vec<blockchain_bridge::RelayedTxRecord>> {
+    Ok(state.bridge.get_relayed_history())
+}
+
+#[tauri::command]
+pub(crate) async fn release_deal(
+    deal_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.release_deal(deal_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn refund_deal(
+    deal_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.refund_deal(deal_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn redeem_voucher(
+    token_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.redeem_voucher(token_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_voucher_owner(
+    token_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.get_voucher_owner(token_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_owned_vouchers(
+    owner: String,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<VoucherView>> {
+    state.bridge.get_owned_vouchers(&owner).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_my_deals(
+    address: String,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<blockchain_bridge::DealView>> {
+    state.bridge.get_my_deals(&address).await
+}
+
+/// Real status of the local Ollama model the Shark Agent / matcher depend on —
+/// pings its local API rather than assuming it's ready just because it auto-started.
+#[tauri::command]
+pub(crate) async fn get_ollama_status(
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    Ok(state.ollama.health_check().await)
+}
+
+/// Models the Ollama server at `url` (default: `ollama_url`) has pulled,
+/// for picking each agent's model.
+#[tauri::command]
+pub(crate) async fn list_ollama_models(
+    url: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<String>> {
+    OllamaManager::list_models(&url.unwrap_or_else(|| state.settings.get().ollama_url)).await
+}
+
+#[tauri::command]
+pub(crate) async fn extract_pdf_text(
+    pdf_bytes: Vec<u8>,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    state.bridge.extract_pdf_text(pdf_bytes)
+}
+
+#[tauri::command]
+pub(crate) async fn sign_content(
+    text: String,
+    state: State<'_, AppState>,
+) -> AppResult<blockchain_bridge::ContentRecord> {
+    state.bridge.sign_content(&text)
+}
+
+#[tauri::command]
+pub(crate) async fn store_content(
+    token_id: u64,
+    record: blockchain_bridge::ContentRecord,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    state.bridge.store_content(token_id, record)
+}
+
+#[tauri::command]
+pub(crate) async fn get_content(
+    token_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<Option<blockchain_bridge::ContentRecord>> {
+    Ok(state.bridge.get_content(token_id))
+}
+
+#[tauri::command]
+pub(crate) async fn receive_content(
+    token_id: u64,
+    text: String,
+    signature: String,
+    expected_seller: String,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    state.bridge.receive_content(token_id, &text, &signature, &expected_seller)
+}
+
+#[tauri::command]
+pub(crate) async fn get_received_content(
+    token_id: u64,
+    state: State<'_, AppState>,
+) -> AppResult<Option<blockchain_bridge::ContentRecord>> {
+    Ok(state.bridge.get_received_content(token_id))
+}
+
+#[tauri::command]
+pub(crate) async fn match_intent_to_listings(
+    intent: String,
+    price_ceiling: f64,
+    state: State<'_, AppState>,
+) -> AppResult<Option<MatchResult>> {
+    state.lifecycle.require(Subsystem::Ollama)?;
+    // Falls back to the cached catalogue when the RPC is unreachable; ask
+    // peers for a fresher one for next time.
+    let catalogue = state.bridge.get_listing_catalogue().await.inspect_err(|e| {
+        if matches!(e, AppError::RpcUnreachable(_)) {
+            let _ = state.request_catalogue();
+        }
+    })?;
+    if catalogue.stale {
+        tracing::info!(fetched_at = %catalogue.fetched_at, "matching against cached listing catalogue");
+        let _ = state.request_catalogue();
+    }
+    // No lock is held while the model runs — other commands proceed meanwhile.
+    let matched = state
+        .matcher
+        .match_intent(&intent, price_ceiling, &catalogue.listings)
+        .await?;
+    Ok(matched.map(|m| MatchResult { stale_since: catalogue.stale.then_some(catalogue.fetched_at), ..m }))
+}
+
+#[tauri::command]
+pub(crate) async fn get_listing_catalogue(
+    state: State<'_, AppState>,
+) -> AppResult<catalogue::ListingCatalogue> {
+    state.bridge.get_listing_catalogue().await
+}
+
+#[tauri::command]
+pub(crate) async fn get_relay_stats(
+    state: State<'_, AppState>,
+) -> AppResult<u64> {
+    Ok(state.relay_bytes.load(Ordering::Relaxed))
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    dotenv::dotenv().ok(); // Load .env file
+
+    let profile = match Profile::from_env_and_args() {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("error [{}]: {}", e.code(), e);
+            std::process::exit(2);
+        }
+    };
+    // Held until the event loop exits so buffered log lines get flushed.
+    let mut log_guard = match logging::init(&profile.data_dir, tracing::Level::INFO.into()) {
+        Ok(guard) => Some(guard),
+        Err(e) => {
+            eprintln!("⚠️  File logging disabled: {}", e);
+            None
+        }
+    };
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .setup(move |app| {
+            let app_handle = app.handle().clone();
+
+            tracing::info!(profile = profile.label(), data_dir = %profile.data_dir.display(), "starting node");
+            if let (Some(name), Some(window)) = (&profile.name, app.get_webview_window("main")) {
+                let _ = window.set_title(&format!("CabalMesh — {}", name));
+            }
+
+            // State is registered up front so every command resolves immediately;
+            // commands that need a subsystem still booting get a `not_ready` error.
+            let settings = Arc::new(SettingsStore::load(&profile.data_dir));
+            let bridge = Arc::new(BlockchainBridge::new(&settings.get(), profile.data_dir.clone()));
+            let Settings { control_api_enabled, control_api_port, .. } = settings.get();
+            let state = AppState::new(settings, bridge, &profile.data_dir);
+            app.manage(state.clone());
+
+            if control_api_enabled {
+                let app_handle = app_handle.clone();
+                let data_dir = profile.data_dir.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = control_api::start(app_handle, control_api_port, &data_dir).await {
+                        tracing::error!(error = %e, "control API failed to start");
+                    }
+                });
+            }
+            app.manage(profile);
+
+            SystemBootstrap::forward_node_state(&state, &app_handle);
+            SystemBootstrap::forward_connectivity(&state, &app_handle);
+            SystemBootstrap::forward_proof_jobs(&state, &app_handle);
+
+            // Initialize System via Bootstrap Workflow
+            tauri::async_runtime::spawn(async move {
+                SystemBootstrap::run(&state, &app_handle).await;
+            });
+
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            send_intent_to_mesh,
+            negotiate_with_shark,
+            generate_zk_proof,
+            submit_proof_job,
+            get_proof_job,
+            list_proof_jobs,
+            cancel_proof_job,
+            list_circuits,
+            compile_circuit,
+            check_circuit_integrity,
+            verify_zk_proof,
+            open_sealed_intent,
+            sync_blockchain_state,
+            enable_instant_session,
+            create_escrow,
+            release_escrow,
+            refund_escrow,
+            get_escrow_status,
+            get_bridge_status,
+            get_rpc_health,
+            get_settings,
+            get_profile,
+            export_diagnostics,
+            update_settings,
+            get_wallet_snapshot,
+            delete_wallet_snapshot,
+            app_initializer::kill_switch,
+            app_initializer::get_node_state,
+            app_initializer::get_connectivity,
+            app_initializer::restart_mesh,
+            app_initializer::retry_bridge_sync,
+            app_initializer::restart_ollama,
+            get_identity,
+            mint_voucher,
+            approve_voucher,
+            create_asset_listing,
+            get_active_asset_listings,
+            buy_listing,
+            release_deal,
+            refund_deal,
+            submit_raw_transaction,
+            get_pending_relay_txs,
+            verify_relay_confirmation,
+            get_relay_reputation,
+            record_relayed_tx,
+            get_relayed_history,
+            redeem_voucher,
+            get_voucher_owner,
+            get_owned_vouchers,
+            get_my_deals,
+            get_ollama_status,
+            list_ollama_models,
+            extract_pdf_text,
+            sign_content,
+            store_content,
+            get_content,
+            receive_content,
+            get_received_content,
+            match_intent_to_listings,
+            get_listing_catalogue,
+            get_relay_stats
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(move |app, event| {
+            if let tauri::RunEvent::ExitRequested { .. } | tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                state.lifecycle.begin_shutdown();
+                state.mesh.stop();
+            }
+            if let tauri::RunEvent::Exit = event {
+                drop(log_guard.take());
+            }
+        });
+}
@@ -0,0 +1,182 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+use tokio::sync::watch;
+
+use crate::error::{AppError, AppResult};
+
+/// The pieces of the node that come up independently and can be retried on
+/// their own without restarting the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Mesh,
+    BridgeSync,
+    Ollama,
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subsystem::Mesh => write!(f, "mesh"),
+            Subsystem::BridgeSync => write!(f, "bridge sync"),
+            Subsystem::Ollama => write!(f, "ollama"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubsystemStatus {
+    Starting,
+    Up,
+    Down(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DegradedReason {
+    pub subsystem: Subsystem,
+    pub message: String,
+}
+
+/// Whole-node lifecycle, derived from the subsystem statuses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum NodeState {
+    /// At least one subsystem is still starting.
+    Booting,
+    /// Everything finished starting, but some subsystems are down.
+    Degraded { reasons: Vec<DegradedReason> },
+    Ready,
+    ShuttingDown,
+}
+
+/// Tracks each subsystem's status and publishes the derived `NodeState` on a
+/// watch channel whenever it changes.
+pub struct Lifecycle {
+    subsystems: RwLock<BTreeMap<Subsystem, SubsystemStatus>>,
+    shutting_down: RwLock<bool>,
+    tx: watch::Sender<NodeState>,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lifecycle {
+    pub fn new() -> Self {
+        let subsystems = [Subsystem::Mesh, Subsystem::BridgeSync, Subsystem::Ollama]
+            .into_iter()
+            .map(|s| (s, SubsystemStatus::Starting))
+            .collect();
+        Lifecycle {
+            subsystems: RwLock::new(subsystems),
+            shutting_down: RwLock::new(false),
+            tx: watch::channel(NodeState::Booting).0,
+        }
+    }
+
+    pub fn state(&self) -> NodeState {
+        if *self.shutting_down.read().unwrap_or_else(PoisonError::into_inner) {
+            return NodeState::ShuttingDown;
+        }
+        let subsystems = self.subsystems.read().unwrap_or_else(PoisonError::into_inner);
+        if subsystems.values().any(|s| *s == SubsystemStatus::Starting) {
+            return NodeState::Booting;
+        }
+        let reasons: Vec<DegradedReason> = subsystems
+            .iter()
+            .filter_map(|(subsystem, status)| match status {
+                SubsystemStatus::Down(message) => Some(DegradedReason { subsystem: *subsystem, message: message.clone() }),
+                _ => None,
+            })
+            .collect();
+        if reasons.is_empty() {
+            NodeState::Ready
+        } else {
+            NodeState::Degraded { reasons }
+        }
+    }
+
+    pub fn status(&self, subsystem: Subsystem) -> SubsystemStatus {
+        self.subsystems
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&subsystem)
+            .cloned()
+            .unwrap_or(SubsystemStatus::Starting)
+    }
+
+    pub fn set(&self, subsystem: Subsystem, status: SubsystemStatus) {
+        self.subsystems.write().unwrap_or_else(PoisonError::into_inner).insert(subsystem, status);
+        self.publish();
+    }
+
+    pub fn begin_shutdown(&self) {
+        *self.shutting_down.write().unwrap_or_else(PoisonError::into_inner) = true;
+        self.publish();
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<NodeState> {
+        self.tx.subscribe()
+    }
+
+    /// Fails with a typed `not_ready` error unless `subsystem` is up.
+    pub fn require(&self, subsystem: Subsystem) -> AppResult<()> {
+        if *self.shutting_down.read().unwrap_or_else(PoisonError::into_inner) {
+            return Err(AppError::NotReady { subsystem, reason: "node is shutting down".to_string() });
+        }
+        match self.status(subsystem) {
+            SubsystemStatus::Up => Ok(()),
+            SubsystemStatus::Starting => Err(AppError::NotReady { subsystem, reason: "still starting".to_string() }),
+            SubsystemStatus::Down(reason) => Err(AppError::NotReady { subsystem, reason }),
+        }
+    }
+
+    fn publish(&self) {
+        let state = self.state();
+        self.tx.send_if_modified(|current| {
+            if *current == state {
+                return false;
+            }
+            *current = state;
+            true
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_node_state_from_subsystems() {
+        let lifecycle = Lifecycle::new();
+        let mut rx = lifecycle.subscribe();
+        assert_eq!(lifecycle.state(), NodeState::Booting);
+        assert_eq!(lifecycle.require(Subsystem::Mesh).unwrap_err().code(), "not_ready");
+
+        lifecycle.set(Subsystem::BridgeSync, SubsystemStatus::Up);
+        lifecycle.set(Subsystem::Ollama, SubsystemStatus::Up);
+        lifecycle.set(Subsystem::Mesh, SubsystemStatus::Down("mDNS bind failed".to_string()));
+        assert_eq!(
+            lifecycle.state(),
+            NodeState::Degraded {
+                reasons: vec![DegradedReason { subsystem: Subsystem::Mesh, message: "mDNS bind failed".to_string() }]
+            }
+        );
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(*rx.borrow_and_update(), lifecycle.state());
+
+        // A successful retry of the failed subsystem brings the node to Ready.
+        lifecycle.set(Subsystem::Mesh, SubsystemStatus::Up);
+        assert_eq!(lifecycle.state(), NodeState::Ready);
+        assert!(lifecycle.require(Subsystem::Mesh).is_ok());
+
+        lifecycle.begin_shutdown();
+        assert_eq!(lifecycle.state(), NodeState::ShuttingDown);
+        assert!(lifecycle.require(Subsystem::Mesh).is_err());
+    }
+}
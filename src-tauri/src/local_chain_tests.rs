@@ -0,0 +1,266 @@
+//! End-to-end tests of `BlockchainBridge` against the real Escrow,
+//! Marketplace and CabalMeshVoucher bytecode on a throwaway local node.
+//!
+//! Each test starts its own `anvil` (or, failing that, the Hardhat node from
+//! `contracts/node_modules`) on a free port, deploys from the node's unlocked
+//! dev account and funds fresh bridge identities from it. Bytecode comes from
+//! the Hardhat artifacts, so run `npx hardhat compile` in `contracts/` first.
+//! Without a node binary or the artifacts the tests print why and pass.
+
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{utils::parse_ether, Address, Bytes, U256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    sol_types::SolValue,
+};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::blockchain_bridge::{BlockchainBridge, TxResult};
+use crate::mock_chain::test_bridge;
+use crate::rpc_pool::RpcPool;
+
+/// Hardhat compiles before it starts serving, so give it a while.
+const NODE_STARTUP: Duration = Duration::from_secs(90);
+
+fn contracts_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../contracts")
+}
+
+/// A dev node process, killed on drop.
+struct LocalNode {
+    process: Child,
+    url: String,
+    provider: DynProvider,
+    dev: Address,
+}
+
+impl Drop for LocalNode {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+impl LocalNode {
+    /// `None` (after saying why) when no node binary can be started.
+    async fn start() -> Option<LocalNode> {
+        let port = TcpListener::bind("127.0.0.1:0").ok()?.local_addr().ok()?.port();
+        let process = match Command::new("anvil")
+            .args(["--port", &port.to_string(), "--silent"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(_) => {
+                let hardhat = contracts_dir().join("node_modules/.bin/hardhat");
+                if !hardhat.exists() {
+                    eprintln!("skipping: neither `anvil` nor {} is available", hardhat.display());
+                    return None;
+                }
+                Command::new(hardhat)
+                    .args(["node", "--port", &port.to_string()])
+                    .current_dir(contracts_dir())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .ok()?
+            }
+        };
+
+        let url = format!("http://127.0.0.1:{}", port);
+        let provider = ProviderBuilder::new().connect_http(url.parse().ok()?).erased();
+        let mut node = LocalNode { process, url, provider, dev: Address::ZERO };
+        let deadline = Instant::now() + NODE_STARTUP;
+        loop {
+            if let Ok(accounts) = node.provider.get_accounts().await {
+                node.dev = *accounts.first()?;
+                return Some(node);
+            }
+            if Instant::now() > deadline || matches!(node.process.try_wait(), Ok(Some(_))) {
+                eprintln!("skipping: local node on {} never answered", node.url);
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+
+    /// Sends from the node's unlocked dev account and waits for the receipt.
+    async fn send_from_dev(&self, tx: TransactionRequest) -> alloy::rpc::types::TransactionReceipt {
+        let tx = tx.with_from(self.dev);
+        self.provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap()
+    }
+
+    async fn deploy(&self, bytecode: Bytes, constructor_args: Vec<u8>) -> Address {
+        let code: Bytes = [bytecode.as_ref(), &constructor_args].concat().into();
+        let receipt = self.send_from_dev(TransactionRequest::default().with_deploy_code(code)).await;
+        assert!(receipt.status(), "deployment reverted");
+        receipt.contract_address.expect("deployment receipt has a contract address")
+    }
+
+    async fn fund(&self, address: &str, avax: &str) {
+        let tx = TransactionRequest::default()
+            .with_to(Address::from_str(address).unwrap())
+            .with_value(parse_ether(avax).unwrap());
+        assert!(self.send_from_dev(tx).await.status());
+    }
+
+    /// Moves the node's clock forward and mines a block at the new time.
+    async fn advance_time(&self, secs: u64) {
+        let _: serde_json::Value = self.provider.raw_request("evm_increaseTime".into(), (secs,)).await.unwrap();
+        let _: serde_json::Value = self.provider.raw_request("evm_mine".into(), ()).await.unwrap();
+    }
+}
+
+/// Creation bytecode from `contracts/artifacts`, or `None` if not compiled.
+fn artifact_bytecode(name: &str) -> Option<Bytes> {
+    let path = contracts_dir().join(format!("artifacts/contracts/{name}.sol/{name}.json"));
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        eprintln!("skipping: {} missing (run `npx hardhat compile` in contracts/)", path.display());
+        return None;
+    };
+    let artifact: serde_json::Value = serde_json::from_str(&json).ok()?;
+    Bytes::from_str(artifact["bytecode"].as_str()?).ok()
+}
+
+/// A node with all three contracts deployed.
+struct Deployment {
+    node: LocalNode,
+    escrow: Address,
+    marketplace: Address,
+    voucher: Address,
+}
+
+impl Deployment {
+    async fn start() -> Option<Deployment> {
+        let node = LocalNode::start().await?;
+        let escrow_code = artifact_bytecode("Escrow")?;
+        let marketplace_code = artifact_bytecode("Marketplace")?;
+        let voucher_code = artifact_bytecode("CabalMeshVoucher")?;
+
+        let escrow = node.deploy(escrow_code, Vec::new()).await;
+        let voucher = node.deploy(voucher_code, Vec::new()).await;
+        let marketplace = node.deploy(marketplace_code, voucher.abi_encode()).await;
+        Some(Deployment { node, escrow, marketplace, voucher })
+    }
+
+    /// A bridge on this node with a fresh identity holding `avax`.
+    async fn bridge(&self, name: &str, avax: &str) -> BlockchainBridge {
+        let bridge = test_bridge(
+            Arc::new(RpcPool::new(vec![self.node.url.clone()], Default::default())),
+            name,
+            Some(self.escrow),
+            Some(self.marketplace),
+            Some(self.voucher),
+        );
+        self.node.fund(&bridge.get_primary_address(), avax).await;
+        bridge
+    }
+}
+
+async fn deal_status(bridge: &BlockchainBridge, deal_id: u64) -> (String, String) {
+    let deals = bridge.get_my_deals(&bridge.get_primary_address()).await.unwrap();
+    let deal = deals.into_iter().find(|d| d.deal_id == deal_id).expect("deal is listed for its party");
+    (deal.status, deal.role)
+}
+
+/// Mints and lists a voucher, returning `(token_id, listing_id)`.
+async fn list_voucher(seller: &BlockchainBridge, description: &str, price: U256) -> (u64, u64) {
+    let token_id = seller.mint_voucher("document", description).await.unwrap();
+    seller.approve_voucher(token_id).await.unwrap();
+    let listing_id = seller.create_asset_listing(description, price, token_id).await.unwrap();
+    (token_id, listing_id)
+}
+
+#[tokio::test]
+async fn marketplace_buy_release_refund_and_redeem() {
+    let Some(chain) = Deployment::start().await else { return };
+    let seller = chain.bridge("lc_seller", "1").await;
+    let buyer = chain.bridge("lc_buyer", "5").await;
+    let price = parse_ether("0.5").unwrap();
+
+    let (first_token, first_listing) = list_voucher(&seller, "Q3 report", price).await;
+    let (second_token, second_listing) = list_voucher(&seller, "Q4 report", price).await;
+    assert_eq!((first_token, first_listing), (1, 1));
+    assert_eq!((second_token, second_listing), (2, 2));
+    let listed: Vec<u64> = buyer.get_active_asset_listings().await.unwrap().iter().map(|l| l.id).collect();
+    assert_eq!(listed, vec![1, 2]);
+
+    let Ok(TxResult::Confirmed { id: released }) = buyer.buy_listing(first_listing, price).await else {
+        panic!("buying listing {} should confirm", first_listing);
+    };
+    let Ok(TxResult::Confirmed { id: refunded }) = buyer.buy_listing(second_listing, price).await else {
+        panic!("buying listing {} should confirm", second_listing);
+    };
+    assert_eq!((released, refunded), (1, 2));
+    assert!(buyer.get_active_asset_listings().await.unwrap().is_empty());
+    assert_eq!(deal_status(&buyer, released).await, ("active".to_string(), "buyer".to_string()));
+    assert_eq!(deal_status(&seller, released).await, ("active".to_string(), "seller".to_string()));
+
+    buyer.release_deal(released).await.unwrap();
+    buyer.refund_deal(refunded).await.unwrap();
+    assert_eq!(deal_status(&buyer, released).await.0, "released");
+    assert_eq!(deal_status(&buyer, refunded).await.0, "refunded");
+
+    let buyer_address = buyer.get_primary_address();
+    assert_eq!(buyer.get_voucher_owner(first_token).await.unwrap(), buyer_address);
+    assert_eq!(seller.get_voucher_owner(second_token).await.unwrap(), seller.get_primary_address());
+
+    buyer.redeem_voucher(first_token).await.unwrap();
+    assert!(buyer.get_owned_vouchers(&buyer_address).await.unwrap().is_empty());
+    assert!(buyer.get_voucher_owner(first_token).await.is_err());
+}
+
+#[tokio::test]
+async fn escrow_create_release_and_refund() {
+    let Some(chain) = Deployment::start().await else { return };
+    let depositor = chain.bridge("lc_depositor", "5").await;
+    let payee = chain.bridge("lc_payee", "1").await;
+    let payee_address = payee.get_primary_address();
+    let amount = parse_ether("1").unwrap();
+
+    let Ok(TxResult::Confirmed { id: released }) = depositor.create_escrow(&payee_address, amount, 0).await else {
+        panic!("create_escrow should confirm");
+    };
+    assert_eq!(released, 1);
+    let status = depositor.get_escrow_status(released).await.unwrap();
+    assert_eq!((status["status"].as_u64(), status["payee"].as_str()), (Some(1), Some(payee_address.as_str())));
+    assert!(payee.release_escrow(released).await.is_err());
+    depositor.release_escrow(released).await.unwrap();
+    assert_eq!(depositor.get_escrow_status(released).await.unwrap()["status"], 2);
+
+    let Ok(TxResult::Confirmed { id: cancelled }) = depositor.create_escrow(&payee_address, amount, 0).await else {
+        panic!("create_escrow should confirm");
+    };
+    depositor.refund_escrow(cancelled).await.unwrap();
+    assert_eq!(depositor.get_escrow_status(cancelled).await.unwrap()["status"], 3);
+
+    let expiry = chrono::Utc::now().timestamp() as u64 + 3600;
+    let Ok(TxResult::Confirmed { id: expired }) = depositor.create_escrow(&payee_address, amount, expiry).await else {
+        panic!("create_escrow should confirm");
+    };
+    assert_eq!(expired, 3);
+    assert!(payee.refund_escrow(expired).await.is_err(), "payee can't refund before expiry");
+    chain.node.advance_time(7200).await;
+    payee.refund_escrow(expired).await.unwrap();
+    assert_eq!(depositor.get_escrow_status(expired).await.unwrap()["status"], 3);
+}
+
+/// The node's `eth_getProof` account proofs verify against its state root,
+/// and the anchor block is one the node can date.
+#[tokio::test]
+async fn anchors_balances_with_account_proofs() {
+    let Some(node) = LocalNode::start().await else { return };
+    let bridge = test_bridge(Arc::new(RpcPool::new(vec![node.url.clone()], Default::default())), "lc_anchor", None, None, None);
+    node.fund(&bridge.get_primary_address(), "2").await;
+
+    let anchor = bridge.anchored_balance().await.unwrap();
+    assert_eq!(anchor.balance, parse_ether("2").unwrap());
+    assert_eq!(bridge.block_age(anchor.block_hash).await.unwrap(), Some(0));
+}
@@ -0,0 +1,322 @@
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::field::{Field, Visit};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::field::{MakeVisitor, VisitFmt, VisitOutput};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::{DefaultFields, Writer};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+use crate::error::{AppError, AppResult};
+
+/// Log files live in `<data_dir>/logs`, one per day, named
+/// `cabalmesh.<date>.log`; only the newest `MAX_LOG_FILES` are kept.
+pub const LOG_DIR: &str = "logs";
+const LOG_PREFIX: &str = "cabalmesh";
+const MAX_LOG_FILES: usize = 7;
+/// Overrides the file filter, in `RUST_LOG` syntax.
+const FILTER_ENV: &str = "CABALMESH_LOG";
+const DEFAULT_FILTER: &str = "info,libp2p=warn";
+
+/// How many of the newest log lines `export_diagnostics` includes.
+const EXPORT_MAX_LINES: usize = 5000;
+
+const REDACTED: &str = "[redacted]";
+/// Field names (or `_`-suffixes of them, e.g. `wallet_private_key`) whose
+/// values never reach a log sink. Secrets should simply not be logged; this
+/// catches the ones that slip through as structured fields.
+const SECRET_FIELDS: &[&str] = &[
+    "private_key",
+    "secret",
+    "password",
+    "mnemonic",
+    "token",
+    "encryption_key",
+    "session_key",
+    "balance",
+    "price_ceiling",
+];
+
+/// Installs the global subscriber: JSON lines to the rotating files in
+/// `<data_dir>/logs`, and human-readable lines to stderr at `stderr_level`.
+/// Both sinks go through redaction: each file line once it's formatted, and
+/// each stderr field as it's recorded. Logs written before this are dropped.
+///
+/// The returned guard flushes the file writer when dropped; keep it alive
+/// until the process exits.
+pub fn init(data_dir: &Path, stderr_level: LevelFilter) -> AppResult<WorkerGuard> {
+    let dir = data_dir.join(LOG_DIR);
+    fs::create_dir_all(&dir)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| AppError::Storage(format!("log dir {}: {}", dir.display(), e)))?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+    let file_filter = EnvFilter::try_from_env(FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_writer(Redacting(file_writer))
+                .with_filter(file_filter),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_target(false)
+                .fmt_fields(RedactingFields)
+                .with_writer(io::stderr)
+                .with_filter(
+                    EnvFilter::builder().with_default_directive(stderr_level.into()).parse_lossy("libp2p=warn"),
+                ),
+        )
+        .try_init()
+        .map_err(|e| AppError::Storage(format!("logging already initialised: {}", e)))?;
+    Ok(guard)
+}
+
+/// Writes a single JSON bug-report bundle to `<data_dir>/diagnostics`: build
+/// and platform info, the caller's `context` (node state, settings, ...), and
+/// the newest log lines. Log lines were redacted when written and are
+/// redacted again here, so the bundle is safe to attach to an issue.
+pub fn export_diagnostics(data_dir: &Path, context: Value) -> AppResult<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(data_dir.join(LOG_DIR)) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(LOG_PREFIX)))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    // Dated names sort chronologically; walk newest first until we have enough.
+    files.sort();
+    let mut lines: Vec<Value> = Vec::new();
+    for path in files.iter().rev() {
+        if lines.len() >= EXPORT_MAX_LINES {
+            break;
+        }
+        let raw = fs::read_to_string(path)?;
+        let mut chunk: Vec<Value> = raw
+            .lines()
+            .rev()
+            .take(EXPORT_MAX_LINES - lines.len())
+            .map(|line| {
+                let mut value = serde_json::from_str(line).unwrap_or_else(|_| Value::String(line.to_string()));
+                redact_value(&mut value);
+                value
+            })
+            .collect();
+        lines.append(&mut chunk);
+    }
+    lines.reverse();
+
+    let mut context = context;
+    redact_value(&mut context);
+    let generated_at = chrono::Utc::now();
+    let bundle = json!({
+        "generated_at": generated_at.to_rfc3339(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "context": context,
+        "log_files": files.iter().filter_map(|p| p.file_name()?.to_str().map(str::to_string)).collect::<Vec<_>>(),
+        "logs": lines,
+    });
+
+    let out_dir = data_dir.join("diagnostics");
+    fs::create_dir_all(&out_dir)?;
+    let path = out_dir.join(format!("cabalmesh-diagnostics-{}.json", generated_at.format("%Y%m%dT%H%M%SZ")));
+    fs::write(&path, serde_json::to_string_pretty(&bundle)?)?;
+    tracing::info!(path = %path.display(), lines = bundle["logs"].as_array().map_or(0, Vec::len), "diagnostics exported");
+    Ok(path)
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_FIELDS.iter().any(|f| key == *f || key.ends_with(&format!("_{}", f)))
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_secret(key) {
+                    *v = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(v);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Redacts one JSON line from the file layer. Anything else is passed
+/// through as is.
+fn redact_line(line: &str) -> String {
+    match serde_json::from_str::<Value>(line.trim_end_matches('\n')) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            format!("{}\n", value)
+        }
+        Err(_) => line.to_string(),
+    }
+}
+
+/// `MakeWriter` wrapper that redacts every JSON event before it reaches `M`.
+/// The fmt layer formats each event into one buffer and writes it in a
+/// single call, so each `write` sees a whole line.
+struct Redacting<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_all(redact_line(&String::from_utf8_lossy(buf)).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// The text formatter's fields, with the values of secret ones replaced as
+/// they're recorded, so a value is hidden whole however it would print.
+struct RedactingFields;
+
+impl<'a> MakeVisitor<Writer<'a>> for RedactingFields {
+    type Visitor = RedactingVisitor<<DefaultFields as MakeVisitor<Writer<'a>>>::Visitor>;
+
+    fn make_visitor(&self, target: Writer<'a>) -> Self::Visitor {
+        RedactingVisitor(DefaultFields::new().make_visitor(target))
+    }
+}
+
+struct RedactingVisitor<V>(V);
+
+impl<V: Visit> Visit for RedactingVisitor<V> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if is_secret(field.name()) {
+            self.record_debug(field, &value);
+        } else {
+            self.0.record_str(field, value);
+        }
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        if is_secret(field.name()) {
+            self.record_debug(field, &value);
+        } else {
+            self.0.record_error(field, value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if is_secret(field.name()) {
+            self.0.record_debug(field, &format_args!("{}", REDACTED));
+        } else {
+            self.0.record_debug(field, value);
+        }
+    }
+}
+
+impl<V: VisitOutput<fmt::Result>> VisitOutput<fmt::Result> for RedactingVisitor<V> {
+    fn finish(self) -> fmt::Result {
+        self.0.finish()
+    }
+}
+
+impl<V: VisitFmt> VisitFmt for RedactingVisitor<V> {
+    fn writer(&mut self) -> &mut dyn fmt::Write {
+        self.0.writer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn redacts_secret_fields_and_exports_bundle() {
+        let json_line = r#"{"level":"INFO","fields":{"message":"proof","bid_amount":90,"balance":1000},"span":{"name":"generate_proof","price_ceiling":95,"token_id":7}}"#;
+        let redacted: Value = serde_json::from_str(&redact_line(json_line)).unwrap();
+        assert_eq!(redacted["fields"]["balance"], REDACTED);
+        assert_eq!(redacted["fields"]["bid_amount"], 90);
+        assert_eq!(redacted["span"]["price_ceiling"], REDACTED);
+        assert_eq!(redacted["span"]["token_id"], 7);
+
+
+        let dir = std::env::temp_dir().join(format!("cabalmesh_logging_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(LOG_DIR)).unwrap();
+        fs::write(dir.join(LOG_DIR).join("cabalmesh.2026-01-01.log"), format!("{}\n", json_line)).unwrap();
+        fs::write(dir.join(LOG_DIR).join("cabalmesh.2026-01-02.log"), "plain line\n").unwrap();
+
+        let path = export_diagnostics(&dir, json!({ "settings": { "session_key": "abc" } })).unwrap();
+        let bundle: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(bundle["context"]["settings"]["session_key"], REDACTED);
+        let logs = bundle["logs"].as_array().unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0]["fields"]["balance"], REDACTED);
+        assert_eq!(logs[1], "plain line");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    /// Text lines hide a secret's whole value, spaces and all, in events
+    /// and in the spans around them.
+    #[test]
+    fn redacts_secret_fields_in_text_lines() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let sink = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .fmt_fields(RedactingFields)
+            .with_writer(move || Captured(sink.clone()))
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("generate_proof", price_ceiling = 95).entered();
+            tracing::info!(secret = "a b", wallet_private_key = "0xabc", bid_amount = 90, "proof");
+        });
+
+        let text = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert!(text.contains("generate_proof{price_ceiling=[redacted]}"), "{}", text);
+        assert!(text.ends_with("proof secret=[redacted] wallet_private_key=[redacted] bid_amount=90\n"), "{}", text);
+        assert!(!text.contains("a b") && !text.contains("0xabc"), "{}", text);
+    }
+
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
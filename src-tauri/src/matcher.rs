@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
+use std::sync::Arc;
 
 use crate::blockchain_bridge::AssetListingView;
+use crate::error::AppResult;
+use crate::settings::{ModelRole, SettingsStore};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct OllamaRequest {
@@ -26,29 +29,36 @@ pub struct MatchResult {
     pub price_wei: String,
     pub token_id: u64,
     pub reason: String,
+    /// Set when the match was made against a cached catalogue because the
+    /// RPC was unreachable: when that catalogue was fetched. The listing may
+    /// have sold or changed price since.
+    #[serde(default)]
+    pub stale_since: Option<DateTime<Utc>>,
 }
 
 /// Matches a buyer's free-text intent against real on-chain listings using
-/// the same local Ollama model the Shark negotiation agent already uses.
+/// the `matching_model`, which can be smaller and faster than the
+/// negotiation model. Read per request, like the Shark agent's.
 pub struct MatchAgent {
     client: Client,
-    ollama_url: String,
+    settings: Arc<SettingsStore>,
 }
 
 impl MatchAgent {
-    pub fn new(ollama_url: Option<String>) -> Self {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
         MatchAgent {
             client: Client::new(),
-            ollama_url: ollama_url.unwrap_or_else(|| "http://localhost:11434".to_string()),
+            settings,
         }
     }
 
+    #[tracing::instrument(skip_all, fields(listings = listings.len()))]
     pub async fn match_intent(
         &self,
         intent: &str,
         price_ceiling: f64,
         listings: &[AssetListingView],
-    ) -> Result<Option<MatchResult>, Box<dyn Error>> {
+    ) -> AppResult<Option<MatchResult>> {
         if listings.is_empty() {
             return Ok(None);
         }
@@ -78,8 +88,10 @@ Catalog:
             catalog
         );
 
+        let endpoint = self.settings.get().model_endpoint(ModelRole::Matching);
+        tracing::debug!(model = %endpoint.model, "asking the matching model");
         let request = OllamaRequest {
-            model: "llama2".to_string(),
+            model: endpoint.model,
             prompt: format!("Buyer intent: {}", intent),
             stream: false,
             system: Some(system_prompt),
@@ -87,7 +99,7 @@ Catalog:
 
         let response = self
             .client
-            .post(format!("{}/api/generate", self.ollama_url))
+            .post(format!("{}/api/generate", endpoint.url))
             .json(&request)
             .send()
             .await?;
@@ -110,10 +122,7 @@ Catalog:
         // Never trust the model on money — verify the price ceiling ourselves.
         let price: f64 = listing.price_avax.parse().unwrap_or(f64::MAX);
         if price > price_ceiling {
-            println!(
-                "⚠️  Match rejected: listing {} price {} AVAX exceeds ceiling {} AVAX",
-                matched_id, price, price_ceiling
-            );
+            tracing::info!(listing_id = matched_id, price, "match rejected: listing exceeds the price ceiling");
             return Ok(None);
         }
 
@@ -125,6 +134,7 @@ Catalog:
             price_wei: listing.price_wei.clone(),
             token_id: listing.token_id,
             reason: parsed["reason"].as_str().unwrap_or("Matched").to_string(),
+            stale_since: None,
         }))
     }
 }
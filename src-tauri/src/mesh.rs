@@ -2,17 +2,22 @@ use futures::StreamExt;
 use libp2p::{
     gossipsub, mdns, noise,
     swarm::{NetworkBehaviour, SwarmEvent},
-    tcp, yamux, Swarm, SwarmBuilder,
+    tcp, yamux, Multiaddr, Swarm, SwarmBuilder,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
-use std::error::Error;
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
+use crate::catalogue::SignedCatalogue;
+use crate::error::{AppError, AppResult};
+use crate::relay_claim::SignedRelayClaim;
+use crate::settings::Settings;
+use crate::zk_handler::BidProof;
+
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "MeshBehaviourEvent")]
 pub struct MeshBehaviour {
@@ -45,6 +50,10 @@ pub struct PrivacyIntent {
     pub encrypted: bool,
     pub relay_path: Vec<String>,
     pub relay_fee: Option<String>,
+    /// Backs the bid of a trade intent. Receivers verify it before the
+    /// intent reaches the UI (`IntentReceived::proof_verified`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bid_proof: Option<BidProof>,
 }
 
 pub struct MeshNetwork {
@@ -54,15 +63,18 @@ pub struct MeshNetwork {
     /// with the Tauri command layer so Relay Mode can show actual traffic
     /// instead of a simulated number.
     pub relay_bytes: Arc<AtomicU64>,
+    listen_addr: Multiaddr,
 }
 
 impl MeshNetwork {
-    pub async fn new() -> Result<Self, Box<dyn Error>> {
+    pub async fn new(settings: &Settings) -> AppResult<Self> {
+        let listen_addr: Multiaddr = settings.mesh_listen_addr.parse().map_err(AppError::mesh)?;
+
         // Generate ephemeral keypair for "Nobody" identity
         let local_key = libp2p::identity::Keypair::generate_ed25519();
         let local_peer_id = local_key.public().to_peer_id();
         
-        println!("🔐 Ephemeral PeerID generated: {}", local_peer_id);
+        tracing::info!(peer_id = %local_peer_id, "ephemeral mesh identity generated");
 
         // Configure Gossipsub for Privacy Intent broadcasting
         let message_id_fn = |message: &gossipsub::Message| {
@@ -76,19 +88,20 @@ impl MeshNetwork {
             .validation_mode(gossipsub::ValidationMode::Strict)
             .message_id_fn(message_id_fn)
             .build()
-            .map_err(|msg| std::io::Error::new(std::io::ErrorKind::Other, msg))?;
+            .map_err(AppError::mesh)?;
 
         let mut gossipsub = gossipsub::Behaviour::new(
             gossipsub::MessageAuthenticity::Signed(local_key.clone()),
             gossipsub_config,
-        )?;
+        )
+        .map_err(AppError::mesh)?;
 
         // Create the Privacy Intent topic
-        let topic = gossipsub::IdentTopic::new("cabalmesh-privacy-intents");
-        gossipsub.subscribe(&topic)?;
+        let topic = gossipsub::IdentTopic::new(settings.mesh_topic.clone());
+        gossipsub.subscribe(&topic).map_err(AppError::mesh)?;
 
         // Set up mDNS for local peer discovery (ShadowWire mesh)
-        let mdns = mdns::tokio::Behaviour::new(mdns::Config::default(), local_peer_id)?;
+        let mdns = mdns::tokio::Behaviour::new(mdns::Config::default(), local_peer_id).map_err(AppError::mesh)?;
 
         let behaviour = MeshBehaviour { mdns, gossipsub };
 
@@ -99,29 +112,32 @@ impl MeshNetwork {
                 tcp::Config::default(),
                 noise::Config::new,
                 yamux::Config::default,
-            )?
-            .with_behaviour(|_| behaviour)?
+            )
+            .map_err(AppError::mesh)?
+            .with_behaviour(|_| behaviour)
+            .map_err(AppError::mesh)?
             .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(60)))
             .build();
 
-        Ok(MeshNetwork { swarm, topic, relay_bytes: Arc::new(AtomicU64::new(0)) })
+        Ok(MeshNetwork { swarm, topic, relay_bytes: Arc::new(AtomicU64::new(0)), listen_addr })
     }
 
+    #[tracing::instrument(name = "mesh", skip_all, fields(peer_id = %self.swarm.local_peer_id()))]
     pub async fn start(
         &mut self, 
         tx: mpsc::UnboundedSender<MeshEvent>,
         mut intent_rx: mpsc::UnboundedReceiver<PrivacyIntent>
-    ) -> Result<(), Box<dyn Error>> {
-        // Listen on all interfaces (offline-first mesh)
-        self.swarm.listen_on("/ip4/0.0.0.0/tcp/0".parse()?)?;
+    ) -> AppResult<()> {
+        // Listen on all interfaces by default (offline-first mesh)
+        self.swarm.listen_on(self.listen_addr.clone()).map_err(AppError::mesh)?;
 
         loop {
             tokio::select! {
                 // Handle incoming intents to broadcast
                 Some(intent) = intent_rx.recv() => {
-                    println!("📤 Broadcasting intent to mesh: {:?}", intent);
+                    tracing::debug!(intent_type = %intent.intent_type, bytes = intent.payload.len(), "broadcasting intent");
                     if let Err(e) = self.broadcast_intent(intent) {
-                        eprintln!("❌ Failed to broadcast intent: {}", e);
+                        tracing::error!(error = %e, "failed to broadcast intent");
                     }
                 }
 
@@ -129,13 +145,13 @@ impl MeshNetwork {
                 event = self.swarm.select_next_some() => {
                     match event {
                         SwarmEvent::NewListenAddr { address, .. } => {
-                            println!("📡 Listening on {}", address);
+                            tracing::info!(%address, "mesh listening");
                             let _ = tx.send(MeshEvent::ListeningStarted { address: address.to_string() });
                         }
                         SwarmEvent::Behaviour(event) => match event {
                             MeshBehaviourEvent::Mdns(mdns::Event::Discovered(list)) => {
                                 for (peer_id, multiaddr) in list {
-                                    println!("🔍 Peer discovered: {} at {}", peer_id, multiaddr);
+                                    tracing::info!(%peer_id, address = %multiaddr, "peer discovered");
                                     self.swarm.behaviour_mut().gossipsub.add_explicit_peer(&peer_id);
                                     let _ = tx.send(MeshEvent::PeerDiscovered {
                                         peer_id: peer_id.to_string(),
@@ -145,7 +161,7 @@ impl MeshNetwork {
                             }
                             MeshBehaviourEvent::Mdns(mdns::Event::Expired(list)) => {
                                 for (peer_id, _) in list {
-                                    println!("👻 Peer expired: {}", peer_id);
+                                    tracing::info!(%peer_id, "peer expired");
                                     self.swarm.behaviour_mut().gossipsub.remove_explicit_peer(&peer_id);
                                 }
                             }
@@ -161,12 +177,12 @@ impl MeshNetwork {
                                             let msg_type = settlement.get("type").and_then(|v| v.as_str());
 
                                             if msg_type == Some("SettlementComplete") {
-                                                println!("✅ Received Settlement Confirmation: {:?}", settlement);
+                                                tracing::info!("received settlement confirmation");
                                                 let _ = tx.send(MeshEvent::SettlementComplete {
                                                     details: settlement.to_string()
                                                 });
                                             } else if msg_type == Some("DealAccepted") {
-                                                println!("🤝 Received Deal Acceptance: {:?}", settlement);
+                                                tracing::info!("received deal acceptance");
                                                 let _ = tx.send(MeshEvent::DealAccepted {
                                                     details: settlement.to_string()
                                                 });
@@ -180,23 +196,33 @@ impl MeshNetwork {
                                             let queue_id = payload.get("queue_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let raw_tx_hex = payload.get("raw_tx_hex").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let summary = payload.get("summary").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                            println!("📡 Received relay_tx request: {} ({})", queue_id, summary);
+                                            tracing::info!(%queue_id, %summary, "received relay_tx request");
                                             let _ = tx.send(MeshEvent::RelayTxReceived { queue_id, raw_tx_hex, summary });
                                         }
                                     } else if intent.intent_type == "relay_confirmed" {
                                         // A relay peer is reporting back the outcome of a relay_tx it submitted.
-                                        if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&intent.payload) {
-                                            let queue_id = payload.get("queue_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                            let status = payload.get("status").and_then(|v| v.as_str()).unwrap_or("failed").to_string();
-                                            let tx_hash = payload.get("tx_hash").and_then(|v| v.as_str()).map(|s| s.to_string());
-                                            println!("📨 Received relay_confirmed: {} -> {}", queue_id, status);
-                                            let _ = tx.send(MeshEvent::RelayConfirmed { queue_id, status, tx_hash });
+                                        // The claim is signed by the relayer's wallet identity, which outlives
+                                        // our ephemeral peer ids, so the bridge can hold it accountable if the
+                                        // claim doesn't check out on-chain. Unsigned claims are dropped.
+                                        if let Ok(claim) = serde_json::from_str::<SignedRelayClaim>(&intent.payload) {
+                                            match claim.verify() {
+                                                Ok(relayer) => {
+                                                    tracing::info!(queue_id = %claim.queue_id, status = %claim.status, %relayer, "received relay_confirmed");
+                                                    let _ = tx.send(MeshEvent::RelayConfirmed {
+                                                        queue_id: claim.queue_id,
+                                                        status: claim.status,
+                                                        tx_hash: claim.tx_hash,
+                                                        relayer: Some(relayer.to_string()),
+                                                    });
+                                                }
+                                                Err(e) => tracing::warn!(error = %e, "dropped relay_confirmed with a bad signature"),
+                                            }
                                         }
                                     } else if intent.intent_type == "content_request" {
                                         // A buyer is asking whoever sold this tokenId to deliver the content.
                                         if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&intent.payload) {
                                             if let Some(token_id) = payload.get("token_id").and_then(|v| v.as_u64()) {
-                                                println!("📨 Received content_request for token #{}", token_id);
+                                                tracing::info!(token_id, "received content_request");
                                                 let _ = tx.send(MeshEvent::ContentRequested { token_id });
                                             }
                                         }
@@ -207,13 +233,23 @@ impl MeshNetwork {
                                             let text = payload.get("text").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let signature = payload.get("signature").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let signer_address = payload.get("signer_address").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                            println!("📬 Received content_delivery for token #{}", token_id);
+                                            tracing::info!(token_id, "received content_delivery");
                                             let _ = tx.send(MeshEvent::ContentDelivered { token_id, text, signature, signer_address });
                                         }
+                                    } else if intent.intent_type == "catalogue_request" {
+                                        // An offline peer wants someone's cached listing catalogue to match against.
+                                        tracing::info!("received catalogue_request");
+                                        let _ = tx.send(MeshEvent::CatalogueRequested);
+                                    } else if intent.intent_type == "catalogue_snapshot" {
+                                        // A peer shared its signed catalogue; the bridge verifies it before caching.
+                                        if let Ok(snapshot) = serde_json::from_str::<SignedCatalogue>(&intent.payload) {
+                                            tracing::info!(signer = %snapshot.signer, listings = snapshot.listings.len(), "received catalogue_snapshot");
+                                            let _ = tx.send(MeshEvent::CatalogueReceived { snapshot });
+                                        }
                                     } else {
                                         // Regular trade intent
-                                        println!("📬 Received Intent: {:?}", intent);
-                                        let _ = tx.send(MeshEvent::IntentReceived { intent });
+                                        tracing::info!(intent_type = %intent.intent_type, hops = intent.relay_path.len(), "received intent");
+                                        let _ = tx.send(MeshEvent::IntentReceived { intent, proof_verified: None });
                                     }
                                 } else {
                                     // Try to parse as raw settlement confirmation
@@ -222,12 +258,12 @@ impl MeshNetwork {
                                         let msg_type = settlement.get("type").and_then(|v| v.as_str());
                                         
                                         if msg_type == Some("SettlementComplete") {
-                                            println!("✅ Received Settlement Confirmation: {:?}", settlement);
+                                            tracing::info!("received settlement confirmation");
                                             let _ = tx.send(MeshEvent::SettlementComplete { 
                                                 details: settlement.to_string() 
                                             });
                                         } else if msg_type == Some("DealAccepted") {
-                                            println!("🤝 Received Deal Acceptance: {:?}", settlement);
+                                            tracing::info!("received deal acceptance");
                                             let _ = tx.send(MeshEvent::DealAccepted { 
                                                 details: settlement.to_string() 
                                             });
@@ -244,9 +280,9 @@ impl MeshNetwork {
         }
     }
 
-    pub fn broadcast_intent(&mut self, intent: PrivacyIntent) -> Result<(), Box<dyn Error>> {
+    pub fn broadcast_intent(&mut self, intent: PrivacyIntent) -> AppResult<()> {
         if !Self::verify_relay_integrity(&intent) {
-            return Err("Integrity check failed: Malformed relay path".into());
+            return Err(AppError::mesh("integrity check failed: malformed relay path"));
         }
 
         let payload = serde_json::to_vec(&intent)?;
@@ -258,18 +294,18 @@ impl MeshNetwork {
         {
             Ok(_) => {
                 self.relay_bytes.fetch_add(payload_len, Ordering::Relaxed);
-                println!("📤 Intent broadcasted to mesh (Relay Hop: {})", intent.relay_path.len());
+                tracing::debug!(hops = intent.relay_path.len(), bytes = payload_len, "intent broadcast");
                 Ok(())
             }
             Err(gossipsub::PublishError::InsufficientPeers) => {
-                println!("⚠️  Note: No peers connected (Single-Node Mode). Intent processed locally.");
+                tracing::info!("no peers connected (single-node mode), intent processed locally");
                 Ok(())
             }
-            Err(e) => Err(Box::new(e))
+            Err(e) => Err(AppError::mesh(e))
         }
     }
 
-    pub fn broadcast_raw(&mut self, message: String) -> Result<(), Box<dyn Error>> {
+    pub fn broadcast_raw(&mut self, message: String) -> AppResult<()> {
         let payload = message.as_bytes().to_vec();
         match self.swarm
             .behaviour_mut()
@@ -277,14 +313,14 @@ impl MeshNetwork {
             .publish(self.topic.clone(), payload) 
         {
             Ok(_) => {
-                println!("📤 Raw message broadcasted to mesh");
+                tracing::debug!("raw message broadcast");
                 Ok(())
             }
             Err(gossipsub::PublishError::InsufficientPeers) => {
-                println!("⚠️  Note: No peers connected (Single-Node Mode).");
+                tracing::info!("no peers connected (single-node mode)");
                 Ok(())
             }
-            Err(e) => Err(Box::new(e))
+            Err(e) => Err(AppError::mesh(e))
         }
     }
     pub fn verify_relay_integrity(intent: &PrivacyIntent) -> bool {
@@ -293,13 +329,13 @@ impl MeshNetwork {
         // 2. Relay fee should be formatted correctly (simple check for now)
         
         if intent.relay_path.is_empty() {
-            println!("❌ Integrity Check Failed: Empty relay path");
+            tracing::warn!("integrity check failed: empty relay path");
             return false;
         }
 
         if let Some(fee) = &intent.relay_fee {
             if !fee.contains("AVAX") {
-                 println!("⚠️  Warning: Unknown fee format: {}", fee);
+                 tracing::warn!(%fee, "unknown relay fee format");
                  // We don't fail validation here for now, just warn
             }
         }
@@ -314,11 +350,15 @@ impl MeshNetwork {
 pub enum MeshEvent {
     ListeningStarted { address: String },
     PeerDiscovered { peer_id: String, address: String },
-    IntentReceived { intent: PrivacyIntent },
+    /// `proof_verified` is `None` when the intent carries no bid proof, and
+    /// otherwise whether the proof verified for the bid it claims.
+    IntentReceived { intent: PrivacyIntent, proof_verified: Option<bool> },
     DealAccepted { details: String },
     SettlementComplete { details: String },
     RelayTxReceived { queue_id: String, raw_tx_hex: String, summary: String },
-    RelayConfirmed { queue_id: String, status: String, tx_hash: Option<String> },
+    RelayConfirmed { queue_id: String, status: String, tx_hash: Option<String>, relayer: Option<String> },
     ContentRequested { token_id: u64 },
     ContentDelivered { token_id: u64, text: String, signature: String, signer_address: String },
+    CatalogueRequested,
+    CatalogueReceived { snapshot: SignedCatalogue },
 }
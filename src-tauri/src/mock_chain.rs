@@ -0,0 +1,787 @@
+use alloy::{
+    consensus::{transaction::SignerRecoverable, SignableTransaction, Transaction as _, TxEip1559, TxEnvelope},
+    eips::{
+        eip2718::{Decodable2718, Encodable2718},
+        BlockId, BlockNumberOrTag,
+    },
+    network::TxSignerSync,
+    primitives::{address, keccak256, utils::parse_ether, Address, Bytes, Log, TxKind, B256, U256},
+    signers::local::PrivateKeySigner,
+    sol_types::{Revert, SolCall, SolError, SolEvent, SolInterface},
+    trie::{proof::ProofRetainer, HashBuilder, Nibbles, TrieAccount, EMPTY_ROOT_HASH, KECCAK_EMPTY},
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::blockchain_bridge::{Escrow, IEscrow, IMarketplace, IVoucher, Marketplace};
+use crate::chain_backend::{AccountProof, ChainBackend, ChainReceipt};
+use crate::rpc_pool::{EndpointStatus, RpcPoolError, RpcTimeouts};
+
+/// Same chain id as Fuji, so transactions the bridge signs offline apply
+/// here unchanged.
+pub const MOCK_CHAIN_ID: u64 = 43113;
+pub const MOCK_URL: &str = "mock://in-memory";
+/// Where the simulated contracts "live". Fixed, so a demo profile's
+/// addresses never change between runs.
+pub const MOCK_ESCROW_ADDRESS: Address = address!("0x00000000000000000000000000000000000e5c00");
+pub const MOCK_MARKETPLACE_ADDRESS: Address = address!("0x000000000000000000000000000000000a4e7000");
+pub const MOCK_VOUCHER_ADDRESS: Address = address!("0x0000000000000000000000000000000000f0c400");
+
+/// Block timestamp of the first block; each transaction mines a new block
+/// `BLOCK_TIME_SECS` later.
+const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+const BLOCK_TIME_SECS: u64 = 2;
+/// Avalanche's minimum base fee. Gas is priced but never charged.
+const GAS_PRICE_WEI: u128 = 25_000_000_000;
+const GAS_LIMIT: u64 = 400_000;
+/// What every account holds before it's first touched, in demo mode.
+const DEMO_STARTING_BALANCE_AVAX: &str = "100";
+
+/// Why a simulated call didn't go through: a contract revert (mined as a
+/// failed transaction) or a node-level rejection (never mined).
+enum Failure {
+    Revert(Bytes),
+    Rejected(String),
+}
+
+fn revert(message: &str) -> Failure {
+    Failure::Revert(Revert { reason: message.to_string() }.abi_encode().into())
+}
+
+fn custom_error(error: impl SolError) -> Failure {
+    Failure::Revert(error.abi_encode().into())
+}
+
+fn require(condition: bool, message: &str) -> Result<(), Failure> {
+    if condition {
+        Ok(())
+    } else {
+        Err(revert(message))
+    }
+}
+
+fn log(address: Address, event: impl SolEvent) -> Log {
+    Log { address, data: event.encode_log_data() }
+}
+
+/// A call's return data plus the logs it emitted.
+type Outcome = (Bytes, Vec<Log>);
+
+#[derive(Clone)]
+struct MinedTx {
+    raw: Bytes,
+    status: bool,
+}
+
+#[derive(Clone, Default)]
+struct ChainState {
+    starting_balance: U256,
+    balances: HashMap<Address, U256>,
+    nonces: HashMap<Address, u64>,
+    timestamp: u64,
+    txs: HashMap<B256, MinedTx>,
+    /// Timestamps of the blocks handed out by `account_proof`, by hash.
+    blocks: HashMap<B256, u64>,
+    /// The account proofs handed out, by block hash and address, so they
+    /// can be served again for that block.
+    account_proofs: HashMap<(B256, Address), AccountProof>,
+
+    escrows: BTreeMap<u64, Escrow::Deal>,
+    next_escrow_id: u64,
+
+    listings: BTreeMap<u64, Marketplace::Listing>,
+    next_listing_id: u64,
+    deals: BTreeMap<u64, Marketplace::Deal>,
+    next_deal_id: u64,
+
+    owners: HashMap<u64, Address>,
+    token_approvals: HashMap<u64, Address>,
+    operators: HashSet<(Address, Address)>,
+    vouchers: HashMap<u64, IVoucher::vouchersReturn>,
+    next_token_id: u64,
+}
+
+// Status codes shared by Escrow.Status and Marketplace.DealStatus.
+const ACTIVE: u8 = 1;
+const RELEASED: u8 = 2;
+const REFUNDED: u8 = 3;
+
+impl ChainState {
+    fn new(starting_balance: U256) -> Self {
+        ChainState {
+            starting_balance,
+            timestamp: GENESIS_TIMESTAMP,
+            next_escrow_id: 1,
+            next_listing_id: 1,
+            next_deal_id: 1,
+            next_token_id: 1,
+            ..Default::default()
+        }
+    }
+
+    fn balance(&self, address: Address) -> U256 {
+        self.balances.get(&address).copied().unwrap_or(self.starting_balance)
+    }
+
+    fn nonce(&self, address: Address) -> u64 {
+        self.nonces.get(&address).copied().unwrap_or(0)
+    }
+
+    /// Proves `address` against a state trie of every account touched so
+    /// far, and records the result as the current block.
+    fn account_proof(&mut self, address: Address) -> AccountProof {
+        let trie_account = |a: Address| TrieAccount {
+            nonce: self.nonce(a),
+            balance: self.balance(a),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let accounts: BTreeMap<B256, TrieAccount> = self
+            .balances
+            .keys()
+            .chain(self.nonces.keys())
+            .chain([&address])
+            .map(|&a| (keccak256(a), trie_account(a)))
+            .collect();
+
+        let target = Nibbles::unpack(keccak256(address));
+        let mut trie = HashBuilder::default().with_proof_retainer(ProofRetainer::from_iter([target]));
+        for (key, account) in &accounts {
+            trie.add_leaf(Nibbles::unpack(key), &alloy::rlp::encode(account));
+        }
+        let state_root = trie.root();
+        let proof = trie.take_proof_nodes().matching_nodes_sorted(&target).into_iter().map(|(_, node)| node).collect();
+
+        let account = trie_account(address);
+        let block_number = self.txs.len() as u64;
+        let block_hash = keccak256([state_root.as_slice(), &block_number.to_be_bytes(), &self.timestamp.to_be_bytes()].concat());
+        self.blocks.insert(block_hash, self.timestamp);
+        let account_proof = AccountProof {
+            block_hash,
+            block_number,
+            block_timestamp: self.timestamp,
+            state_root,
+            address,
+            balance: account.balance,
+            nonce: account.nonce,
+            storage_hash: account.storage_root,
+            code_hash: account.code_hash,
+            proof,
+        };
+        self.account_proofs.insert((block_hash, address), account_proof.clone());
+        account_proof
+    }
+
+    fn transfer(&mut self, from: Address, to: Address, amount: U256) -> Result<(), Failure> {
+        let available = self.balance(from);
+        if available < amount {
+            return Err(Failure::Rejected(format!(
+                "insufficient funds for transfer: address {} have {} want {}",
+                from, available, amount
+            )));
+        }
+        let credited = self.balance(to);
+        self.balances.insert(from, available - amount);
+        self.balances.insert(to, credited + amount);
+        Ok(())
+    }
+
+    /// Runs one call as `from`, moving `value` first. Changes are applied in
+    /// place, so callers run it on a copy and keep that only on success.
+    fn execute(&mut self, from: Address, to: Address, input: &[u8], value: U256) -> Result<Outcome, Failure> {
+        self.transfer(from, to, value)?;
+        match to {
+            MOCK_ESCROW_ADDRESS => self.escrow(from, input, value),
+            MOCK_MARKETPLACE_ADDRESS => self.marketplace(from, input, value),
+            MOCK_VOUCHER_ADDRESS => self.voucher(from, input, value),
+            _ => Ok((Bytes::new(), Vec::new())),
+        }
+    }
+
+    fn escrow(&mut self, from: Address, input: &[u8], value: U256) -> Result<Outcome, Failure> {
+        use IEscrow::IEscrowCalls as Call;
+        let call = Call::abi_decode(input).map_err(|_| Failure::Revert(Bytes::new()))?;
+        if !matches!(call, Call::createEscrow(_)) && !value.is_zero() {
+            return Err(Failure::Revert(Bytes::new())); // non-payable
+        }
+        let this = MOCK_ESCROW_ADDRESS;
+        match call {
+            Call::createEscrow(c) => {
+                require(!value.is_zero(), "No funds sent")?;
+                require(c.payee != Address::ZERO, "Invalid payee")?;
+                require(c.expiry.is_zero() || c.expiry > U256::from(self.timestamp), "Invalid expiry")?;
+                let id = self.next_escrow_id;
+                self.next_escrow_id += 1;
+                self.escrows.insert(
+                    id,
+                    Escrow::Deal { depositor: from, payee: c.payee, amount: value, expiry: c.expiry, status: ACTIVE },
+                );
+                let event = IEscrow::EscrowCreated {
+                    escrowId: U256::from(id),
+                    depositor: from,
+                    payee: c.payee,
+                    amount: value,
+                    expiry: c.expiry,
+                };
+                Ok((IEscrow::createEscrowCall::abi_encode_returns(&U256::from(id)).into(), vec![log(this, event)]))
+            }
+            Call::release(c) => {
+                let id = c.escrowId.saturating_to::<u64>();
+                let deal = self.escrows.get(&id).cloned().unwrap_or_default();
+                require(deal.status == ACTIVE, "Not active")?;
+                require(from == deal.depositor, "Only depositor")?;
+                self.escrows.entry(id).and_modify(|d| d.status = RELEASED);
+                self.transfer(this, deal.payee, deal.amount)?;
+                let event = IEscrow::EscrowReleased { escrowId: c.escrowId, payee: deal.payee, amount: deal.amount };
+                Ok((Bytes::new(), vec![log(this, event)]))
+            }
+            Call::refund(c) => {
+                let id = c.escrowId.saturating_to::<u64>();
+                let deal = self.escrows.get(&id).cloned().unwrap_or_default();
+                require(deal.status == ACTIVE, "Not active")?;
+                require(
+                    from == deal.depositor || (!deal.expiry.is_zero() && U256::from(self.timestamp) >= deal.expiry),
+                    "Not authorized or not expired",
+                )?;
+                self.escrows.entry(id).and_modify(|d| d.status = REFUNDED);
+                self.transfer(this, deal.depositor, deal.amount)?;
+                let event = IEscrow::EscrowRefunded { escrowId: c.escrowId, depositor: deal.depositor, amount: deal.amount };
+                Ok((Bytes::new(), vec![log(this, event)]))
+            }
+            Call::getDeal(c) => {
+                let deal = self.escrows.get(&c.escrowId.saturating_to()).cloned().unwrap_or_default();
+                Ok((IEscrow::getDealCall::abi_encode_returns(&deal).into(), Vec::new()))
+            }
+            Call::nextEscrowId(_) => {
+                Ok((IEscrow::nextEscrowIdCall::abi_encode_returns(&U256::from(self.next_escrow_id)).into(), Vec::new()))
+            }
+            _ => Err(Failure::Revert(Bytes::new())),
+        }
+    }
+
+    fn marketplace(&mut self, from: Address, input: &[u8], value: U256) -> Result<Outcome, Failure> {
+        use IMarketplace::IMarketplaceCalls as Call;
+        let call = Call::abi_decode(input).map_err(|_| Failure::Revert(Bytes::new()))?;
+        if !matches!(call, Call::buy(_)) && !value.is_zero() {
+            return Err(Failure::Revert(Bytes::new())); // non-payable
+        }
+        let this = MOCK_MARKETPLACE_ADDRESS;
+        match call {
+            Call::createListing(c) => {
+                require(!c.priceWei.is_zero(), "Price must be > 0")?;
+                require(!c.description.is_empty(), "Description required")?;
+                let token_id = c.tokenId.saturating_to::<u64>();
+                require(self.owner_of(token_id)? == from, "Not the voucher owner")?;
+                require(self.token_approvals.get(&token_id) == Some(&this), "Approve marketplace first")?;
+                let id = self.next_listing_id;
+                self.next_listing_id += 1;
+                self.listings.insert(
+                    id,
+                    Marketplace::Listing {
+                        seller: from,
+                        description: c.description.clone(),
+                        priceWei: c.priceWei,
+                        tokenId: c.tokenId,
+                        active: true,
+                    },
+                );
+                let event = IMarketplace::ListingCreated {
+                    id: U256::from(id),
+                    seller: from,
+                    tokenId: c.tokenId,
+                    description: c.description,
+                    priceWei: c.priceWei,
+                };
+                Ok((IMarketplace::createListingCall::abi_encode_returns(&U256::from(id)).into(), vec![log(this, event)]))
+            }
+            Call::buy(c) => {
+                let listing_id = c.listingId.saturating_to::<u64>();
+                let listing = self.listings.get(&listing_id).cloned().unwrap_or_default();
+                require(listing.active, "Not active")?;
+                require(value == listing.priceWei, "Wrong amount")?;
+                self.listings.entry(listing_id).and_modify(|l| l.active = false);
+                let mut logs = self.transfer_voucher(this, listing.seller, this, listing.tokenId.saturating_to())?;
+                let deal_id = self.next_deal_id;
+                self.next_deal_id += 1;
+                self.deals.insert(
+                    deal_id,
+                    Marketplace::Deal {
+                        buyer: from,
+                        seller: listing.seller,
+                        tokenId: listing.tokenId,
+                        amount: value,
+                        status: ACTIVE,
+                    },
+                );
+                logs.push(log(
+                    this,
+                    IMarketplace::DealCreated {
+                        dealId: U256::from(deal_id),
+                        listingId: c.listingId,
+                        buyer: from,
+                        tokenId: listing.tokenId,
+                        amount: value,
+                    },
+                ));
+                Ok((IMarketplace::buyCall::abi_encode_returns(&U256::from(deal_id)).into(), logs))
+            }
+            Call::releaseDeal(c) => self.settle_deal(from, c.dealId, RELEASED),
+            Call::refundDeal(c) => self.settle_deal(from, c.dealId, REFUNDED),
+            Call::getActiveListings(_) => {
+                let (ids, result) = self
+                    .listings
+                    .iter()
+                    .filter(|(_, l)| l.active)
+                    .map(|(id, l)| (U256::from(*id), l.clone()))
+                    .unzip();
+                let returns = IMarketplace::getActiveListingsReturn { result, ids };
+                Ok((IMarketplace::getActiveListingsCall::abi_encode_returns(&returns).into(), Vec::new()))
+            }
+            Call::getDeal(c) => {
+                let deal = self.deals.get(&c.dealId.saturating_to()).cloned().unwrap_or_default();
+                Ok((IMarketplace::getDealCall::abi_encode_returns(&deal).into(), Vec::new()))
+            }
+            Call::nextDealId(_) => {
+                Ok((IMarketplace::nextDealIdCall::abi_encode_returns(&U256::from(self.next_deal_id)).into(), Vec::new()))
+            }
+            Call::nextListingId(_) => Ok((
+                IMarketplace::nextListingIdCall::abi_encode_returns(&U256::from(self.next_listing_id)).into(),
+                Vec::new(),
+            )),
+            Call::voucher(_) => Ok((IMarketplace::voucherCall::abi_encode_returns(&MOCK_VOUCHER_ADDRESS).into(), Vec::new())),
+            _ => Err(Failure::Revert(Bytes::new())),
+        }
+    }
+
+    /// `releaseDeal` / `refundDeal`: the voucher and the funds move together.
+    fn settle_deal(&mut self, from: Address, deal_id: U256, outcome: u8) -> Result<Outcome, Failure> {
+        let this = MOCK_MARKETPLACE_ADDRESS;
+        let id = deal_id.saturating_to::<u64>();
+        let deal = self.deals.get(&id).cloned().unwrap_or_default();
+        require(deal.status == ACTIVE, "Not active")?;
+        require(from == deal.buyer, "Only buyer")?;
+        self.deals.entry(id).and_modify(|d| d.status = outcome);
+        let (voucher_to, funds_to) = if outcome == RELEASED { (deal.buyer, deal.seller) } else { (deal.seller, deal.buyer) };
+        let mut logs = self.transfer_voucher(this, this, voucher_to, deal.tokenId.saturating_to())?;
+        self.transfer(this, funds_to, deal.amount)?;
+        logs.push(if outcome == RELEASED {
+            log(this, IMarketplace::DealReleased { dealId: deal_id })
+        } else {
+            log(this, IMarketplace::DealRefunded { dealId: deal_id })
+        });
+        Ok((Bytes::new(), logs))
+    }
+
+    fn voucher(&mut self, from: Address, input: &[u8], value: U256) -> Result<Outcome, Failure> {
+        use IVoucher::IVoucherCalls as Call;
+        let call = Call::abi_decode(input).map_err(|_| Failure::Revert(Bytes::new()))?;
+        if !value.is_zero() {
+            return Err(Failure::Revert(Bytes::new())); // nothing here is payable
+        }
+        let this = MOCK_VOUCHER_ADDRESS;
+        let none = |logs| Ok((Bytes::new(), logs));
+        match call {
+            Call::mintVoucher(c) => {
+                require(!c.voucherType.is_empty(), "Voucher type required")?;
+                let token_id = self.next_token_id;
+                self.next_token_id += 1;
+                self.owners.insert(token_id, from);
+                self.vouchers.insert(
+                    token_id,
+                    IVoucher::vouchersReturn {
+                        voucherType: c.voucherType.clone(),
+                        description: c.description.clone(),
+                        mintedBy: from,
+                    },
+                );
+                let id = U256::from(token_id);
+                let logs = vec![
+                    log(this, IVoucher::Transfer { from: Address::ZERO, to: from, tokenId: id }),
+                    log(
+                        this,
+                        IVoucher::VoucherMinted { tokenId: id, owner: from, voucherType: c.voucherType, description: c.description },
+                    ),
+                ];
+                Ok((IVoucher::mintVoucherCall::abi_encode_returns(&id).into(), logs))
+            }
+            Call::redeemVoucher(c) => {
+                let token_id = c.tokenId.saturating_to::<u64>();
+                require(self.owner_of(token_id)? == from, "Not the owner")?;
+                let voucher_type = self.vouchers.get(&token_id).map(|v| v.voucherType.clone()).unwrap_or_default();
+                self.owners.remove(&token_id);
+                self.token_approvals.remove(&token_id);
+                none(vec![
+                    log(this, IVoucher::Transfer { from, to: Address::ZERO, tokenId: c.tokenId }),
+                    log(this, IVoucher::VoucherRedeemed { tokenId: c.tokenId, redeemer: from, voucherType: voucher_type }),
+                ])
+            }
+            Call::approve(c) => {
+                let token_id = c.tokenId.saturating_to::<u64>();
+                let owner = self.owner_of(token_id)?;
+                if from != owner && !self.operators.contains(&(owner, from)) {
+                    return Err(custom_error(IVoucher::ERC721InvalidApprover { approver: from }));
+                }
+                self.token_approvals.insert(token_id, c.to);
+                none(vec![log(this, IVoucher::Approval { owner, approved: c.to, tokenId: c.tokenId })])
+            }
+            Call::setApprovalForAll(c) => {
+                if c.operator == Address::ZERO {
+                    return Err(custom_error(IVoucher::ERC721InvalidOperator { operator: c.operator }));
+                }
+                if c.approved {
+                    self.operators.insert((from, c.operator));
+                } else {
+                    self.operators.remove(&(from, c.operator));
+                }
+                none(vec![log(this, IVoucher::ApprovalForAll { owner: from, operator: c.operator, approved: c.approved })])
+            }
+            Call::transferFrom(c) => none(self.transfer_voucher(from, c.from, c.to, c.tokenId.saturating_to())?),
+            Call::safeTransferFrom_0(c) => none(self.transfer_voucher(from, c.from, c.to, c.tokenId.saturating_to())?),
+            Call::safeTransferFrom_1(c) => none(self.transfer_voucher(from, c.from, c.to, c.tokenId.saturating_to())?),
+            Call::ownerOf(c) => {
+                let owner = self.owner_of(c.tokenId.saturating_to())?;
+                Ok((IVoucher::ownerOfCall::abi_encode_returns(&owner).into(), Vec::new()))
+            }
+            Call::getApproved(c) => {
+                let token_id = c.tokenId.saturating_to::<u64>();
+                self.owner_of(token_id)?;
+                let approved = self.token_approvals.get(&token_id).copied().unwrap_or_default();
+                Ok((IVoucher::getApprovedCall::abi_encode_returns(&approved).into(), Vec::new()))
+            }
+            Call::isApprovedForAll(c) => {
+                let approved = self.operators.contains(&(c.owner, c.operator));
+                Ok((IVoucher::isApprovedForAllCall::abi_encode_returns(&approved).into(), Vec::new()))
+            }
+            Call::balanceOf(c) => {
+                if c.owner == Address::ZERO {
+                    return Err(custom_error(IVoucher::ERC721InvalidOwner { owner: c.owner }));
+                }
+                let count = self.owners.values().filter(|o| **o == c.owner).count();
+                Ok((IVoucher::balanceOfCall::abi_encode_returns(&U256::from(count)).into(), Vec::new()))
+            }
+            Call::vouchers(c) => {
+                let data = self.vouchers.get(&c.0.saturating_to()).cloned().unwrap_or_default();
+                Ok((IVoucher::vouchersCall::abi_encode_returns(&data).into(), Vec::new()))
+            }
+            Call::nextTokenId(_) => {
+                Ok((IVoucher::nextTokenIdCall::abi_encode_returns(&U256::from(self.next_token_id)).into(), Vec::new()))
+            }
+            Call::name(_) => Ok((IVoucher::nameCall::abi_encode_returns(&"CabalMesh Voucher".to_string()).into(), Vec::new())),
+            Call::symbol(_) => Ok((IVoucher::symbolCall::abi_encode_returns(&"CMV".to_string()).into(), Vec::new())),
+            _ => Err(Failure::Revert(Bytes::new())),
+        }
+    }
+
+    fn owner_of(&self, token_id: u64) -> Result<Address, Failure> {
+        self.owners
+            .get(&token_id)
+            .copied()
+            .ok_or_else(|| custom_error(IVoucher::ERC721NonexistentToken { tokenId: U256::from(token_id) }))
+    }
+
+    /// ERC-721 `transferFrom` as called by `operator`, with OpenZeppelin's checks.
+    fn transfer_voucher(&mut self, operator: Address, from: Address, to: Address, token_id: u64) -> Result<Vec<Log>, Failure> {
+        let id = U256::from(token_id);
+        if to == Address::ZERO {
+            return Err(custom_error(IVoucher::ERC721InvalidReceiver { receiver: to }));
+        }
+        let owner = self.owner_of(token_id)?;
+        let authorised = operator == owner
+            || self.token_approvals.get(&token_id) == Some(&operator)
+            || self.operators.contains(&(owner, operator));
+        if !authorised {
+            return Err(custom_error(IVoucher::ERC721InsufficientApproval { operator, tokenId: id }));
+        }
+        if owner != from {
+            return Err(custom_error(IVoucher::ERC721IncorrectOwner { sender: from, tokenId: id, owner }));
+        }
+        self.token_approvals.remove(&token_id);
+        self.owners.insert(token_id, to);
+        Ok(vec![log(MOCK_VOUCHER_ADDRESS, IVoucher::Transfer { from, to, tokenId: id })])
+    }
+}
+
+/// A deterministic, in-memory chain running the Escrow, Marketplace and
+/// CabalMeshVoucher contract semantics at the `MOCK_*_ADDRESS` addresses.
+/// Transactions are really signed and their hashes are real, so offline
+/// signing, relaying and relay verification behave as they do on Fuji.
+/// Each transaction mines its own block; gas is priced but not charged.
+pub struct MockChain {
+    state: Mutex<ChainState>,
+    reachable: AtomicBool,
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockChain {
+    /// An empty chain where every account starts with nothing.
+    pub fn new() -> Self {
+        MockChain { state: Mutex::new(ChainState::new(U256::ZERO)), reachable: AtomicBool::new(true) }
+    }
+
+    /// A chain where every account starts with 100 AVAX, for demo mode.
+    pub fn demo() -> Self {
+        let starting_balance = parse_ether(DEMO_STARTING_BALANCE_AVAX).expect("valid demo balance");
+        MockChain { state: Mutex::new(ChainState::new(starting_balance)), reachable: AtomicBool::new(true) }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ChainState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn ensure_reachable(&self) -> Result<(), RpcPoolError> {
+        if self.reachable.load(Ordering::Relaxed) {
+            Ok(())
+        } else {
+            Err(RpcPoolError::Unreachable(format!("{}: simulated outage", MOCK_URL)))
+        }
+    }
+
+    /// Validates, executes and mines one signed transaction. A revert is
+    /// mined as a failed transaction (nonce used, state untouched); a
+    /// node-level problem (bad nonce, insufficient funds) is rejected unmined.
+    fn apply_raw(state: &mut ChainState, raw: Bytes) -> Result<ChainReceipt, RpcPoolError> {
+        let rejected = |message: String| RpcPoolError::Rejected { message, revert_data: None };
+
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_ref()).map_err(|e| rejected(format!("invalid transaction: {}", e)))?;
+        let from = envelope.recover_signer().map_err(|e| rejected(format!("invalid signature: {}", e)))?;
+        if envelope.chain_id().is_some_and(|id| id != MOCK_CHAIN_ID) {
+            return Err(rejected("invalid chain id for signer".to_string()));
+        }
+        let hash = keccak256(&raw);
+        if state.txs.contains_key(&hash) {
+            return Err(rejected("already known".to_string()));
+        }
+        let expected = state.nonce(from);
+        if envelope.nonce() < expected {
+            return Err(rejected(format!("nonce too low: next nonce {}, tx nonce {}", expected, envelope.nonce())));
+        }
+        if envelope.nonce() > expected {
+            return Err(rejected(format!("nonce too high: next nonce {}, tx nonce {}", expected, envelope.nonce())));
+        }
+        let TxKind::Call(to) = envelope.kind() else {
+            return Err(rejected("contract creation is not supported by the mock chain".to_string()));
+        };
+
+        state.timestamp += BLOCK_TIME_SECS;
+        let mut next = state.clone();
+        let (status, logs) = match next.execute(from, to, envelope.input(), envelope.value()) {
+            Ok((_, logs)) => {
+                *state = next;
+                (true, logs)
+            }
+            Err(Failure::Revert(_)) => (false, Vec::new()),
+            Err(Failure::Rejected(message)) => {
+                state.timestamp -= BLOCK_TIME_SECS;
+                return Err(rejected(message));
+            }
+        };
+        state.nonces.insert(from, expected + 1);
+        state.txs.insert(hash, MinedTx { raw, status });
+        Ok(ChainReceipt { transaction_hash: hash, status, logs })
+    }
+}
+
+/// Test controls.
+#[cfg(test)]
+impl MockChain {
+    pub fn fund(&self, address: Address, amount: U256) {
+        let mut state = self.state();
+        let balance = state.balance(address);
+        state.balances.insert(address, balance + amount);
+    }
+
+    /// Moves the clock forward, e.g. past an escrow's expiry.
+    pub fn advance_time(&self, secs: u64) {
+        self.state().timestamp += secs;
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.state().timestamp
+    }
+
+    /// Simulates losing (or regaining) the connection: while unreachable
+    /// every call fails as `RpcPoolError::Unreachable`.
+    pub fn set_reachable(&self, reachable: bool) {
+        self.reachable.store(reachable, Ordering::Relaxed);
+    }
+}
+
+/// Where `test_bridge` keeps the stores of the identity called `name`.
+#[cfg(test)]
+pub fn test_data_dir(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("cabalmesh_{}_{}", name, std::process::id()))
+}
+
+/// A bridge on `chain` with a fresh identity called `name`, in an emptied
+/// `test_data_dir(name)`.
+#[cfg(test)]
+pub fn test_bridge(
+    chain: std::sync::Arc<dyn ChainBackend>,
+    name: &str,
+    escrow_address: Option<Address>,
+    marketplace_address: Option<Address>,
+    voucher_address: Option<Address>,
+) -> crate::blockchain_bridge::BlockchainBridge {
+    let dir = test_data_dir(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    let bridge = crate::blockchain_bridge::BlockchainBridge::with_data_dir(
+        chain,
+        dir,
+        escrow_address,
+        marketplace_address,
+        voucher_address,
+    );
+    bridge.generate_new_identity(name.to_string(), "🧪".to_string()).unwrap();
+    bridge
+}
+
+/// A `test_bridge` on `chain`'s simulated contracts whose identity holds
+/// `avax`.
+#[cfg(test)]
+pub fn funded_bridge(chain: &std::sync::Arc<MockChain>, name: &str, avax: &str) -> crate::blockchain_bridge::BlockchainBridge {
+    let bridge = test_bridge(
+        chain.clone(),
+        name,
+        Some(MOCK_ESCROW_ADDRESS),
+        Some(MOCK_MARKETPLACE_ADDRESS),
+        Some(MOCK_VOUCHER_ADDRESS),
+    );
+    chain.fund(bridge.get_primary_address().parse().unwrap(), parse_ether(avax).unwrap());
+    bridge
+}
+
+#[async_trait]
+impl ChainBackend for MockChain {
+    async fn balance(&self, address: Address) -> Result<U256, RpcPoolError> {
+        self.ensure_reachable()?;
+        Ok(self.state().balance(address))
+    }
+
+    async fn account_proof(&self, address: Address) -> Result<AccountProof, RpcPoolError> {
+        self.ensure_reachable()?;
+        Ok(self.state().account_proof(address))
+    }
+
+    /// Only blocks this chain handed out, and only for accounts it proved
+    /// there; any other account is refused like a pruned node would.
+    async fn account_proof_at(&self, address: Address, block_hash: B256) -> Result<Option<AccountProof>, RpcPoolError> {
+        self.ensure_reachable()?;
+        let state = self.state();
+        if !state.blocks.contains_key(&block_hash) {
+            return Ok(None);
+        }
+        match state.account_proofs.get(&(block_hash, address)) {
+            Some(proof) => Ok(Some(proof.clone())),
+            None => Err(RpcPoolError::Rejected { message: "missing trie node".to_string(), revert_data: None }),
+        }
+    }
+
+    async fn block_timestamp(&self, block: BlockId) -> Result<Option<u64>, RpcPoolError> {
+        self.ensure_reachable()?;
+        let state = self.state();
+        Ok(match block {
+            BlockId::Hash(hash) => state.blocks.get(&hash.block_hash).copied(),
+            BlockId::Number(BlockNumberOrTag::Latest) => Some(state.timestamp),
+            BlockId::Number(_) => None,
+        })
+    }
+
+    async fn pending_nonce(&self, address: Address) -> Result<u64, RpcPoolError> {
+        self.ensure_reachable()?;
+        Ok(self.state().nonce(address))
+    }
+
+    async fn gas_price(&self) -> Result<u128, RpcPoolError> {
+        self.ensure_reachable()?;
+        Ok(GAS_PRICE_WEI)
+    }
+
+    async fn call(&self, from: Option<Address>, to: Address, input: Bytes, value: U256) -> Result<Bytes, RpcPoolError> {
+        self.ensure_reachable()?;
+        let mut scratch = self.state().clone();
+        match scratch.execute(from.unwrap_or_default(), to, &input, value) {
+            Ok((output, _)) => Ok(output),
+            Err(Failure::Revert(data)) => {
+                Err(RpcPoolError::Rejected { message: "execution reverted".to_string(), revert_data: Some(data) })
+            }
+            Err(Failure::Rejected(message)) => Err(RpcPoolError::Rejected { message, revert_data: None }),
+        }
+    }
+
+    async fn send(
+        &self,
+        signer: PrivateKeySigner,
+        to: Address,
+        input: Bytes,
+        value: U256,
+        _limit: Duration,
+    ) -> Result<ChainReceipt, RpcPoolError> {
+        self.ensure_reachable()?;
+        // Nonce lookup, signing and mining happen under one lock, so
+        // concurrent sends from one account never collide.
+        let mut state = self.state();
+        let mut tx = TxEip1559 {
+            chain_id: MOCK_CHAIN_ID,
+            nonce: state.nonce(signer.address()),
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: GAS_PRICE_WEI,
+            max_priority_fee_per_gas: 0,
+            to: TxKind::Call(to),
+            value,
+            input,
+            ..Default::default()
+        };
+        let signature = signer
+            .sign_transaction_sync(&mut tx)
+            .map_err(|e| RpcPoolError::Rejected { message: e.to_string(), revert_data: None })?;
+        let raw = TxEnvelope::from(tx.into_signed(signature)).encoded_2718();
+        Self::apply_raw(&mut state, raw.into())
+    }
+
+    async fn send_raw(&self, raw: Bytes) -> Result<ChainReceipt, RpcPoolError> {
+        self.ensure_reachable()?;
+        Self::apply_raw(&mut self.state(), raw)
+    }
+
+    async fn raw_transaction(&self, hash: B256) -> Result<Option<Bytes>, RpcPoolError> {
+        self.ensure_reachable()?;
+        Ok(self.state().txs.get(&hash).map(|tx| tx.raw.clone()))
+    }
+
+    async fn receipt_status(&self, hash: B256) -> Result<Option<bool>, RpcPoolError> {
+        self.ensure_reachable()?;
+        Ok(self.state().txs.get(&hash).map(|tx| tx.status))
+    }
+
+    async fn probe(&self, _limit: Duration) -> Option<String> {
+        self.ensure_reachable().ok().map(|_| MOCK_URL.to_string())
+    }
+
+    fn timeouts(&self) -> RpcTimeouts {
+        RpcTimeouts::default()
+    }
+
+    fn status(&self) -> Vec<EndpointStatus> {
+        let reachable = self.reachable.load(Ordering::Relaxed);
+        vec![EndpointStatus {
+            url: MOCK_URL.to_string(),
+            healthy: reachable,
+            latency_ms: reachable.then_some(0),
+            successes: 0,
+            failures: 0,
+            last_error: (!reachable).then(|| "simulated outage".to_string()),
+        }]
+    }
+
+    fn primary_url(&self) -> String {
+        MOCK_URL.to_string()
+    }
+}
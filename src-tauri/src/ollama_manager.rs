@@ -1,18 +1,43 @@
+use serde::Deserialize;
 use std::process::{Command, Child, Stdio};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use crate::error::{AppError, AppResult};
+use crate::settings::{ModelRole, Settings, SettingsStore};
+
+const TAGS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `/api/tags` reply: the models a server has pulled.
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    models: Vec<TagEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagEntry {
+    name: String,
+}
+
+/// Whether `model` is among `available`. Ollama lists an untagged name
+/// as `name:latest`.
+fn has_model(available: &[String], model: &str) -> bool {
+    available
+        .iter()
+        .any(|name| name == model || (!model.contains(':') && *name == format!("{}:latest", model)))
+}
+
 pub struct OllamaManager {
     process: Arc<Mutex<Option<Child>>>,
-    model_name: String,
+    settings: Arc<SettingsStore>,
 }
 
 impl OllamaManager {
-    pub fn new(model_name: Option<String>) -> Self {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
         OllamaManager {
             process: Arc::new(Mutex::new(None)),
-            model_name: model_name.unwrap_or_else(|| "llama2".to_string()),
+            settings,
         }
     }
 
@@ -32,7 +57,7 @@ impl OllamaManager {
             return Err("Ollama is not installed. Please install it from https://ollama.ai".to_string());
         }
 
-        println!("🤖 Starting Ollama service...");
+        tracing::info!("starting Ollama service");
 
         // Start ollama serve as a background process
         match Command::new("ollama")
@@ -42,8 +67,8 @@ impl OllamaManager {
             .spawn()
         {
             Ok(child) => {
+                tracing::info!(pid = child.id(), "Ollama service started");
                 *self.process.lock().unwrap() = Some(child);
-                println!("✅ Ollama service started");
                 
                 // Give it time to start
                 thread::sleep(Duration::from_secs(2));
@@ -55,8 +80,8 @@ impl OllamaManager {
     }
 
     /// Pull the AI model if not already available
-    pub fn pull_model(&self) -> Result<(), String> {
-        println!("📥 Checking for model: {}", self.model_name);
+    pub fn pull_model(model_name: &str) -> Result<(), String> {
+        tracing::info!(model = model_name, "checking for model");
 
         // Check if model exists
         let list_output = Command::new("ollama")
@@ -66,22 +91,22 @@ impl OllamaManager {
 
         let list_str = String::from_utf8_lossy(&list_output.stdout);
         
-        if list_str.contains(&self.model_name) {
-            println!("✅ Model {} already available", self.model_name);
+        if list_str.contains(model_name) {
+            tracing::info!(model = model_name, "model already available");
             return Ok(());
         }
 
         // Pull the model
-        println!("📥 Pulling model {} (this may take a few minutes)...", self.model_name);
+        tracing::info!(model = model_name, "pulling model (this may take a few minutes)");
         
         let output = Command::new("ollama")
             .arg("pull")
-            .arg(&self.model_name)
+            .arg(model_name)
             .output()
             .map_err(|e| format!("Failed to pull model: {}", e))?;
 
         if output.status.success() {
-            println!("✅ Model {} downloaded successfully", self.model_name);
+            tracing::info!(model = model_name, "model downloaded");
             Ok(())
         } else {
             Err(format!(
@@ -91,28 +116,80 @@ impl OllamaManager {
         }
     }
 
-    /// Initialize Ollama (start service + pull model)
+    /// Initialize Ollama (start service + pull the models agents use on it)
     pub async fn initialize(&self) -> Result<(), String> {
         // Start the service
         self.start_service()?;
 
-        // Pull the model in the background
-        let model_name = self.model_name.clone();
+        // Pull the models in the background; those on other servers are
+        // theirs to manage.
+        let settings = self.settings.get();
+        let mut models: Vec<String> = ModelRole::ALL
+            .into_iter()
+            .map(|role| settings.model_endpoint(role))
+            .filter(|endpoint| endpoint.url == settings.ollama_url)
+            .map(|endpoint| endpoint.model)
+            .collect();
+        models.sort();
+        models.dedup();
         tokio::task::spawn_blocking(move || {
-            let manager = OllamaManager::new(Some(model_name));
-            if let Err(e) = manager.pull_model() {
-                eprintln!("⚠️  Warning: {}", e);
+            for model_name in models {
+                if let Err(e) = Self::pull_model(&model_name) {
+                    tracing::warn!(error = %e, model = %model_name, "model pull failed");
+                }
             }
         });
 
         Ok(())
     }
 
+    /// The models the Ollama server at `url` has pulled, from `/api/tags`.
+    pub async fn list_models(url: &str) -> AppResult<Vec<String>> {
+        let response = reqwest::Client::new()
+            .get(format!("{}/api/tags", url.trim_end_matches('/')))
+            .timeout(TAGS_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        let tags: TagsResponse = response.json().await?;
+        Ok(tags.models.into_iter().map(|m| m.name).collect())
+    }
+
+    /// Checks every agent whose model or endpoint differs from `previous`
+    /// against the models its endpoint reports. An endpoint that doesn't
+    /// answer can't be checked and is let through; its agent reports
+    /// `model_unavailable` when used.
+    pub async fn check_models(settings: &Settings, previous: &Settings) -> AppResult<()> {
+        for role in ModelRole::ALL {
+            let endpoint = settings.model_endpoint(role);
+            if endpoint == previous.model_endpoint(role) {
+                continue;
+            }
+            match Self::list_models(&endpoint.url).await {
+                Ok(available) if !has_model(&available, &endpoint.model) => {
+                    return Err(AppError::InvalidInput(format!(
+                        "{}: {} has no model {:?} (pull it with `ollama pull {}`); available: {}",
+                        role.model_field(),
+                        endpoint.url,
+                        endpoint.model,
+                        endpoint.model,
+                        if available.is_empty() { "none".to_string() } else { available.join(", ") }
+                    )));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, url = %endpoint.url, model = %endpoint.model, "could not list models, accepting unchecked");
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Check if Ollama service is responding
     pub async fn health_check(&self) -> bool {
         let client = reqwest::Client::new();
         match client
-            .get("http://localhost:11434/api/tags")
+            .get(format!("{}/api/tags", self.settings.get().ollama_url))
             .timeout(Duration::from_secs(2))
             .send()
             .await
@@ -125,7 +202,7 @@ impl OllamaManager {
     /// Stop the Ollama service
     pub fn stop_service(&self) {
         if let Some(mut child) = self.process.lock().unwrap().take() {
-            println!("🛑 Stopping Ollama service...");
+            tracing::info!("stopping Ollama service");
             let _ = child.kill();
             let _ = child.wait();
         }
@@ -137,3 +214,60 @@ impl Drop for OllamaManager {
         self.stop_service();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers every request with `/api/tags` JSON listing `models`.
+    async fn tags_server(models: &[&str]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let names: Vec<_> = models.iter().map(|name| serde_json::json!({ "name": name })).collect();
+        let body = serde_json::json!({ "models": names }).to_string();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = socket.read(&mut request).await;
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(reply.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    /// Each agent's model is checked against its own endpoint, only when
+    /// it changes, and an unreachable endpoint doesn't block the change.
+    #[tokio::test]
+    async fn checks_agent_models_against_their_endpoints() {
+        let local = tags_server(&["llama2:latest", "llama3.1:70b"]).await;
+        let small = tags_server(&["llama3.2:1b"]).await;
+        assert_eq!(OllamaManager::list_models(&small).await.unwrap(), vec!["llama3.2:1b"]);
+
+        let previous = Settings { ollama_url: local.clone(), ..Settings::default() };
+        let split = Settings {
+            negotiation_model: Some("llama3.1:70b".to_string()),
+            matching_model: Some("llama3.2:1b".to_string()),
+            matching_url: Some(small.clone()),
+            ..previous.clone()
+        };
+        OllamaManager::check_models(&split, &previous).await.unwrap();
+        OllamaManager::check_models(&previous, &split).await.unwrap();
+
+        let missing = Settings { matching_url: None, ..split.clone() };
+        let err = OllamaManager::check_models(&missing, &previous).await.unwrap_err();
+        assert!(matches!(&err, AppError::InvalidInput(m) if m.starts_with("matching_model") && m.contains("llama3.1:70b")));
+
+        // Unchanged roles aren't re-checked, and a dead endpoint isn't fatal.
+        let stale = Settings { ollama_model: "gone".to_string(), ..split.clone() };
+        OllamaManager::check_models(&stale, &stale).await.unwrap();
+        let offline = Settings { matching_url: Some("http://127.0.0.1:1".to_string()), ..split.clone() };
+        OllamaManager::check_models(&offline, &split).await.unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+
+/// Which node instance this process is. Every persisted file lives under
+/// `data_dir`, and the bridge namespaces its keychain entry by it, so two
+/// profiles never share identities, queues, content or settings. The mesh
+/// keypair is ephemeral and generated per process, so it's never shared.
+#[derive(Debug, Clone, Serialize)]
+pub struct Profile {
+    /// `None` for the default profile.
+    pub name: Option<String>,
+    pub data_dir: PathBuf,
+}
+
+/// `~/.local/share/cabalmesh` and friends — the default profile's data dir,
+/// kept where it always was so existing installs carry over.
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("cabalmesh")
+}
+
+impl Profile {
+    /// Resolves the profile from `--profile <name>` / `--data-dir <path>`
+    /// (either also as `--flag=value`), falling back to `CABALMESH_PROFILE` /
+    /// `CABALMESH_DATA_DIR`. Unrecognised arguments are left for Tauri.
+    pub fn from_env_and_args() -> AppResult<Self> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let name = flag_value(&args, "--profile")?.or_else(|| env("CABALMESH_PROFILE"));
+        let data_dir = flag_value(&args, "--data-dir")?.or_else(|| env("CABALMESH_DATA_DIR"));
+        Self::resolve(name, data_dir.map(PathBuf::from))
+    }
+
+    /// A named profile lives in `profiles/<name>` under the default data dir
+    /// unless an explicit data dir is given.
+    pub fn resolve(name: Option<String>, data_dir: Option<PathBuf>) -> AppResult<Self> {
+        if let Some(name) = &name {
+            let valid = !name.is_empty()
+                && name.len() <= 64
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                return Err(AppError::InvalidInput(format!(
+                    "profile name {:?} must be 1-64 letters, digits, '-' or '_'",
+                    name
+                )));
+            }
+        }
+        let data_dir = match (&name, data_dir) {
+            (_, Some(dir)) => dir,
+            (Some(name), None) => default_data_dir().join("profiles").join(name),
+            (None, None) => default_data_dir(),
+        };
+        Ok(Profile { name, data_dir })
+    }
+
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+}
+
+fn flag_value(args: &[String], flag: &str) -> AppResult<Option<String>> {
+    let prefix = format!("{}=", flag);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            return iter
+                .next()
+                .filter(|v| !v.starts_with("--"))
+                .map(|v| Some(v.clone()))
+                .ok_or_else(|| AppError::InvalidInput(format!("{} needs a value", flag)));
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Ok(Some(value.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_profile_flags_and_data_dirs() {
+        let args: Vec<String> = ["--config", "x.json", "--profile=node2", "--data-dir", "/tmp/n2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(flag_value(&args, "--profile").unwrap().as_deref(), Some("node2"));
+        assert_eq!(flag_value(&args, "--data-dir").unwrap().as_deref(), Some("/tmp/n2"));
+        assert!(flag_value(&["--profile".to_string()], "--profile").is_err());
+
+        let named = Profile::resolve(Some("node2".to_string()), None).unwrap();
+        assert_eq!(named.data_dir, default_data_dir().join("profiles").join("node2"));
+        assert_ne!(named.data_dir, Profile::resolve(None, None).unwrap().data_dir);
+        assert_eq!(Profile::resolve(None, Some("/tmp/n3".into())).unwrap().data_dir, PathBuf::from("/tmp/n3"));
+        assert!(Profile::resolve(Some("../escape".to_string()), None).is_err());
+    }
+}
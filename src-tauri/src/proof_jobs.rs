@@ -0,0 +1,376 @@
+use alloy::primitives::{keccak256, B256};
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::{broadcast, Semaphore};
+use tokio::task::AbortHandle;
+
+use crate::blockchain_bridge::BlockchainBridge;
+use crate::chain_backend::AccountProof;
+use crate::error::{AppError, AppResult};
+use crate::proving_backend::ProvingBackendKind;
+use crate::sealed_intent::{encode_public_key, parse_public_key, IntentOpening};
+use crate::zk_handler::{BidProof, ProofBinding, ProofRequest, ZKHandler, PROOF_TTL_SECS};
+
+pub type ProofJobId = u64;
+
+/// Finished jobs kept for `get`/`list`; the oldest are forgotten first.
+const KEEP_FINISHED: usize = 100;
+
+/// A cached bid proof is only handed out again while receivers have at
+/// least this long left to check it.
+const MIN_REMAINING_SECS: u64 = PROOF_TTL_SECS / 2;
+
+const EVENT_CAPACITY: usize = 256;
+
+/// A bid proof to make. The intent and price ceiling stay in the job and
+/// never appear in its events.
+#[derive(Debug, Clone)]
+pub struct ProofJobRequest {
+    pub bid_amount: u64,
+    pub price_ceiling: u64,
+    pub intent: String,
+    /// Public key the intent is sealed to.
+    pub recipient_key: String,
+}
+
+/// Where a running job is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofStage {
+    /// Fetching (or reusing) the account proof the balance is anchored to.
+    Anchoring,
+    Proving,
+    Signing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ProofJobState {
+    /// Anchored, not in the cache, and waiting for a free proof worker.
+    Queued,
+    Running { stage: ProofStage },
+    /// `cached` if an identical earlier proof was reused.
+    Done { bid_proof: BidProof, cached: bool },
+    Failed { error: AppError },
+    Cancelled,
+}
+
+impl ProofJobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, ProofJobState::Done { .. } | ProofJobState::Failed { .. } | ProofJobState::Cancelled)
+    }
+}
+
+/// Payload of the `proof-job` event, sent on every state change.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofJob {
+    pub id: ProofJobId,
+    pub bid_amount: u64,
+    /// Unix seconds.
+    pub submitted_at: i64,
+    #[serde(flatten)]
+    pub state: ProofJobState,
+}
+
+struct JobEntry {
+    job: ProofJob,
+    task: Option<AbortHandle>,
+}
+
+/// Makes bid proofs in the background: jobs are submitted, then polled or
+/// followed through `subscribe`, and can be cancelled. At most `workers`
+/// proofs are generated at once; the rest queue. Finished proofs are cached
+/// by circuit hash and witness hash, so asking again for the same bid on
+/// the same intent, recipient and anchored balance returns the earlier
+/// proof instead of proving again.
+pub struct ProofJobs {
+    zk: Arc<ZKHandler>,
+    bridge: Arc<BlockchainBridge>,
+    workers: Arc<Semaphore>,
+    next_id: AtomicU64,
+    jobs: Mutex<BTreeMap<ProofJobId, JobEntry>>,
+    cache: Mutex<HashMap<B256, BidProof>>,
+    events: broadcast::Sender<ProofJob>,
+}
+
+impl ProofJobs {
+    pub fn new(zk: Arc<ZKHandler>, bridge: Arc<BlockchainBridge>, workers: usize) -> Self {
+        ProofJobs {
+            zk,
+            bridge,
+            workers: Arc::new(Semaphore::new(workers.max(1))),
+            next_id: AtomicU64::new(1),
+            jobs: Mutex::new(BTreeMap::new()),
+            cache: Mutex::new(HashMap::new()),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ProofJob> {
+        self.events.subscribe()
+    }
+
+    /// Starts a job and returns it as first reported. Fails up front only
+    /// for a recipient key that doesn't parse; everything else shows up as
+    /// the job's `Failed` state.
+    pub fn submit(self: &Arc<Self>, mut request: ProofJobRequest) -> AppResult<ProofJob> {
+        request.recipient_key = encode_public_key(&parse_public_key(&request.recipient_key)?);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let job = ProofJob {
+            id,
+            bid_amount: request.bid_amount,
+            submitted_at: Utc::now().timestamp(),
+            state: ProofJobState::Running { stage: ProofStage::Anchoring },
+        };
+        self.lock_jobs().insert(id, JobEntry { job: job.clone(), task: None });
+        let _ = self.events.send(job.clone());
+
+        let jobs = self.clone();
+        let task = tokio::spawn(async move {
+            let state = match jobs.run(id, &request).await {
+                Ok((bid_proof, cached)) => ProofJobState::Done { bid_proof, cached },
+                Err(error) => ProofJobState::Failed { error },
+            };
+            jobs.set_state(id, state);
+        });
+        if let Some(entry) = self.lock_jobs().get_mut(&id).filter(|e| !e.job.state.is_finished()) {
+            entry.task = Some(task.abort_handle());
+        }
+        Ok(job)
+    }
+
+    /// Submits a job and waits for its proof.
+    pub async fn prove(self: &Arc<Self>, request: ProofJobRequest) -> AppResult<BidProof> {
+        let events = self.subscribe();
+        let id = self.submit(request)?.id;
+        self.wait(id, events).await
+    }
+
+    async fn wait(&self, id: ProofJobId, mut events: broadcast::Receiver<ProofJob>) -> AppResult<BidProof> {
+        let mut state = self.get(id)?.state;
+        loop {
+            match state {
+                ProofJobState::Done { bid_proof, .. } => return Ok(bid_proof),
+                ProofJobState::Failed { error } => return Err(error),
+                ProofJobState::Cancelled => return Err(AppError::Proof("proof job was cancelled".to_string())),
+                _ => {}
+            }
+            state = match events.recv().await {
+                Ok(job) if job.id == id => job.state,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => self.get(id)?.state,
+                Err(broadcast::error::RecvError::Closed) => return Err(AppError::Proof("proof jobs shut down".to_string())),
+            };
+        }
+    }
+
+    pub fn get(&self, id: ProofJobId) -> AppResult<ProofJob> {
+        self.lock_jobs()
+            .get(&id)
+            .map(|entry| entry.job.clone())
+            .ok_or_else(|| AppError::NotFound(format!("proof job {}", id)))
+    }
+
+    /// Every job still known, oldest first.
+    pub fn list(&self) -> Vec<ProofJob> {
+        self.lock_jobs().values().map(|entry| entry.job.clone()).collect()
+    }
+
+    /// Stops a job that hasn't finished. Returns whether it was stopped;
+    /// `false` if it had already finished. A `nargo` or `bb` process it
+    /// started runs to completion, but its output is discarded.
+    pub fn cancel(&self, id: ProofJobId) -> AppResult<bool> {
+        let mut jobs = self.lock_jobs();
+        let entry = jobs.get_mut(&id).ok_or_else(|| AppError::NotFound(format!("proof job {}", id)))?;
+        if entry.job.state.is_finished() {
+            return Ok(false);
+        }
+        if let Some(task) = entry.task.take() {
+            task.abort();
+        }
+        entry.job.state = ProofJobState::Cancelled;
+        let _ = self.events.send(entry.job.clone());
+        tracing::info!(job = id, "proof job cancelled");
+        Ok(true)
+    }
+
+    async fn run(&self, id: ProofJobId, request: &ProofJobRequest) -> AppResult<(BidProof, bool)> {
+        let anchor = self.bridge.anchored_balance().await?;
+        let key = self.cache_key(self.zk.proving_backend(), &anchor, request)?;
+        if let Some(bid_proof) = self.cached(&key) {
+            tracing::info!(job = id, "reusing a cached bid proof");
+            return Ok((bid_proof, true));
+        }
+
+        self.set_state(id, ProofJobState::Queued);
+        let _worker = self.workers.acquire().await.map_err(|e| AppError::Proof(e.to_string()))?;
+        self.set_state(id, ProofJobState::Running { stage: ProofStage::Proving });
+        let opening = IntentOpening::new(&request.intent, request.bid_amount);
+        let sealed = opening.seal(&parse_public_key(&request.recipient_key)?)?;
+        let binding = ProofBinding::new(&opening, anchor.address);
+        let proof_request = ProofRequest::anchored(&anchor, request.bid_amount, request.price_ceiling, binding);
+        let proof = self.zk.generate_proof(proof_request, sealed).await?;
+
+        self.set_state(id, ProofJobState::Running { stage: ProofStage::Signing });
+        let bid_proof = self.bridge.sign_bid_proof(request.bid_amount, proof)?;
+        // Keyed by the backend that actually proved it, in case the setting
+        // changed while this job was queued.
+        let key = self.cache_key(bid_proof.proof.backend, &anchor, request)?;
+        self.remember(key, bid_proof.clone());
+        Ok((bid_proof, false))
+    }
+
+    /// The circuit hash of `backend`, then a hash of every witness and
+    /// binding input except the fresh salt and expiry: the anchored account
+    /// proof, the bid, the price ceiling, the intent and the recipient.
+    fn cache_key(&self, backend: ProvingBackendKind, anchor: &AccountProof, request: &ProofJobRequest) -> AppResult<B256> {
+        let witness = serde_json::to_vec(&(
+            anchor.block_hash,
+            anchor.address,
+            anchor.balance,
+            request.bid_amount,
+            request.price_ceiling,
+            &request.intent,
+            &request.recipient_key,
+        ))?;
+        let circuit_hash = self.zk.circuit_hash(backend)?;
+        Ok(keccak256([circuit_hash.as_slice(), keccak256(witness).as_slice()].concat()))
+    }
+
+    fn cached(&self, key: &B256) -> Option<BidProof> {
+        let cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.get(key).filter(|bid_proof| still_fresh(bid_proof)).cloned()
+    }
+
+    fn remember(&self, key: B256, bid_proof: BidProof) {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.retain(|_, cached| still_fresh(cached));
+        cache.insert(key, bid_proof);
+    }
+
+    /// Records and announces a job's new state, unless it has already
+    /// finished (a cancelled job's task may still report in).
+    fn set_state(&self, id: ProofJobId, state: ProofJobState) {
+        let mut jobs = self.lock_jobs();
+        let Some(entry) = jobs.get_mut(&id).filter(|e| !e.job.state.is_finished()) else {
+            return;
+        };
+        let finished = state.is_finished();
+        entry.job.state = state;
+        if finished {
+            entry.task = None;
+        }
+        let _ = self.events.send(entry.job.clone());
+
+        if finished {
+            let done: Vec<ProofJobId> = jobs.iter().filter(|(_, e)| e.job.state.is_finished()).map(|(id, _)| *id).collect();
+            for id in done.iter().take(done.len().saturating_sub(KEEP_FINISHED)) {
+                jobs.remove(id);
+            }
+        }
+    }
+
+    fn lock_jobs(&self) -> std::sync::MutexGuard<'_, BTreeMap<ProofJobId, JobEntry>> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Whether a bid proof has at least `MIN_REMAINING_SECS` before it expires.
+fn still_fresh(bid_proof: &BidProof) -> bool {
+    let expiry = bid_proof.proof.public_inputs.get(2).and_then(|expiry| expiry.parse::<u64>().ok());
+    expiry.is_some_and(|expiry| expiry >= Utc::now().timestamp() as u64 + MIN_REMAINING_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_registry::CircuitRegistry;
+    use crate::mock_chain::{funded_bridge, test_data_dir, MockChain};
+    use crate::settings::SettingsStore;
+    use std::path::PathBuf;
+
+    /// A queue with `workers` workers, for an identity holding 5 AVAX.
+    fn proof_jobs(name: &str, workers: usize) -> (Arc<ProofJobs>, Arc<ZKHandler>, PathBuf) {
+        let chain = Arc::new(MockChain::new());
+        let dir = test_data_dir(name);
+        let bridge = Arc::new(funded_bridge(&chain, name, "5"));
+        let circuits = CircuitRegistry::load(dir.join("circuits"));
+        let zk = Arc::new(ZKHandler::new(Arc::new(SettingsStore::load(&dir)), bridge.clone(), circuits));
+        (Arc::new(ProofJobs::new(zk.clone(), bridge, workers)), zk, dir)
+    }
+
+    fn request(jobs: &ProofJobs, bid_amount: u64) -> ProofJobRequest {
+        ProofJobRequest {
+            bid_amount,
+            price_ceiling: 60,
+            intent: r#"{"want":"lamp"}"#.to_string(),
+            recipient_key: encode_public_key(&jobs.bridge.intent_public_key().unwrap()),
+        }
+    }
+
+    /// A job reports each stage as it goes, and asking again for the same
+    /// proof is answered from the cache without proving again.
+    #[tokio::test]
+    async fn reports_progress_and_serves_repeats_from_the_cache() {
+        let (jobs, zk, dir) = proof_jobs("jobs_progress", 2);
+        let mut events = jobs.subscribe();
+
+        let bid = jobs.prove(request(&jobs, 50)).await.unwrap();
+        assert!(zk.verify_bid(&bid, r#"{"want":"lamp"}"#).await.unwrap());
+        let mut states = Vec::new();
+        while let Ok(job) = events.try_recv() {
+            assert_eq!((job.id, job.bid_amount), (1, 50));
+            states.push(serde_json::to_value(&job.state).unwrap()["state"].as_str().unwrap().to_string());
+        }
+        assert_eq!(states, ["running", "queued", "running", "running", "done"]);
+
+        let again = jobs.prove(request(&jobs, 50)).await.unwrap();
+        assert_eq!(again, bid);
+        assert!(matches!(jobs.get(2).unwrap().state, ProofJobState::Done { cached: true, .. }));
+
+        let other = jobs.prove(request(&jobs, 40)).await.unwrap();
+        assert_ne!(other.proof, bid.proof);
+        assert!(matches!(jobs.get(3).unwrap().state, ProofJobState::Done { cached: false, .. }));
+
+        let failed = jobs.prove(request(&jobs, 70)).await;
+        assert!(matches!(failed, Err(AppError::InvalidInput(_))));
+        assert!(matches!(jobs.get(4).unwrap().state, ProofJobState::Failed { .. }));
+        assert_eq!(jobs.list().len(), 4);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// With every worker busy jobs wait in the queue, where they can be
+    /// cancelled; the rest run once a worker frees up.
+    #[tokio::test]
+    async fn queues_behind_busy_workers_and_cancels() {
+        let (jobs, _zk, dir) = proof_jobs("jobs_cancel", 1);
+        let busy = jobs.workers.clone().acquire_owned().await.unwrap();
+        let mut events = jobs.subscribe();
+
+        let cancelled = jobs.submit(request(&jobs, 50)).unwrap().id;
+        let waiting = jobs.submit(request(&jobs, 40)).unwrap().id;
+        let mut queued = 0;
+        while queued < 2 {
+            if matches!(events.recv().await.unwrap().state, ProofJobState::Queued) {
+                queued += 1;
+            }
+        }
+
+        assert!(jobs.cancel(cancelled).unwrap());
+        assert!(matches!(jobs.get(cancelled).unwrap().state, ProofJobState::Cancelled));
+        assert!(!jobs.cancel(cancelled).unwrap());
+        assert!(matches!(jobs.cancel(99), Err(AppError::NotFound(_))));
+        assert!(matches!(jobs.get(waiting).unwrap().state, ProofJobState::Queued));
+
+        drop(busy);
+        let bid = jobs.wait(waiting, jobs.subscribe()).await.unwrap();
+        assert_eq!(bid.bid_amount, 40);
+        assert!(matches!(jobs.wait(cancelled, jobs.subscribe()).await, Err(AppError::Proof(_))));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,660 @@
+use alloy::primitives::{keccak256, B256, U256};
+use async_trait::async_trait;
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek::ristretto::CompressedRistretto;
+use curve25519_dalek::scalar::Scalar;
+use merlin::Transcript;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::str::FromStr;
+
+use crate::error::{AppError, AppResult};
+use crate::zk_handler::ProofRequest;
+
+/// Package name in `noir-circuit/Nargo.toml`; nargo names its artifacts
+/// after it, and it's the circuit's name in the registry.
+const CIRCUIT_PACKAGE: &str = "cabalmesh_bid";
+
+/// The bundled circuit, written into each proof workspace unless a custom
+/// circuit directory is configured.
+const BUNDLED_NARGO_TOML: &str = include_str!("../../noir-circuit/Nargo.toml");
+const BUNDLED_CIRCUIT: &str = include_str!("../../noir-circuit/src/main.nr");
+
+/// nargo 1.0 dropped `nargo prove`: it only executes the circuit into a
+/// witness, and proving moved to Barretenberg's `bb`. Before 0.84, `bb prove`
+/// wrote a single file with the public inputs baked in, which we don't parse.
+const MIN_NARGO_VERSION: (u64, u64, u64) = (1, 0, 0);
+const MIN_BB_VERSION: (u64, u64, u64) = (0, 84, 0);
+
+/// Field elements are 32 big-endian bytes in `bb`'s `public_inputs` file.
+const FIELD_BYTES: usize = 32;
+
+/// Which prover makes (and checks) bid proofs. Selected by the
+/// `proving_backend` setting; every proof records the backend that made it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvingBackendKind {
+    /// The Noir circuit, proved by the `nargo` and `bb` CLIs.
+    Nargo,
+    /// A Bulletproofs range proof in pure Rust; needs no external binary.
+    #[default]
+    Bulletproofs,
+    /// Deterministic fake proofs for tests. Only accepted by nodes that are
+    /// themselves set to `mock`.
+    Mock,
+}
+
+impl ProvingBackendKind {
+    /// Proofs from before the backend was recorded were all Noir proofs.
+    pub fn legacy() -> Self {
+        ProvingBackendKind::Nargo
+    }
+}
+
+impl std::fmt::Display for ProvingBackendKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ProvingBackendKind::Nargo => "nargo",
+            ProvingBackendKind::Bulletproofs => "bulletproofs",
+            ProvingBackendKind::Mock => "mock",
+        })
+    }
+}
+
+/// A backend's proof, before the handler wraps it into a `ZKProof`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofOutput {
+    pub proof: Vec<u8>,
+    pub public_inputs: Vec<String>,
+}
+
+/// What verifying a circuit's proofs needs, as built by
+/// `ProvingBackend::compile`. The circuit registry pins it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledCircuit {
+    /// The compiled program, for backends that have one (nargo's ACIR JSON).
+    pub artifact: Option<Vec<u8>>,
+    pub verification_key: Vec<u8>,
+}
+
+/// Proves that a bid fits the bidder's balance and price ceiling without
+/// revealing the ceiling. The public inputs are `ProofRequest::public_inputs`:
+/// the bid first, then what binds the proof to one intent, then the block
+/// the balance was proven at and that balance, which receivers check
+/// against the chain.
+#[async_trait]
+pub trait ProvingBackend: Send + Sync {
+    fn kind(&self) -> ProvingBackendKind;
+
+    /// The circuit's name in the registry; its id adds the source hash.
+    fn circuit_name(&self) -> &'static str;
+
+    /// Builds the current circuit's program and verification key.
+    async fn compile(&self) -> AppResult<CompiledCircuit>;
+
+    /// Proves `request`. The caller has already checked the statement holds.
+    async fn prove(&self, request: &ProofRequest) -> AppResult<ProofOutput>;
+
+    /// Checks a proof against a circuit's pinned verification key.
+    /// `Ok(false)` for a proof that doesn't verify; `Err` when it can't be
+    /// checked on this machine.
+    async fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[String]) -> AppResult<bool>;
+
+    /// Why this backend can't run here, if it can't.
+    async fn unavailable(&self) -> Option<String>;
+
+    /// Identifies the statement this backend proves: proofs with the same
+    /// circuit hash and public inputs mean the same thing. Keys the proof
+    /// cache, so anything that changes what a proof shows must change it.
+    fn circuit_hash(&self) -> AppResult<B256>;
+}
+
+/// The bid, which every backend takes as its first public input.
+fn bid_of(public_inputs: &[String]) -> Option<u64> {
+    public_inputs.first()?.parse().ok()
+}
+
+/// The proven balance, the last of the public inputs.
+fn balance_of(public_inputs: &[String]) -> Option<u64> {
+    public_inputs.get(7)?.parse().ok()
+}
+
+/// A throwaway copy of the circuit for one proof, so concurrent proofs never
+/// share a `Prover.toml` or `target/`. Deleted on drop, witnesses included.
+struct ProofWorkspace {
+    dir: PathBuf,
+}
+
+impl ProofWorkspace {
+    fn empty() -> AppResult<Self> {
+        let dir = std::env::temp_dir().join(format!("cabalmesh-proof-{:016x}", rand::random::<u64>()));
+        let workspace = ProofWorkspace { dir };
+        std::fs::create_dir_all(workspace.dir.join("target"))?;
+        Ok(workspace)
+    }
+
+    fn create(circuit_dir: Option<&Path>) -> AppResult<Self> {
+        let workspace = Self::empty()?;
+        std::fs::create_dir_all(workspace.dir.join("src"))?;
+        for (path, contents) in circuit_sources(circuit_dir)? {
+            std::fs::write(workspace.dir.join(path), contents)?;
+        }
+        Ok(workspace)
+    }
+
+    fn target(&self, file: &str) -> PathBuf {
+        self.dir.join("target").join(file)
+    }
+}
+
+impl Drop for ProofWorkspace {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            tracing::warn!(error = %e, dir = %self.dir.display(), "could not remove proof workspace");
+        }
+    }
+}
+
+/// The circuit package as (path within the package, contents), sorted by
+/// path: `circuit_dir`'s `Nargo.toml` and the files in its `src/`, or the
+/// bundled circuit.
+fn circuit_sources(circuit_dir: Option<&Path>) -> AppResult<Vec<(PathBuf, Vec<u8>)>> {
+    let Some(circuit_dir) = circuit_dir else {
+        return Ok(vec![
+            (PathBuf::from("Nargo.toml"), BUNDLED_NARGO_TOML.as_bytes().to_vec()),
+            (PathBuf::from("src/main.nr"), BUNDLED_CIRCUIT.as_bytes().to_vec()),
+        ]);
+    };
+    let mut sources = vec![(PathBuf::from("Nargo.toml"), std::fs::read(circuit_dir.join("Nargo.toml"))?)];
+    for entry in std::fs::read_dir(circuit_dir.join("src"))? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            sources.push((Path::new("src").join(entry.file_name()), std::fs::read(entry.path())?));
+        }
+    }
+    sources.sort();
+    Ok(sources)
+}
+
+/// Runs `program` in `dir`, turning a missing binary into an
+/// `AppError::Proof`. The exit status is left to the caller.
+async fn spawn_tool(program: &'static str, args: Vec<String>, dir: PathBuf) -> AppResult<Output> {
+    let output = tokio::task::spawn_blocking(move || Command::new(program).args(&args).current_dir(&dir).output())
+        .await
+        .map_err(|e| AppError::Proof(e.to_string()))?;
+
+    output.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::Proof(format!(
+            "`{}` is not installed (install nargo with `noirup` and bb with `bbup`)",
+            program
+        )),
+        _ => AppError::Proof(format!("could not run {}: {}", program, e)),
+    })
+}
+
+/// Like `spawn_tool`, but a non-zero exit is an error carrying the tool's stderr.
+async fn run_tool(program: &'static str, args: Vec<String>, dir: PathBuf) -> AppResult<Output> {
+    let step = args.first().cloned().unwrap_or_default();
+    let output = spawn_tool(program, args, dir).await?;
+    if !output.status.success() {
+        return Err(AppError::Proof(format!(
+            "{} {} failed ({}): {}",
+            program,
+            step,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output)
+}
+
+fn path_arg(path: PathBuf) -> String {
+    path.display().to_string()
+}
+
+/// First `major.minor.patch` in a `--version` banner, ignoring pre-release
+/// suffixes (`nargo version = 1.0.0-beta.3` → `(1, 0, 0)`).
+fn parse_version(banner: &str) -> Option<(u64, u64, u64)> {
+    banner.split(|c: char| !(c.is_ascii_digit() || c == '.')).find_map(|word| {
+        let mut parts = word.split('.').map(|p| p.parse::<u64>().ok());
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Some(major)), Some(Some(minor)), Some(Some(patch))) => Some((major, minor, patch)),
+            _ => None,
+        }
+    })
+}
+
+async fn check_version(program: &'static str, minimum: (u64, u64, u64)) -> AppResult<()> {
+    let output = run_tool(program, vec!["--version".to_string()], std::env::temp_dir()).await?;
+    let banner = String::from_utf8_lossy(&output.stdout);
+    let version = parse_version(&banner)
+        .ok_or_else(|| AppError::Proof(format!("unrecognised `{} --version` output: {}", program, banner.trim())))?;
+    if version < minimum {
+        return Err(AppError::Proof(format!(
+            "{} {}.{}.{} is too old, need {}.{}.{} or newer",
+            program, version.0, version.1, version.2, minimum.0, minimum.1, minimum.2
+        )));
+    }
+    Ok(())
+}
+
+/// `bb`'s `public_inputs` file, one decimal string per field element.
+fn parse_public_inputs(bytes: &[u8]) -> AppResult<Vec<String>> {
+    if !bytes.len().is_multiple_of(FIELD_BYTES) {
+        return Err(AppError::Proof(format!("public inputs are {} bytes, not whole field elements", bytes.len())));
+    }
+    Ok(bytes.chunks(FIELD_BYTES).map(|field| U256::from_be_slice(field).to_string()).collect())
+}
+
+/// The inverse of `parse_public_inputs`.
+fn encode_public_inputs(inputs: &[String]) -> AppResult<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(inputs.len() * FIELD_BYTES);
+    for input in inputs {
+        let field = U256::from_str(input).map_err(|e| AppError::invalid_input(format!("public input {:?}: {}", input, e)))?;
+        bytes.extend_from_slice(&field.to_be_bytes::<FIELD_BYTES>());
+    }
+    Ok(bytes)
+}
+
+/// The circuit's inputs as nargo reads them. Holds the private witness,
+/// so it only ever goes to a proof workspace, never to a log.
+fn prover_toml(request: &ProofRequest) -> String {
+    let [_, intent_hash, expiry, prover, intent_commitment, anchor_hi, anchor_lo, _] = request.public_inputs();
+    format!(
+        "balance = \"{}\"\nbid_amount = \"{}\"\nprice_ceiling = \"{}\"\nintent_hash = \"{}\"\nexpiry = \"{}\"\nprover = \"{}\"\nintent_commitment = \"{}\"\nanchor_hi = \"{}\"\nanchor_lo = \"{}\"\n",
+        request.balance, request.bid_amount, request.price_ceiling, intent_hash, expiry, prover, intent_commitment, anchor_hi, anchor_lo
+    )
+}
+
+/// The Noir circuit in `noir-circuit/`, proved with `nargo execute` +
+/// `bb prove` and checked with `bb verify`.
+pub struct NargoBackend {
+    /// A Noir package (`Nargo.toml` + `src/`) to prove instead of the bundled one.
+    circuit_dir: Option<PathBuf>,
+}
+
+impl NargoBackend {
+    pub fn new(circuit_dir: Option<PathBuf>) -> Self {
+        NargoBackend { circuit_dir }
+    }
+}
+
+#[async_trait]
+impl ProvingBackend for NargoBackend {
+    fn kind(&self) -> ProvingBackendKind {
+        ProvingBackendKind::Nargo
+    }
+
+    fn circuit_name(&self) -> &'static str {
+        CIRCUIT_PACKAGE
+    }
+
+    /// `nargo compile`, then `bb write_vk` on the program.
+    async fn compile(&self) -> AppResult<CompiledCircuit> {
+        check_version("nargo", MIN_NARGO_VERSION).await?;
+        check_version("bb", MIN_BB_VERSION).await?;
+        let workspace = ProofWorkspace::create(self.circuit_dir.as_deref())?;
+        run_tool("nargo", vec!["compile".into()], workspace.dir.clone()).await?;
+        let args = vec![
+            "write_vk".into(),
+            "-b".into(),
+            path_arg(workspace.target(&format!("{}.json", CIRCUIT_PACKAGE))),
+            "-o".into(),
+            path_arg(workspace.dir.join("target")),
+        ];
+        run_tool("bb", args, workspace.dir.clone()).await?;
+        tracing::info!("built circuit verification key");
+        Ok(CompiledCircuit {
+            artifact: Some(std::fs::read(workspace.target(&format!("{}.json", CIRCUIT_PACKAGE)))?),
+            verification_key: std::fs::read(workspace.target("vk"))?,
+        })
+    }
+
+    /// Writes the request into a `Prover.toml` in a fresh workspace, has
+    /// `nargo execute` solve the witness and `bb prove` prove it.
+    async fn prove(&self, request: &ProofRequest) -> AppResult<ProofOutput> {
+        check_version("nargo", MIN_NARGO_VERSION).await?;
+        check_version("bb", MIN_BB_VERSION).await?;
+
+        let workspace = ProofWorkspace::create(self.circuit_dir.as_deref())?;
+        std::fs::write(workspace.dir.join("Prover.toml"), prover_toml(request))?;
+
+        run_tool("nargo", vec!["execute".into(), "witness".into()], workspace.dir.clone()).await?;
+        let args = vec![
+            "prove".into(),
+            "-b".into(),
+            path_arg(workspace.target(&format!("{}.json", CIRCUIT_PACKAGE))),
+            "-w".into(),
+            path_arg(workspace.target("witness.gz")),
+            "-o".into(),
+            path_arg(workspace.dir.join("target")),
+        ];
+        run_tool("bb", args, workspace.dir.clone()).await?;
+
+        Ok(ProofOutput {
+            proof: std::fs::read(workspace.target("proof"))?,
+            public_inputs: parse_public_inputs(&std::fs::read(workspace.target("public_inputs"))?)?,
+        })
+    }
+
+    async fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[String]) -> AppResult<bool> {
+        check_version("bb", MIN_BB_VERSION).await?;
+
+        let workspace = ProofWorkspace::empty()?;
+        std::fs::write(workspace.target("vk"), verification_key)?;
+        std::fs::write(workspace.target("proof"), proof)?;
+        std::fs::write(workspace.target("public_inputs"), encode_public_inputs(public_inputs)?)?;
+        let args = vec![
+            "verify".into(),
+            "-k".into(),
+            path_arg(workspace.target("vk")),
+            "-p".into(),
+            path_arg(workspace.target("proof")),
+            "-i".into(),
+            path_arg(workspace.target("public_inputs")),
+        ];
+        let output = spawn_tool("bb", args, workspace.dir.clone()).await?;
+        if !output.status.success() {
+            tracing::info!(stderr = %String::from_utf8_lossy(&output.stderr).trim(), "bb rejected the proof");
+        }
+        Ok(output.status.success())
+    }
+
+    async fn unavailable(&self) -> Option<String> {
+        let nargo = check_version("nargo", MIN_NARGO_VERSION).await;
+        let bb = check_version("bb", MIN_BB_VERSION).await;
+        nargo.and(bb).err().map(|e| e.to_string())
+    }
+
+    /// Hash of the circuit's source files, names included.
+    fn circuit_hash(&self) -> AppResult<B256> {
+        let mut preimage = Vec::new();
+        for (path, contents) in circuit_sources(self.circuit_dir.as_deref())? {
+            preimage.extend_from_slice(path.to_string_lossy().as_bytes());
+            preimage.push(0);
+            preimage.extend_from_slice(&(contents.len() as u64).to_be_bytes());
+            preimage.extend_from_slice(&contents);
+        }
+        Ok(keccak256(preimage))
+    }
+}
+
+/// The range proof is over a 64-bit value: `price_ceiling - bid` is shown
+/// to be non-negative. The balance is public, so `bid <= balance` is
+/// checked directly.
+const RANGE_BITS: usize = 64;
+const RANGE_PARTIES: usize = 1;
+const COMMITMENT_BYTES: usize = 32;
+/// Domain separator for the proof transcript; bump it with any change to
+/// what the range proof shows.
+const TRANSCRIPT_LABEL: &[u8] = b"cabalmesh-bid-proof-v4";
+
+/// The statement without a circuit: a Pedersen commitment to
+/// `price_ceiling`, and a Bulletproofs range proof that it minus the public
+/// bid lies in `[0, 2^64)`. The verifier derives the slack commitment
+/// itself, as `C - bid·B`, so the range proof only holds for the bid it was
+/// made for, and checks the bid against the public balance. Every public
+/// input is also absorbed into the transcript, binding the proof to the
+/// intent.
+///
+/// Proof bytes are the compressed commitment followed by the range proof.
+#[derive(Default)]
+pub struct BulletproofsBackend;
+
+impl BulletproofsBackend {
+    fn transcript(public_inputs: &[String]) -> Transcript {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        for input in public_inputs {
+            transcript.append_message(b"public_input", input.as_bytes());
+        }
+        transcript
+    }
+
+    fn gens() -> (BulletproofGens, PedersenGens) {
+        (BulletproofGens::new(RANGE_BITS, RANGE_PARTIES), PedersenGens::default())
+    }
+
+    /// There's no setup: the statement is fixed by the transcript label and
+    /// the range proof's shape, which stand in for a verification key.
+    fn verification_key() -> Vec<u8> {
+        let shape = [RANGE_BITS as u64, RANGE_PARTIES as u64].map(u64::to_be_bytes).concat();
+        [TRANSCRIPT_LABEL, &shape].concat()
+    }
+
+    /// Commitments to `value - bid` for each committed value, which the
+    /// range proof must be over.
+    fn slack_commitments(pc_gens: &PedersenGens, commitments: &[CompressedRistretto], bid: u64) -> Option<Vec<CompressedRistretto>> {
+        let bid = Scalar::from(bid) * pc_gens.B;
+        commitments.iter().map(|c| Some((c.decompress()? - bid).compress())).collect()
+    }
+}
+
+#[async_trait]
+impl ProvingBackend for BulletproofsBackend {
+    fn kind(&self) -> ProvingBackendKind {
+        ProvingBackendKind::Bulletproofs
+    }
+
+    fn circuit_name(&self) -> &'static str {
+        "cabalmesh_bid_range"
+    }
+
+    async fn compile(&self) -> AppResult<CompiledCircuit> {
+        Ok(CompiledCircuit { artifact: None, verification_key: Self::verification_key() })
+    }
+
+    async fn prove(&self, request: &ProofRequest) -> AppResult<ProofOutput> {
+        let values = [request.price_ceiling];
+        let slacks = values.map(|value| value - request.bid_amount);
+        let blindings: Vec<Scalar> = (0..RANGE_PARTIES)
+            .map(|_| {
+                let mut wide = [0u8; 64];
+                wide[..32].copy_from_slice(&rand::random::<[u8; 32]>());
+                wide[32..].copy_from_slice(&rand::random::<[u8; 32]>());
+                Scalar::from_bytes_mod_order_wide(&wide)
+            })
+            .collect();
+        let public_inputs = request.public_inputs().to_vec();
+        let transcript_inputs = public_inputs.clone();
+
+        let (proof, commitments) = tokio::task::spawn_blocking(move || {
+            let (bp_gens, pc_gens) = Self::gens();
+            let mut transcript = Self::transcript(&transcript_inputs);
+            // Same blinding for the value and its slack, so the slack
+            // commitment is the value's commitment minus `bid·B`.
+            let (proof, _) = RangeProof::prove_multiple(&bp_gens, &pc_gens, &mut transcript, &slacks, &blindings, RANGE_BITS)?;
+            let commitments: Vec<_> =
+                values.iter().zip(&blindings).map(|(value, blinding)| pc_gens.commit(Scalar::from(*value), *blinding).compress()).collect();
+            Ok::<_, bulletproofs::ProofError>((proof, commitments))
+        })
+        .await
+        .map_err(|e| AppError::Proof(e.to_string()))?
+        .map_err(|e| AppError::Proof(format!("range proof failed: {}", e)))?;
+
+        let mut bytes: Vec<u8> = commitments.iter().flat_map(|c| c.to_bytes()).collect();
+        bytes.extend(proof.to_bytes());
+        Ok(ProofOutput { proof: bytes, public_inputs })
+    }
+
+    async fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[String]) -> AppResult<bool> {
+        let Some(bid) = bid_of(public_inputs).filter(|bid| *bid > 0) else {
+            return Ok(false);
+        };
+        if balance_of(public_inputs).is_none_or(|balance| bid > balance) || verification_key != Self::verification_key() {
+            return Ok(false);
+        }
+        let public_inputs = public_inputs.to_vec();
+        let split = RANGE_PARTIES * COMMITMENT_BYTES;
+        if proof.len() <= split {
+            return Ok(false);
+        }
+        let commitments: Vec<CompressedRistretto> = proof[..split]
+            .chunks(COMMITMENT_BYTES)
+            .filter_map(|c| CompressedRistretto::from_slice(c).ok())
+            .collect();
+        let Ok(range_proof) = RangeProof::from_bytes(&proof[split..]) else {
+            return Ok(false);
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let (bp_gens, pc_gens) = Self::gens();
+            let Some(slacks) = Self::slack_commitments(&pc_gens, &commitments, bid) else {
+                return false;
+            };
+            range_proof
+                .verify_multiple(&bp_gens, &pc_gens, &mut Self::transcript(&public_inputs), &slacks, RANGE_BITS)
+                .is_ok()
+        })
+        .await
+        .map_err(|e| AppError::Proof(e.to_string()))
+    }
+
+    async fn unavailable(&self) -> Option<String> {
+        None
+    }
+
+    fn circuit_hash(&self) -> AppResult<B256> {
+        Ok(keccak256(Self::verification_key()))
+    }
+}
+
+const MOCK_LABEL: &str = "cabalmesh-mock-proof";
+
+/// Same input always gives the same "proof": a hash of the public inputs.
+/// Proves nothing; for tests and demos only.
+#[derive(Default)]
+pub struct MockBackend;
+
+impl MockBackend {
+    fn proof_for(public_inputs: &[String]) -> Vec<u8> {
+        keccak256(format!("{}:{}", MOCK_LABEL, public_inputs.join(","))).to_vec()
+    }
+}
+
+#[async_trait]
+impl ProvingBackend for MockBackend {
+    fn kind(&self) -> ProvingBackendKind {
+        ProvingBackendKind::Mock
+    }
+
+    fn circuit_name(&self) -> &'static str {
+        "cabalmesh_bid_mock"
+    }
+
+    async fn compile(&self) -> AppResult<CompiledCircuit> {
+        Ok(CompiledCircuit { artifact: None, verification_key: MOCK_LABEL.as_bytes().to_vec() })
+    }
+
+    async fn prove(&self, request: &ProofRequest) -> AppResult<ProofOutput> {
+        Ok(ProofOutput {
+            proof: Self::proof_for(&request.public_inputs()),
+            public_inputs: request.public_inputs().to_vec(),
+        })
+    }
+
+    async fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[String]) -> AppResult<bool> {
+        Ok(verification_key == MOCK_LABEL.as_bytes() && bid_of(public_inputs).is_some() && proof == Self::proof_for(public_inputs))
+    }
+
+    async fn unavailable(&self) -> Option<String> {
+        None
+    }
+
+    fn circuit_hash(&self) -> AppResult<B256> {
+        Ok(keccak256(MOCK_LABEL))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zk_handler::ProofBinding;
+    use alloy::primitives::{Address, B256};
+
+    fn request(bid_amount: u64) -> ProofRequest {
+        ProofRequest {
+            balance: 1000,
+            bid_amount,
+            price_ceiling: 95,
+            binding: ProofBinding {
+                intent_hash: U256::from(7),
+                commitment: U256::from(8),
+                expiry: 1_800_000_000,
+                prover: Address::with_last_byte(1),
+            },
+            anchor: B256::from((U256::from(2) << 128) | U256::from(3)),
+        }
+    }
+
+    #[test]
+    fn prepares_witnesses_and_parses_bb_output() {
+        assert_eq!(
+            prover_toml(&request(90)),
+            "balance = \"1000\"\nbid_amount = \"90\"\nprice_ceiling = \"95\"\nintent_hash = \"7\"\nexpiry = \"1800000000\"\nprover = \"1\"\nintent_commitment = \"8\"\nanchor_hi = \"2\"\nanchor_lo = \"3\"\n"
+        );
+
+        let workspace = ProofWorkspace::create(None).unwrap();
+        let dir = workspace.dir.clone();
+        assert!(dir.join("src/main.nr").exists() && dir.join("Nargo.toml").exists());
+        drop(workspace);
+        assert!(!dir.exists());
+
+        assert_eq!(parse_version("nargo version = 1.0.0-beta.3\nnoirc version = 1.0.0-beta.3+abc"), Some((1, 0, 0)));
+        assert_eq!(parse_version("v0.84.0"), Some((0, 84, 0)));
+        assert!(parse_version("0.36.0").unwrap() < MIN_NARGO_VERSION);
+
+        let mut fields = vec![0u8; 2 * FIELD_BYTES];
+        fields[FIELD_BYTES - 1] = 90;
+        fields[2 * FIELD_BYTES - 2] = 1;
+        assert_eq!(parse_public_inputs(&fields).unwrap(), vec!["90", "256"]);
+        assert!(parse_public_inputs(&fields[..40]).is_err());
+        assert_eq!(encode_public_inputs(&["90".to_string(), "256".to_string()]).unwrap(), fields);
+    }
+
+    /// A native proof verifies for its own public inputs only, not once
+    /// tampered with, not for a bid over the public balance, and not when
+    /// its commitment isn't to a value at least the bid.
+    #[tokio::test]
+    async fn bulletproofs_bind_the_public_inputs() {
+        let backend = BulletproofsBackend;
+        let vk = backend.compile().await.unwrap().verification_key;
+        let request = request(90);
+        let output = backend.prove(&request).await.unwrap();
+        assert_eq!(output.public_inputs, request.public_inputs());
+        assert!(backend.verify(&vk, &output.proof, &output.public_inputs).await.unwrap());
+        assert!(!backend.verify(b"other circuit", &output.proof, &output.public_inputs).await.unwrap());
+
+        for field in 0..output.public_inputs.len() {
+            let mut other = output.public_inputs.clone();
+            other[field].push('1');
+            assert!(!backend.verify(&vk, &output.proof, &other).await.unwrap());
+        }
+        let mut tampered = output.proof.clone();
+        tampered[0] ^= 1;
+        assert!(!backend.verify(&vk, &tampered, &output.public_inputs).await.unwrap());
+        assert!(!backend.verify(&vk, &output.proof[..64], &output.public_inputs).await.unwrap());
+
+        // A range proof over a value unrelated to the bid, sent as if it
+        // were the ceiling commitment, doesn't verify.
+        let (bp_gens, pc_gens) = BulletproofsBackend::gens();
+        let mut transcript = BulletproofsBackend::transcript(&output.public_inputs);
+        let (forged, commitments) =
+            RangeProof::prove_multiple(&bp_gens, &pc_gens, &mut transcript, &[5], &[Scalar::ONE], RANGE_BITS).unwrap();
+        let mut forged_bytes: Vec<u8> = commitments.iter().flat_map(|c| c.to_bytes()).collect();
+        forged_bytes.extend(forged.to_bytes());
+        assert!(!backend.verify(&vk, &forged_bytes, &output.public_inputs).await.unwrap());
+
+        let mock = MockBackend;
+        let mock_vk = mock.compile().await.unwrap().verification_key;
+        let fake = mock.prove(&request).await.unwrap();
+        assert_eq!(fake, mock.prove(&request).await.unwrap());
+        assert!(mock.verify(&mock_vk, &fake.proof, &fake.public_inputs).await.unwrap());
+        assert!(!backend.verify(&vk, &fake.proof, &fake.public_inputs).await.unwrap());
+        assert_ne!(backend.circuit_hash().unwrap(), mock.circuit_hash().unwrap());
+
+        let broke = ProofRequest { balance: 80, ..request };
+        let overdrawn = backend.prove(&broke).await.unwrap();
+        assert!(!backend.verify(&vk, &overdrawn.proof, &overdrawn.public_inputs).await.unwrap());
+    }
+}
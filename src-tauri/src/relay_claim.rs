@@ -0,0 +1,65 @@
+use alloy::primitives::{keccak256, Address, Signature, B256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::error::{AppError, AppResult};
+
+/// A relay peer's report on a `relay_tx` it was asked to broadcast, as sent
+/// in `relay_confirmed` intents. EIP-191 signed by the relayer's primary
+/// identity, which is what its relay reputation is kept under: the mesh
+/// peer id is regenerated on every start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedRelayClaim {
+    pub queue_id: String,
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub relayer: String,
+    pub signature: String,
+}
+
+impl SignedRelayClaim {
+    fn digest(queue_id: &str, status: &str, tx_hash: Option<&str>) -> AppResult<B256> {
+        Ok(keccak256(serde_json::to_vec(&(queue_id, status, tx_hash))?))
+    }
+
+    pub fn sign(queue_id: &str, status: &str, tx_hash: Option<&str>, signer: &PrivateKeySigner) -> AppResult<Self> {
+        let digest = Self::digest(queue_id, status, tx_hash)?;
+        let signature = signer.sign_message_sync(digest.as_slice()).map_err(AppError::crypto)?;
+        Ok(SignedRelayClaim {
+            queue_id: queue_id.to_string(),
+            status: status.to_string(),
+            tx_hash: tx_hash.map(str::to_string),
+            relayer: signer.address().to_string(),
+            signature: signature.to_string(),
+        })
+    }
+
+    /// The relayer's address, once the signature is checked to be its.
+    pub fn verify(&self) -> AppResult<Address> {
+        let digest = Self::digest(&self.queue_id, &self.status, self.tx_hash.as_deref())?;
+        let signature = Signature::from_str(&self.signature).map_err(AppError::invalid_input)?;
+        let recovered = signature.recover_address_from_msg(digest.as_slice()).map_err(AppError::invalid_input)?;
+        if recovered != Address::from_str(&self.relayer)? {
+            return Err(AppError::invalid_input("relay claim signature does not match its relayer"));
+        }
+        Ok(recovered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_signed_relay_claims() {
+        let signer = PrivateKeySigner::random();
+        let claim = SignedRelayClaim::sign("q1", "confirmed", Some("0xabc"), &signer).unwrap();
+        assert_eq!(claim.verify().unwrap(), signer.address());
+
+        let retargeted = SignedRelayClaim { queue_id: "q2".to_string(), ..claim.clone() };
+        assert!(retargeted.verify().is_err());
+        let impersonated = SignedRelayClaim { relayer: PrivateKeySigner::random().address().to_string(), ..claim };
+        assert!(impersonated.verify().is_err());
+    }
+}
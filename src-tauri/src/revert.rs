@@ -0,0 +1,98 @@
+use alloy::{
+    dyn_abi::{DynSolValue, JsonAbiExt},
+    json_abi,
+    sol_types::{Panic, Revert, SolError},
+};
+use serde::Serialize;
+use std::fmt;
+
+use crate::blockchain_bridge::{IEscrow, IMarketplace, IVoucher};
+
+/// Why a contract call reverted, decoded against the contract ABIs we ship.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RevertReason {
+    /// `require(cond, "message")` / `revert("message")`.
+    Message { message: String },
+    /// A Solidity panic (failed assert, overflow, ...), by panic code.
+    Panic { code: u64 },
+    /// A custom error from one of our contracts, e.g. `ERC721NonexistentToken(7)`.
+    CustomError { name: String, args: Vec<String> },
+    /// Revert data none of our ABIs recognise, or none at all.
+    Unknown { data: String },
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Message { message } => write!(f, "{}", message),
+            RevertReason::Panic { code } => write!(f, "panic 0x{:02x}", code),
+            RevertReason::CustomError { name, args } => write!(f, "{}({})", name, args.join(", ")),
+            RevertReason::Unknown { data } if data.is_empty() => write!(f, "no revert reason given"),
+            RevertReason::Unknown { data } => write!(f, "unrecognised revert data {}", data),
+        }
+    }
+}
+
+/// Decodes revert data as a require string, a panic, or a custom error from
+/// the Escrow / Marketplace / Voucher ABIs, in that order.
+pub fn decode_revert(data: &[u8]) -> RevertReason {
+    if let Ok(revert) = Revert::abi_decode(data) {
+        return RevertReason::Message { message: revert.reason };
+    }
+    if let Ok(panic) = Panic::abi_decode(data) {
+        return RevertReason::Panic { code: panic.code.saturating_to() };
+    }
+
+    if let Some(selector) = data.get(..4).and_then(|s| <[u8; 4]>::try_from(s).ok()) {
+        let signature = IEscrow::IEscrowErrors::signature_by_selector(selector)
+            .or_else(|| IMarketplace::IMarketplaceErrors::signature_by_selector(selector))
+            .or_else(|| IVoucher::IVoucherErrors::signature_by_selector(selector));
+
+        if let Some(error) = signature.and_then(|sig| json_abi::Error::parse(sig).ok()) {
+            let args = error
+                .abi_decode_input(&data[4..])
+                .map(|values| values.iter().map(format_value).collect())
+                .unwrap_or_default();
+            return RevertReason::CustomError { name: error.name, args };
+        }
+    }
+
+    RevertReason::Unknown { data: if data.is_empty() { String::new() } else { format!("0x{}", hex::encode(data)) } }
+}
+
+fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Address(a) => a.to_string(),
+        DynSolValue::Uint(u, _) => u.to_string(),
+        DynSolValue::Int(i, _) => i.to_string(),
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::String(s) => format!("{:?}", s),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+
+    #[test]
+    fn decodes_require_strings_and_custom_errors() {
+        let require = Revert::from("Wrong amount").abi_encode();
+        assert_eq!(decode_revert(&require), RevertReason::Message { message: "Wrong amount".to_string() });
+
+        let custom = IVoucher::ERC721NonexistentToken { tokenId: U256::from(7) }.abi_encode();
+        assert_eq!(
+            decode_revert(&custom),
+            RevertReason::CustomError { name: "ERC721NonexistentToken".to_string(), args: vec!["7".to_string()] }
+        );
+
+        let owner = Address::repeat_byte(0x11);
+        let custom = IVoucher::ERC721IncorrectOwner { sender: owner, tokenId: U256::from(3), owner }.abi_encode();
+        assert!(decode_revert(&custom).to_string().starts_with("ERC721IncorrectOwner("));
+
+        assert_eq!(decode_revert(&[]), RevertReason::Unknown { data: String::new() });
+        assert_eq!(decode_revert(&[0xde, 0xad, 0xbe, 0xef]), RevertReason::Unknown { data: "0xdeadbeef".to_string() });
+    }
+}
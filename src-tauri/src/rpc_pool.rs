@@ -0,0 +1,450 @@
+use alloy::{
+    contract::Error as ContractError,
+    primitives::Bytes,
+    providers::{DynProvider, PendingTransactionError, Provider, ProviderBuilder, RootProvider},
+    signers::local::PrivateKeySigner,
+    transports::{RpcError, TransportError},
+};
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::blockchain_bridge::DEFAULT_AVAX_RPC_URL;
+
+/// After this many consecutive failures an endpoint is benched for
+/// `COOLDOWN` and only tried again once every healthy endpoint has failed.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+const COOLDOWN: Duration = Duration::from_secs(30);
+/// Weight of the newest sample in the latency moving average.
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+
+/// How long each class of RPC call may take before the endpoint is treated
+/// as unreachable. `write` is deliberately short: it's the window an online
+/// send gets before we give up and sign offline for mesh relay instead.
+#[derive(Debug, Clone, Copy)]
+pub struct RpcTimeouts {
+    /// View calls, balance/nonce/gas reads.
+    pub read: Duration,
+    /// Send + receipt for writes that can fall back to offline signing.
+    pub write: Duration,
+    /// Send + receipt for writes with no offline fallback (release, refund, mint...).
+    pub confirm: Duration,
+    /// Broadcasting another peer's already-signed transaction.
+    pub relay: Duration,
+}
+
+impl Default for RpcTimeouts {
+    fn default() -> Self {
+        RpcTimeouts {
+            read: Duration::from_secs(8),
+            write: Duration::from_secs(6),
+            confirm: Duration::from_secs(60),
+            relay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RpcTimeouts {
+    /// Defaults, overridden per class by `RPC_{READ,WRITE,CONFIRM,RELAY}_TIMEOUT_SECS`.
+    pub fn from_env() -> Self {
+        let secs = |var: &str, default: Duration| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|s| *s > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+        let d = Self::default();
+        RpcTimeouts {
+            read: secs("RPC_READ_TIMEOUT_SECS", d.read),
+            write: secs("RPC_WRITE_TIMEOUT_SECS", d.write),
+            confirm: secs("RPC_CONFIRM_TIMEOUT_SECS", d.confirm),
+            relay: secs("RPC_RELAY_TIMEOUT_SECS", d.relay),
+        }
+    }
+}
+
+/// Why a single attempt against one endpoint failed. `endpoint_fault` is true
+/// when another endpoint might do better (transport failure, garbage or null
+/// response); false when the node gave a real answer (e.g. a revert), which
+/// no amount of failover will change. `revert_data` is set when that answer
+/// was an execution revert (empty if the node gave no data with it).
+#[derive(Debug)]
+pub struct AttemptError {
+    pub endpoint_fault: bool,
+    pub message: String,
+    pub revert_data: Option<Bytes>,
+}
+
+impl AttemptError {
+    pub(crate) fn endpoint_fault(message: String) -> Self {
+        AttemptError { endpoint_fault: true, message, revert_data: None }
+    }
+}
+
+impl From<TransportError> for AttemptError {
+    fn from(e: TransportError) -> Self {
+        let endpoint_fault = matches!(e, RpcError::Transport(_) | RpcError::NullResp | RpcError::DeserError { .. });
+        let revert_data = e.as_error_resp().and_then(|resp| {
+            resp.as_revert_data()
+                .or_else(|| resp.message.contains("revert").then(Bytes::new))
+        });
+        AttemptError { endpoint_fault, message: e.to_string(), revert_data }
+    }
+}
+
+impl From<PendingTransactionError> for AttemptError {
+    fn from(e: PendingTransactionError) -> Self {
+        match e {
+            PendingTransactionError::TransportError(t) => t.into(),
+            other => AttemptError::endpoint_fault(other.to_string()),
+        }
+    }
+}
+
+impl From<ContractError> for AttemptError {
+    fn from(e: ContractError) -> Self {
+        match e {
+            ContractError::TransportError(t) => t.into(),
+            ContractError::PendingTransactionError(p) => p.into(),
+            other => AttemptError { endpoint_fault: false, message: other.to_string(), revert_data: None },
+        }
+    }
+}
+
+/// Outcome of a pooled call once every eligible endpoint has been tried.
+#[derive(Debug)]
+pub enum RpcPoolError {
+    /// No endpoint answered in time — callers with an offline path should take it.
+    Unreachable(String),
+    /// An endpoint answered, with an error — a revert if `revert_data` is set.
+    Rejected { message: String, revert_data: Option<Bytes> },
+}
+
+impl fmt::Display for RpcPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcPoolError::Unreachable(e) => write!(f, "RPC unreachable: {}", e),
+            RpcPoolError::Rejected { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for RpcPoolError {}
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    latency_ms: Option<f64>,
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    benched_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+impl EndpointHealth {
+    fn is_benched(&self, now: Instant) -> bool {
+        self.benched_until.is_some_and(|until| now < until)
+    }
+
+    /// Lower is better: fewer consecutive failures first, then latency. An
+    /// endpoint that has never answered has no latency, so an untried one is
+    /// probed early but one that keeps failing never outranks a working one.
+    fn score(&self) -> (u32, f64) {
+        (self.consecutive_failures, self.latency_ms.unwrap_or(0.0))
+    }
+}
+
+struct RpcEndpoint {
+    url: String,
+    /// Shared for the life of the pool so every call reuses the same HTTP
+    /// client and connection pool instead of building a fresh one.
+    provider: RootProvider,
+    health: Mutex<EndpointHealth>,
+}
+
+/// Per-endpoint health as reported to the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub healthy: bool,
+    pub latency_ms: Option<u64>,
+    pub successes: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+/// A ranked set of RPC endpoints with latency/error tracking. Reads fail over
+/// to the next-best endpoint; writes stick to the single best endpoint, since
+/// retrying a send elsewhere could broadcast the same intent twice.
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    pub timeouts: RpcTimeouts,
+}
+
+impl RpcPool {
+    pub fn new(urls: Vec<String>, timeouts: RpcTimeouts) -> Self {
+        let mut endpoints = Vec::new();
+        for url in urls {
+            let url = url.trim().to_string();
+            if url.is_empty() || endpoints.iter().any(|e: &RpcEndpoint| e.url == url) {
+                continue;
+            }
+            match url.parse() {
+                Ok(parsed) => endpoints.push(RpcEndpoint {
+                    provider: RootProvider::new_http(parsed),
+                    url,
+                    health: Mutex::new(EndpointHealth::default()),
+                }),
+                Err(e) => tracing::warn!(%url, error = %e, "ignoring invalid RPC endpoint"),
+            }
+        }
+        if endpoints.is_empty() {
+            endpoints.push(RpcEndpoint {
+                url: DEFAULT_AVAX_RPC_URL.to_string(),
+                provider: RootProvider::new_http(DEFAULT_AVAX_RPC_URL.parse().expect("default RPC URL is valid")),
+                health: Mutex::new(EndpointHealth::default()),
+            });
+        }
+        RpcPool { endpoints, timeouts }
+    }
+
+    /// Endpoint indices, best first: healthy endpoints by score, then benched
+    /// ones as a last resort.
+    fn ranked(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut ranked: Vec<(usize, bool, (u32, f64))> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let h = e.health.lock().unwrap();
+                (i, h.is_benched(now), h.score())
+            })
+            .collect();
+        ranked.sort_by(|(_, benched_a, (failures_a, latency_a)), (_, benched_b, (failures_b, latency_b))| {
+            benched_a.cmp(benched_b).then(failures_a.cmp(failures_b)).then(latency_a.total_cmp(latency_b))
+        });
+        ranked.into_iter().map(|(i, _, _)| i).collect()
+    }
+
+    fn record_success(&self, index: usize, elapsed: Duration) {
+        let mut h = self.endpoints[index].health.lock().unwrap();
+        let sample = elapsed.as_secs_f64() * 1000.0;
+        h.latency_ms = Some(match h.latency_ms {
+            Some(prev) => prev + LATENCY_EWMA_ALPHA * (sample - prev),
+            None => sample,
+        });
+        h.successes += 1;
+        h.consecutive_failures = 0;
+        h.benched_until = None;
+    }
+
+    fn record_failure(&self, index: usize, error: &str) {
+        let mut h = self.endpoints[index].health.lock().unwrap();
+        h.failures += 1;
+        h.consecutive_failures += 1;
+        h.last_error = Some(error.to_string());
+        if h.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            h.benched_until = Some(Instant::now() + COOLDOWN);
+        }
+    }
+
+    /// Runs one attempt against one endpoint, recording its health.
+    async fn attempt<T, Fut>(&self, index: usize, limit: Duration, fut: Fut) -> Result<T, AttemptError>
+    where
+        Fut: Future<Output = Result<T, AttemptError>>,
+    {
+        let started = Instant::now();
+        match tokio::time::timeout(limit, fut).await {
+            Ok(Ok(value)) => {
+                self.record_success(index, started.elapsed());
+                Ok(value)
+            }
+            Ok(Err(e)) => {
+                if e.endpoint_fault {
+                    self.record_failure(index, &e.message);
+                } else {
+                    // The node answered; it's healthy even if the answer is "no".
+                    self.record_success(index, started.elapsed());
+                }
+                Err(e)
+            }
+            Err(_elapsed) => {
+                let message = format!("timed out after {}s", limit.as_secs());
+                self.record_failure(index, &message);
+                Err(AttemptError::endpoint_fault(message))
+            }
+        }
+    }
+
+    async fn with_failover<T, F, Fut>(&self, limit: Duration, op: F) -> Result<T, RpcPoolError>
+    where
+        F: Fn(DynProvider) -> Fut,
+        Fut: Future<Output = Result<T, AttemptError>>,
+    {
+        let mut last_error = String::from("no RPC endpoints configured");
+        for index in self.ranked() {
+            let provider = self.endpoints[index].provider.clone().erased();
+            match self.attempt(index, limit, op(provider)).await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.endpoint_fault => {
+                    last_error = format!("{}: {}", self.endpoints[index].url, e.message);
+                }
+                Err(e) => return Err(RpcPoolError::Rejected { message: e.message, revert_data: e.revert_data }),
+            }
+        }
+        Err(RpcPoolError::Unreachable(last_error))
+    }
+
+    /// A read-only call, failing over across endpoints in health order.
+    pub async fn read<T, F, Fut>(&self, op: F) -> Result<T, RpcPoolError>
+    where
+        F: Fn(DynProvider) -> Fut,
+        Fut: Future<Output = Result<T, AttemptError>>,
+    {
+        self.with_failover(self.timeouts.read, op).await
+    }
+
+    /// Broadcasts an already-signed transaction. Safe to fail over: the same
+    /// signed bytes can only ever be mined once.
+    pub async fn relay<T, F, Fut>(&self, op: F) -> Result<T, RpcPoolError>
+    where
+        F: Fn(DynProvider) -> Fut,
+        Fut: Future<Output = Result<T, AttemptError>>,
+    {
+        self.with_failover(self.timeouts.relay, op).await
+    }
+
+    /// A signed write against the single healthiest endpoint, bounded by
+    /// `timeout` (normally `timeouts.write` or `timeouts.confirm`). Never
+    /// retried elsewhere, so an `Unreachable` here means the send may or may
+    /// not have reached the network.
+    pub async fn write<T, F, Fut>(&self, signer: PrivateKeySigner, timeout: Duration, op: F) -> Result<T, RpcPoolError>
+    where
+        F: FnOnce(DynProvider) -> Fut,
+        Fut: Future<Output = Result<T, AttemptError>>,
+    {
+        let index = self.ranked()[0];
+        let provider = ProviderBuilder::new()
+            .wallet(signer)
+            .connect_provider(self.endpoints[index].provider.clone())
+            .erased();
+        match self.attempt(index, timeout, op(provider)).await {
+            Ok(value) => Ok(value),
+            Err(e) if e.endpoint_fault => Err(RpcPoolError::Unreachable(format!("{}: {}", self.endpoints[index].url, e.message))),
+            Err(e) => Err(RpcPoolError::Rejected { message: e.message, revert_data: e.revert_data }),
+        }
+    }
+
+    /// Asks each endpoint, best first, for the chain id until one answers
+    /// within `limit`, recording health as usual. Returns the URL that
+    /// answered, or `None` if none did.
+    pub async fn probe(&self, limit: Duration) -> Option<String> {
+        for index in self.ranked() {
+            let provider = self.endpoints[index].provider.clone();
+            let answer = self.attempt(index, limit, async move { Ok::<_, AttemptError>(provider.get_chain_id().await?) }).await;
+            if answer.is_ok() {
+                return Some(self.endpoints[index].url.clone());
+            }
+        }
+        None
+    }
+
+    pub fn primary_url(&self) -> &str {
+        &self.endpoints[self.ranked()[0]].url
+    }
+
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|e| {
+                let h = e.health.lock().unwrap();
+                EndpointStatus {
+                    url: e.url.clone(),
+                    healthy: !h.is_benched(now) && h.consecutive_failures == 0,
+                    latency_ms: h.latency_ms.map(|l| l.round() as u64),
+                    successes: h.successes,
+                    failures: h.failures,
+                    last_error: h.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(urls: &[&str]) -> RpcPool {
+        RpcPool::new(urls.iter().map(|u| u.to_string()).collect(), RpcTimeouts::default())
+    }
+
+    /// A failing endpoint drops behind a healthy one and gets benched after
+    /// repeated failures; a success restores it.
+    #[test]
+    fn ranks_failing_endpoints_last() {
+        let pool = pool(&["http://127.0.0.1:9", "http://127.0.0.1:10"]);
+        pool.record_success(1, Duration::from_millis(50));
+        assert_eq!(pool.ranked(), vec![0, 1], "untried endpoints are probed first");
+
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            pool.record_failure(0, "connection refused");
+        }
+        assert_eq!(pool.ranked(), vec![1, 0]);
+        assert!(!pool.status()[0].healthy);
+
+        pool.record_success(0, Duration::from_millis(10));
+        assert_eq!(pool.ranked(), vec![0, 1]);
+        assert!(pool.status()[0].healthy);
+    }
+
+    /// A dead endpoint listed first drops behind a healthy one on its first
+    /// failure, and stays behind it once its cooldown runs out.
+    #[test]
+    fn dead_first_endpoint_does_not_outrank_a_healthy_one() {
+        let pool = pool(&["http://127.0.0.1:9", "http://127.0.0.1:10"]);
+        pool.record_success(1, Duration::from_millis(50));
+        pool.record_failure(0, "connection refused");
+        assert_eq!(pool.ranked(), vec![1, 0]);
+        assert_eq!(pool.primary_url(), "http://127.0.0.1:10");
+
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            pool.record_failure(0, "connection refused");
+        }
+        pool.endpoints[0].health.lock().unwrap().benched_until = Some(Instant::now());
+        assert_eq!(pool.ranked(), vec![1, 0], "cooldown over, but still failing");
+    }
+
+    /// With nothing listening anywhere, a read fails over through every
+    /// endpoint and reports `Unreachable` rather than hanging.
+    #[tokio::test]
+    async fn read_fails_over_then_reports_unreachable() {
+        let pool = pool(&["http://127.0.0.1:9", "http://127.0.0.1:10"]);
+        let result = pool
+            .read(|p| async move { Ok::<_, AttemptError>(p.get_block_number().await?) })
+            .await;
+
+        assert!(matches!(result, Err(RpcPoolError::Unreachable(_))));
+        assert!(pool.status().iter().all(|s| s.failures == 1));
+    }
+
+    #[tokio::test]
+    async fn probe_reports_no_endpoint_when_all_are_down() {
+        let pool = pool(&["http://127.0.0.1:9"]);
+        assert_eq!(pool.probe(Duration::from_secs(1)).await, None);
+        assert_eq!(pool.status()[0].failures, 1);
+    }
+
+    #[test]
+    fn skips_invalid_and_duplicate_urls() {
+        let pool = pool(&["not a url", "http://127.0.0.1:9", "http://127.0.0.1:9"]);
+        assert_eq!(pool.status().len(), 1);
+        assert_eq!(RpcPool::new(Vec::new(), RpcTimeouts::default()).primary_url(), DEFAULT_AVAX_RPC_URL);
+    }
+}
@@ -0,0 +1,171 @@
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::blockchain_bridge::{AssetListingView, DealView};
+use crate::connectivity::Reachability;
+use crate::error::AppResult;
+use crate::lifecycle::{Subsystem, SubsystemStatus};
+use crate::AppState;
+
+/// How often the scheduler wakes to see what's due. Intervals come from
+/// settings on every tick, so edits apply without a restart.
+const TICK: Duration = Duration::from_secs(5);
+
+/// What changed in a list fetched from the chain since the previous fetch.
+/// The first fetch reports everything as `added`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListDiff<T> {
+    pub added: Vec<T>,
+    pub updated: Vec<T>,
+    pub removed: Vec<u64>,
+}
+
+impl<T> ListDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Payload of the `chain-update` event, emitted only when something changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum ChainUpdate {
+    Balance { address: String, balance_wei: String, previous_wei: Option<String> },
+    Listings { diff: ListDiff<AssetListingView> },
+    Deals { address: String, diff: ListDiff<DealView> },
+}
+
+/// Diffs two lists keyed by `id`.
+pub fn diff_by_id<T: Clone + PartialEq>(old: &[T], new: &[T], id: impl Fn(&T) -> u64) -> ListDiff<T> {
+    let mut diff = ListDiff { added: Vec::new(), updated: Vec::new(), removed: Vec::new() };
+    for item in new {
+        match old.iter().find(|o| id(o) == id(item)) {
+            None => diff.added.push(item.clone()),
+            Some(o) if o != item => diff.updated.push(item.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old.iter().map(&id).filter(|i| !new.iter().any(|n| id(n) == *i)).collect();
+    diff
+}
+
+#[derive(Clone, Copy)]
+enum Job {
+    Balance,
+    Listings,
+    Deals,
+}
+
+/// Refreshes the balance snapshot (and with it the chain cache and queued-tx
+/// pre-flight), active listings and our deals on the intervals in settings,
+/// but only while the connectivity monitor reports online. Everything is due
+/// again the moment connectivity comes back. Changes go to `emit`.
+pub async fn run(state: AppState, emit: impl Fn(ChainUpdate) + Send + 'static) {
+    let mut connectivity = state.bridge.connectivity.subscribe();
+    let mut due = [Instant::now(); 3];
+    let mut balance: Option<String> = None;
+    let mut listings: Option<Vec<AssetListingView>> = None;
+    let mut deals: Option<(String, Vec<DealView>)> = None;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(TICK) => {}
+            changed = connectivity.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                if connectivity.borrow_and_update().state == Reachability::Online {
+                    due = [Instant::now(); 3];
+                }
+            }
+        }
+        if state.bridge.connectivity.status().state != Reachability::Online {
+            continue;
+        }
+
+        let settings = state.settings.get();
+        for (job, secs) in [
+            (Job::Balance, settings.refresh_balance_secs),
+            (Job::Listings, settings.refresh_listings_secs),
+            (Job::Deals, settings.refresh_deals_secs),
+        ] {
+            let slot = &mut due[job as usize];
+            if secs == 0 || Instant::now() < *slot {
+                continue;
+            }
+            *slot = Instant::now() + Duration::from_secs(secs);
+
+            let result = match job {
+                Job::Balance => refresh_balance(&state, &mut balance).await,
+                Job::Listings => refresh_listings(&state, &mut listings).await,
+                Job::Deals => refresh_deals(&state, &mut deals).await,
+            };
+            match result {
+                Ok(Some(update)) => emit(update),
+                Ok(None) => {}
+                Err(e) => tracing::warn!(error = %e, "background refresh failed"),
+            }
+        }
+    }
+}
+
+#[tracing::instrument(skip_all)]
+async fn refresh_balance(state: &AppState, last: &mut Option<String>) -> AppResult<Option<ChainUpdate>> {
+    let snapshot = state.bridge.sync_state("").await?;
+    state.lifecycle.set(Subsystem::BridgeSync, SubsystemStatus::Up);
+    let Some(asset) = snapshot.assets.into_iter().next() else {
+        return Ok(None);
+    };
+    if last.as_ref() == Some(&asset.amount) {
+        return Ok(None);
+    }
+    let previous_wei = last.replace(asset.amount.clone());
+    Ok(Some(ChainUpdate::Balance { address: asset.owner, balance_wei: asset.amount, previous_wei }))
+}
+
+#[tracing::instrument(skip_all)]
+async fn refresh_listings(state: &AppState, last: &mut Option<Vec<AssetListingView>>) -> AppResult<Option<ChainUpdate>> {
+    let fresh = state.bridge.get_active_asset_listings().await?;
+    let diff = diff_by_id(last.as_deref().unwrap_or_default(), &fresh, |l| l.id);
+    *last = Some(fresh);
+    // Keep offline peers' catalogues current; best-effort, the mesh may be down.
+    if !diff.is_empty() {
+        if let Err(e) = state.share_catalogue() {
+            tracing::debug!(error = %e, "could not share listing catalogue");
+        }
+    }
+    Ok((!diff.is_empty()).then_some(ChainUpdate::Listings { diff }))
+}
+
+#[tracing::instrument(skip_all)]
+async fn refresh_deals(state: &AppState, last: &mut Option<(String, Vec<DealView>)>) -> AppResult<Option<ChainUpdate>> {
+    let address = state.bridge.get_primary_address();
+    let fresh = state.bridge.get_my_deals(&address).await?;
+    // A different primary identity starts from scratch.
+    let previous = last.as_ref().filter(|(a, _)| *a == address).map(|(_, d)| d.as_slice()).unwrap_or_default();
+    let diff = diff_by_id(previous, &fresh, |d| d.deal_id);
+    *last = Some((address.clone(), fresh));
+    Ok((!diff.is_empty()).then_some(ChainUpdate::Deals { address, diff }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_lists_by_id() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Item(u64, &'static str);
+
+        let old = vec![Item(1, "lamp"), Item(2, "desk"), Item(3, "chair")];
+        let new = vec![Item(1, "lamp"), Item(2, "desk (discounted)"), Item(4, "rug")];
+        let diff = diff_by_id(&old, &new, |i| i.0);
+        assert_eq!(diff.added, vec![Item(4, "rug")]);
+        assert_eq!(diff.updated, vec![Item(2, "desk (discounted)")]);
+        assert_eq!(diff.removed, vec![3]);
+
+        assert!(diff_by_id(&new, &new, |i| i.0).is_empty());
+        assert_eq!(diff_by_id(&[], &new, |i| i.0).added.len(), 3);
+    }
+}
@@ -0,0 +1,180 @@
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use alloy::primitives::{keccak256, B256, U256};
+use alloy::sol_types::SolValue;
+use hkdf::Hkdf;
+use k256::ecdh::{EphemeralSecret, SharedSecret};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::sha2::Sha256;
+use k256::{PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+
+/// HKDF info string; bump it with any change to the sealing scheme.
+const SEAL_INFO: &[u8] = b"cabalmesh-sealed-intent-v1";
+/// HKDF info string for intent keys; bumping it gives every identity a new
+/// intent key.
+const INTENT_KEY_INFO: &[u8] = b"cabalmesh-intent-key-v1";
+const NONCE_BYTES: usize = 12;
+
+/// What a sealed intent opens to: the full intent a bid is for, the bid,
+/// and the salt that keeps the commitment from being brute-forced from a
+/// guessable intent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntentOpening {
+    pub intent: String,
+    pub bid_amount: u64,
+    pub salt: B256,
+}
+
+impl IntentOpening {
+    pub fn new(intent: &str, bid_amount: u64) -> Self {
+        let mut salt = B256::ZERO;
+        OsRng.fill_bytes(salt.as_mut_slice());
+        IntentOpening { intent: intent.to_string(), bid_amount, salt }
+    }
+
+    /// keccak256 of the ABI-encoded opening with the top byte cleared, so it
+    /// fits in a BN254 field element. A proof's public inputs carry it.
+    pub fn commitment(&self) -> U256 {
+        let mut hash = keccak256((self.intent.clone(), self.bid_amount, self.salt).abi_encode()).0;
+        hash[0] = 0;
+        U256::from_be_bytes(hash)
+    }
+
+    /// Encrypts the opening so only the holder of `recipient`'s secret key
+    /// can read it: ECDH with a one-time key, HKDF-SHA256, AES-256-GCM.
+    pub fn seal(&self, recipient: &PublicKey) -> AppResult<SealedIntent> {
+        let ephemeral = EphemeralSecret::random(&mut OsRng);
+        let ephemeral_key = ephemeral.public_key();
+        let cipher = Aes256Gcm::new(&derive_key(&ephemeral.diffie_hellman(recipient), &ephemeral_key, recipient)?);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, serde_json::to_vec(self)?.as_ref())
+            .map_err(|_| AppError::crypto("encryption failed"))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(SealedIntent {
+            recipient: encode_public_key(recipient),
+            ephemeral_key: encode_public_key(&ephemeral_key),
+            ciphertext: hex::encode(sealed),
+        })
+    }
+}
+
+/// An `IntentOpening` encrypted to one public key: a counterparty's intent
+/// key or the configured committee key. Everyone else sees only who it's
+/// for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedIntent {
+    /// Compressed secp256k1 public key it's sealed to, hex.
+    pub recipient: String,
+    /// The sender's one-time public key for the key agreement, hex.
+    pub ephemeral_key: String,
+    /// AES-GCM nonce followed by the ciphertext, hex.
+    pub ciphertext: String,
+}
+
+impl SealedIntent {
+    /// Decrypts with the recipient's secret key. Checking the opening
+    /// against a proof's commitment is up to the caller.
+    pub fn open(&self, secret: &SecretKey) -> AppResult<IntentOpening> {
+        let recipient = secret.public_key();
+        if parse_public_key(&self.recipient)? != recipient {
+            return Err(AppError::crypto("intent is sealed to a different key"));
+        }
+        let ephemeral_key = parse_public_key(&self.ephemeral_key)?;
+        let shared = k256::ecdh::diffie_hellman(secret.to_nonzero_scalar(), ephemeral_key.as_affine());
+        let cipher = Aes256Gcm::new(&derive_key(&shared, &ephemeral_key, &recipient)?);
+
+        let sealed = hex::decode(self.ciphertext.trim_start_matches("0x")).map_err(AppError::invalid_input)?;
+        if sealed.len() < NONCE_BYTES {
+            return Err(AppError::invalid_input("sealed intent is truncated"));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_BYTES);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| AppError::crypto("decryption failed - not sealed to this key or corrupted"))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+/// Binds the key to both public keys, so a ciphertext can't be re-aimed at
+/// another recipient.
+fn derive_key(shared: &SharedSecret, ephemeral_key: &PublicKey, recipient: &PublicKey) -> AppResult<Key<Aes256Gcm>> {
+    let salt = [encoded(ephemeral_key), encoded(recipient)].concat();
+    let mut key = [0u8; 32];
+    shared
+        .extract::<Sha256>(Some(&salt))
+        .expand(SEAL_INFO, &mut key)
+        .map_err(AppError::crypto)?;
+    Ok(key.into())
+}
+
+fn encoded(key: &PublicKey) -> Vec<u8> {
+    key.to_encoded_point(true).as_bytes().to_vec()
+}
+
+/// The secret intent key of a wallet: HKDF-SHA256 of its signing key, so
+/// the key used for ECDH is never the one that signs transactions.
+pub fn intent_secret_key(wallet_key: &SecretKey) -> AppResult<SecretKey> {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, &wallet_key.to_bytes())
+        .expand(INTENT_KEY_INFO, &mut key)
+        .map_err(AppError::crypto)?;
+    SecretKey::from_slice(&key).map_err(|_| AppError::crypto("derived intent key is out of range"))
+}
+
+/// Hex of the compressed SEC1 encoding, as shared with counterparties.
+pub fn encode_public_key(key: &PublicKey) -> String {
+    format!("0x{}", hex::encode(encoded(key)))
+}
+
+/// Accepts compressed or uncompressed SEC1 hex, with or without `0x`.
+pub fn parse_public_key(hex_key: &str) -> AppResult<PublicKey> {
+    let bytes = hex::decode(hex_key.trim().trim_start_matches("0x")).map_err(AppError::invalid_input)?;
+    PublicKey::from_sec1_bytes(&bytes).map_err(|_| AppError::invalid_input("not a secp256k1 public key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Only the recipient can open a sealed intent, and tampering with any
+    /// part of it makes opening fail rather than return something else.
+    #[test]
+    fn seals_to_the_recipient_only() {
+        let recipient = SecretKey::random(&mut OsRng);
+        let opening = IntentOpening::new(r#"{"want":"lamp"}"#, 50);
+        let sealed = opening.seal(&recipient.public_key()).unwrap();
+        assert_eq!(parse_public_key(&sealed.recipient).unwrap(), recipient.public_key());
+        assert!(!sealed.ciphertext.contains(&hex::encode("lamp")));
+
+        let opened = sealed.open(&recipient).unwrap();
+        assert_eq!(opened, opening);
+        assert_eq!(opened.commitment(), opening.commitment());
+        assert_ne!(IntentOpening::new(&opening.intent, 50).commitment(), opening.commitment());
+
+        assert!(sealed.open(&SecretKey::random(&mut OsRng)).is_err());
+        let redirected = SealedIntent { recipient: encode_public_key(&SecretKey::random(&mut OsRng).public_key()), ..sealed.clone() };
+        assert!(redirected.open(&recipient).is_err());
+        let mut tampered = hex::decode(&sealed.ciphertext).unwrap();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(SealedIntent { ciphertext: hex::encode(tampered), ..sealed }.open(&recipient).is_err());
+    }
+
+    /// A wallet's intent key is stable, and is neither its signing key nor
+    /// another wallet's intent key.
+    #[test]
+    fn intent_keys_are_derived_apart_from_wallet_keys() {
+        let wallet = SecretKey::random(&mut OsRng);
+        let intent_key = intent_secret_key(&wallet).unwrap();
+        assert_eq!(intent_key, intent_secret_key(&wallet).unwrap());
+        assert_ne!(intent_key.public_key(), wallet.public_key());
+        assert_ne!(intent_key, intent_secret_key(&SecretKey::random(&mut OsRng)).unwrap());
+    }
+}
@@ -0,0 +1,440 @@
+use alloy::primitives::{utils::parse_ether, Address};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{PoisonError, RwLock};
+
+use crate::error::{AppError, AppResult};
+use crate::proving_backend::ProvingBackendKind;
+use crate::sealed_intent::parse_public_key;
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OLLAMA_MODEL: &str = "llama2";
+pub const DEFAULT_MESH_TOPIC: &str = "cabalmesh-privacy-intents";
+pub const DEFAULT_MESH_LISTEN_ADDR: &str = "/ip4/0.0.0.0/tcp/0";
+pub const DEFAULT_SESSION_EXPIRY_SECS: u64 = 60 * 60;
+pub const DEFAULT_RELAY_FEE_AVAX: &str = "0.005";
+pub const DEFAULT_CONTROL_API_PORT: u16 = 7421;
+pub const DEFAULT_REFRESH_BALANCE_SECS: u64 = 60;
+pub const DEFAULT_REFRESH_LISTINGS_SECS: u64 = 30;
+pub const DEFAULT_REFRESH_DEALS_SECS: u64 = 60;
+pub const DEFAULT_PROOF_WORKERS: usize = 2;
+
+const MIN_SESSION_EXPIRY_SECS: u64 = 60;
+const MAX_SESSION_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
+/// Shortest background refresh interval, to keep public RPCs from rate-limiting us.
+const MIN_REFRESH_SECS: u64 = 10;
+/// Proofs are CPU-bound; more workers than cores only adds memory pressure.
+const MAX_PROOF_WORKERS: usize = 16;
+
+/// Settings that only take effect when the app is restarted: the RPC pool
+/// and contract addresses are baked into the bridge when it's constructed,
+/// the control API is only started at launch, and the proof worker pool is
+/// sized once.
+const RESTART_REQUIRED: &[&str] = &[
+    "rpc_urls",
+    "escrow_contract_address",
+    "marketplace_contract_address",
+    "voucher_contract_address",
+    "control_api_enabled",
+    "control_api_port",
+    "demo_chain",
+    "proof_workers",
+];
+
+/// The agents that call a model, each configurable on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelRole {
+    Negotiation,
+    Matching,
+}
+
+impl ModelRole {
+    pub const ALL: [ModelRole; 2] = [ModelRole::Negotiation, ModelRole::Matching];
+
+    /// The settings field naming this role's model.
+    pub fn model_field(self) -> &'static str {
+        match self {
+            ModelRole::Negotiation => "negotiation_model",
+            ModelRole::Matching => "matching_model",
+        }
+    }
+}
+
+/// Where an agent's generate requests go.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModelEndpoint {
+    pub url: String,
+    pub model: String,
+}
+
+/// User-editable node configuration, persisted as `settings.json` in the app
+/// data dir. Missing fields take their defaults, so older files keep loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Avalanche C-Chain RPC endpoints, in failover order. Empty means the
+    /// public Fuji endpoint.
+    pub rpc_urls: Vec<String>,
+    pub escrow_contract_address: Option<String>,
+    pub marketplace_contract_address: Option<String>,
+    pub voucher_contract_address: Option<String>,
+    /// The managed local Ollama server, and the model agents use unless
+    /// they have their own.
+    pub ollama_url: String,
+    pub ollama_model: String,
+    /// Model and endpoint for the shark agent's negotiation; unset means
+    /// `ollama_model` at `ollama_url`.
+    pub negotiation_model: Option<String>,
+    pub negotiation_url: Option<String>,
+    /// Same for listing matching, which suits a small fast model.
+    pub matching_model: Option<String>,
+    pub matching_url: Option<String>,
+    pub mesh_topic: String,
+    pub mesh_listen_addr: String,
+    pub session_expiry_secs: u64,
+    /// Fee offered to relayers for intents we broadcast, in AVAX.
+    pub relay_fee_avax: String,
+    /// Serve the localhost JSON-RPC control API (off unless opted in).
+    pub control_api_enabled: bool,
+    pub control_api_port: u16,
+    /// Background refresh intervals while online, in seconds; 0 disables.
+    /// The balance refresh also renews the offline-signing chain cache.
+    pub refresh_balance_secs: u64,
+    pub refresh_listings_secs: u64,
+    pub refresh_deals_secs: u64,
+    /// Addresses whose signed catalogue snapshots are cached from the mesh
+    /// for offline matching. Snapshots from anyone else are refused; empty
+    /// accepts none.
+    pub catalogue_peers: Vec<String>,
+    /// Run against an in-memory simulated chain instead of `rpc_urls`, with
+    /// every account funded and the contract addresses ignored. Nothing
+    /// persists across restarts; use a separate profile for it.
+    pub demo_chain: bool,
+    /// How bid proofs are made: `bulletproofs` (native, no setup), `nargo`
+    /// (the Noir circuit; needs `nargo` and `bb`) or `mock` (tests only).
+    pub proving_backend: ProvingBackendKind,
+    /// Public key (secp256k1, hex) that bid proofs' intents are sealed to
+    /// when the caller doesn't name a counterparty key, e.g. a committee's.
+    pub intent_committee_key: Option<String>,
+    /// How many bid proofs are generated at once; further jobs queue.
+    pub proof_workers: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            rpc_urls: Vec::new(),
+            escrow_contract_address: None,
+            marketplace_contract_address: None,
+            voucher_contract_address: None,
+            ollama_url: DEFAULT_OLLAMA_URL.to_string(),
+            ollama_model: DEFAULT_OLLAMA_MODEL.to_string(),
+            negotiation_model: None,
+            negotiation_url: None,
+            matching_model: None,
+            matching_url: None,
+            mesh_topic: DEFAULT_MESH_TOPIC.to_string(),
+            mesh_listen_addr: DEFAULT_MESH_LISTEN_ADDR.to_string(),
+            session_expiry_secs: DEFAULT_SESSION_EXPIRY_SECS,
+            relay_fee_avax: DEFAULT_RELAY_FEE_AVAX.to_string(),
+            control_api_enabled: false,
+            control_api_port: DEFAULT_CONTROL_API_PORT,
+            refresh_balance_secs: DEFAULT_REFRESH_BALANCE_SECS,
+            refresh_listings_secs: DEFAULT_REFRESH_LISTINGS_SECS,
+            refresh_deals_secs: DEFAULT_REFRESH_DEALS_SECS,
+            catalogue_peers: Vec::new(),
+            demo_chain: false,
+            proving_backend: ProvingBackendKind::default(),
+            intent_committee_key: None,
+            proof_workers: DEFAULT_PROOF_WORKERS,
+        }
+    }
+}
+
+impl Settings {
+    /// Defaults overlaid with the legacy `.env` variables: `AVAX_RPC_URL`
+    /// first, then any extra comma-separated `AVAX_RPC_URLS`, and the three
+    /// `*_CONTRACT_ADDRESS` values. Only used to seed a missing settings file.
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let mut rpc_urls: Vec<String> = var("AVAX_RPC_URL").into_iter().collect();
+        if let Some(extra) = var("AVAX_RPC_URLS") {
+            rpc_urls.extend(extra.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()));
+        }
+
+        Settings {
+            rpc_urls,
+            escrow_contract_address: var("ESCROW_CONTRACT_ADDRESS"),
+            marketplace_contract_address: var("MARKETPLACE_CONTRACT_ADDRESS"),
+            voucher_contract_address: var("VOUCHER_CONTRACT_ADDRESS"),
+            ..Self::default()
+        }
+    }
+
+    pub fn validate(&self) -> AppResult<()> {
+        for url in &self.rpc_urls {
+            check_http_url("rpc_urls", url)?;
+        }
+        for (field, address) in [
+            ("escrow_contract_address", &self.escrow_contract_address),
+            ("marketplace_contract_address", &self.marketplace_contract_address),
+            ("voucher_contract_address", &self.voucher_contract_address),
+        ] {
+            if let Some(address) = address {
+                Address::from_str(address)
+                    .map_err(|e| AppError::InvalidInput(format!("{}: {}", field, e)))?;
+            }
+        }
+        for peer in &self.catalogue_peers {
+            Address::from_str(peer).map_err(|e| AppError::InvalidInput(format!("catalogue_peers: {}: {}", peer, e)))?;
+        }
+        if let Some(key) = &self.intent_committee_key {
+            parse_public_key(key).map_err(|e| AppError::InvalidInput(format!("intent_committee_key: {}", e)))?;
+        }
+        if !(1..=MAX_PROOF_WORKERS).contains(&self.proof_workers) {
+            return Err(AppError::InvalidInput(format!("proof_workers must be between 1 and {}", MAX_PROOF_WORKERS)));
+        }
+        check_http_url("ollama_url", &self.ollama_url)?;
+        for (field, model) in [
+            ("ollama_model", Some(&self.ollama_model)),
+            ("negotiation_model", self.negotiation_model.as_ref()),
+            ("matching_model", self.matching_model.as_ref()),
+        ] {
+            if model.is_some_and(|model| model.trim().is_empty()) {
+                return Err(AppError::InvalidInput(format!("{} must not be empty", field)));
+            }
+        }
+        for (field, url) in [("negotiation_url", &self.negotiation_url), ("matching_url", &self.matching_url)] {
+            if let Some(url) = url {
+                check_http_url(field, url)?;
+            }
+        }
+        if self.mesh_topic.trim().is_empty() {
+            return Err(AppError::InvalidInput("mesh_topic must not be empty".to_string()));
+        }
+        self.mesh_listen_addr
+            .parse::<libp2p::Multiaddr>()
+            .map_err(|e| AppError::InvalidInput(format!("mesh_listen_addr: {}", e)))?;
+        if !(MIN_SESSION_EXPIRY_SECS..=MAX_SESSION_EXPIRY_SECS).contains(&self.session_expiry_secs) {
+            return Err(AppError::InvalidInput(format!(
+                "session_expiry_secs must be between {} and {}",
+                MIN_SESSION_EXPIRY_SECS, MAX_SESSION_EXPIRY_SECS
+            )));
+        }
+        parse_ether(&self.relay_fee_avax)
+            .map_err(|e| AppError::InvalidInput(format!("relay_fee_avax: {}", e)))?;
+        if self.control_api_port == 0 {
+            return Err(AppError::InvalidInput("control_api_port must not be 0".to_string()));
+        }
+        for (field, secs) in [
+            ("refresh_balance_secs", self.refresh_balance_secs),
+            ("refresh_listings_secs", self.refresh_listings_secs),
+            ("refresh_deals_secs", self.refresh_deals_secs),
+        ] {
+            if secs != 0 && secs < MIN_REFRESH_SECS {
+                return Err(AppError::InvalidInput(format!(
+                    "{} must be 0 (off) or at least {}",
+                    field, MIN_REFRESH_SECS
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn escrow_address(&self) -> Option<Address> {
+        parse_address(&self.escrow_contract_address)
+    }
+
+    pub fn marketplace_address(&self) -> Option<Address> {
+        parse_address(&self.marketplace_contract_address)
+    }
+
+    pub fn voucher_address(&self) -> Option<Address> {
+        parse_address(&self.voucher_contract_address)
+    }
+
+    pub fn catalogue_peers(&self) -> Vec<Address> {
+        self.catalogue_peers.iter().filter_map(|peer| Address::from_str(peer).ok()).collect()
+    }
+
+    pub fn session_expiry(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.session_expiry_secs as i64)
+    }
+
+    /// The model `role` runs on and the Ollama server that hosts it.
+    pub fn model_endpoint(&self, role: ModelRole) -> ModelEndpoint {
+        let (model, url) = match role {
+            ModelRole::Negotiation => (&self.negotiation_model, &self.negotiation_url),
+            ModelRole::Matching => (&self.matching_model, &self.matching_url),
+        };
+        ModelEndpoint {
+            url: url.clone().unwrap_or_else(|| self.ollama_url.clone()),
+            model: model.clone().unwrap_or_else(|| self.ollama_model.clone()),
+        }
+    }
+
+    /// Names of the top-level fields that differ between `self` and `other`.
+    fn changed_fields(&self, other: &Settings) -> Vec<String> {
+        let (serde_json::Value::Object(a), serde_json::Value::Object(b)) =
+            (serde_json::json!(self), serde_json::json!(other))
+        else {
+            return Vec::new();
+        };
+        a.into_iter().filter(|(k, v)| b.get(k) != Some(v)).map(|(k, _)| k).collect()
+    }
+}
+
+fn parse_address(address: &Option<String>) -> Option<Address> {
+    address.as_deref().and_then(|s| Address::from_str(s).ok())
+}
+
+fn check_http_url(field: &str, url: &str) -> AppResult<()> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        Ok(_) => Err(AppError::InvalidInput(format!("{}: {} is not an http(s) URL", field, url))),
+        Err(e) => Err(AppError::InvalidInput(format!("{}: {}: {}", field, url, e))),
+    }
+}
+
+/// What `update_settings` did with each changed field.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsUpdate {
+    pub settings: Settings,
+    /// Changed fields already in effect.
+    pub applied: Vec<String>,
+    /// Changed fields that are saved but only take effect after a restart.
+    pub requires_restart: Vec<String>,
+}
+
+/// The live settings plus the file they're persisted to. Readers always get
+/// a snapshot; subsystems that support live reload read it per call.
+pub struct SettingsStore {
+    path: PathBuf,
+    current: RwLock<Settings>,
+}
+
+impl SettingsStore {
+    /// Loads `settings.json` from `app_dir`. A missing file is seeded from
+    /// the environment and written out; an unreadable or invalid one is left
+    /// untouched for the user to fix, and the environment defaults are used.
+    pub fn load(app_dir: &Path) -> Self {
+        let path = app_dir.join("settings.json");
+        let settings = match fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str::<Settings>(&raw).map_err(AppError::from).and_then(|s| {
+                s.validate()?;
+                Ok(s)
+            }) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "ignoring invalid settings file");
+                    Settings::from_env()
+                }
+            },
+            Err(_) => {
+                let settings = Settings::from_env();
+                if let Err(e) = write_settings(&path, &settings) {
+                    tracing::warn!(error = %e, "failed to write default settings");
+                }
+                settings
+            }
+        };
+        SettingsStore { path, current: RwLock::new(settings) }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.current.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Validates and persists `settings`, then makes them current. Returns
+    /// the changed fields split by whether they apply live.
+    pub fn update(&self, settings: Settings) -> AppResult<SettingsUpdate> {
+        settings.validate()?;
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        write_settings(&self.path, &settings)?;
+        let (requires_restart, applied) = current
+            .changed_fields(&settings)
+            .into_iter()
+            .partition(|field| RESTART_REQUIRED.contains(&field.as_str()));
+        *current = settings.clone();
+        Ok(SettingsUpdate { settings, applied, requires_restart })
+    }
+}
+
+/// Write-then-rename so a crash mid-write never leaves a truncated file.
+fn write_settings(path: &Path, settings: &Settings) -> AppResult<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(settings)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_persists_and_classifies_changes() {
+        let dir = std::env::temp_dir().join(format!("cabalmesh_settings_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        // Partial files fill in defaults.
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("settings.json"), r#"{ "ollama_model": "mistral" }"#).unwrap();
+        let store = SettingsStore::load(&dir);
+        assert_eq!(store.get().ollama_model, "mistral");
+        assert_eq!(store.get().mesh_topic, DEFAULT_MESH_TOPIC);
+
+        let bad = Settings { mesh_listen_addr: "not a multiaddr".to_string(), ..store.get() };
+        assert_eq!(store.update(bad).unwrap_err().code(), "invalid_input");
+        let bad = Settings { escrow_contract_address: Some("0x1234".to_string()), ..store.get() };
+        assert!(store.update(bad).is_err());
+        let bad = Settings { session_expiry_secs: 5, ..store.get() };
+        assert!(store.update(bad).is_err());
+        let bad = Settings { refresh_listings_secs: 1, ..store.get() };
+        assert!(store.update(bad).is_err());
+        let bad = Settings { matching_url: Some("localhost:11434".to_string()), ..store.get() };
+        assert!(store.update(bad).is_err());
+        let bad = Settings { negotiation_model: Some(" ".to_string()), ..store.get() };
+        assert!(store.update(bad).is_err());
+        let bad = Settings { catalogue_peers: vec!["peer-1".to_string()], ..store.get() };
+        assert!(store.update(bad).is_err());
+
+        // Agents without their own model fall back to `ollama_model`.
+        let split = Settings {
+            matching_model: Some("llama3.2:1b".to_string()),
+            matching_url: Some("http://10.0.0.2:11434".to_string()),
+            ..store.get()
+        };
+        assert_eq!(
+            split.model_endpoint(ModelRole::Matching),
+            ModelEndpoint { url: "http://10.0.0.2:11434".to_string(), model: "llama3.2:1b".to_string() }
+        );
+        assert_eq!(
+            split.model_endpoint(ModelRole::Negotiation),
+            ModelEndpoint { url: DEFAULT_OLLAMA_URL.to_string(), model: "mistral".to_string() }
+        );
+
+        let update = store
+            .update(Settings {
+                relay_fee_avax: "0.01".to_string(),
+                rpc_urls: vec!["https://rpc.example.org".to_string()],
+                ..store.get()
+            })
+            .unwrap();
+        assert_eq!(update.applied, vec!["relay_fee_avax"]);
+        assert_eq!(update.requires_restart, vec!["rpc_urls"]);
+
+        // Survives a reload from disk.
+        let reloaded = SettingsStore::load(&dir);
+        assert_eq!(reloaded.get(), store.get());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,110 +1,633 @@
+use alloy::primitives::{keccak256, Address, Signature, B256, U256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
-use std::process::Command;
+use std::str::FromStr;
+use std::sync::Arc;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::blockchain_bridge::BlockchainBridge;
+use crate::chain_backend::AccountProof;
+use crate::circuit_registry::{circuit_id, CircuitIntegrity, CircuitRecord, CircuitRegistry};
+use crate::error::{AppError, AppResult};
+use crate::proving_backend::{
+    BulletproofsBackend, MockBackend, NargoBackend, ProvingBackend, ProvingBackendKind,
+};
+use crate::sealed_intent::{parse_public_key, IntentOpening, SealedIntent};
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ZKProof {
     pub proof: String,
     pub public_inputs: Vec<String>,
-    pub encrypted_intent: String,
+    /// The full intent and bid, sealed to the counterparty or committee
+    /// key; the proof's public inputs carry its commitment.
+    pub encrypted_intent: SealedIntent,
+    /// Which backend made the proof, and so which one can check it.
+    #[serde(default = "ProvingBackendKind::legacy")]
+    pub backend: ProvingBackendKind,
+    /// Registry id of the circuit version it was made with. Proofs from
+    /// before circuits were versioned have none, and don't verify.
+    #[serde(default)]
+    pub circuit_id: String,
+}
+
+/// How long a bid proof stays valid for receivers.
+pub const PROOF_TTL_SECS: u64 = 10 * 60;
+
+/// How far a prover's clock may run ahead of ours. A proof expiring more
+/// than `PROOF_TTL_SECS` plus this from now is rejected, so the expiry can't
+/// be pushed out to keep a proof replayable for longer.
+const EXPIRY_CLOCK_SKEW_SECS: u64 = 5 * 60;
+
+/// How far behind the chain head, by block time, a proof's anchor block may
+/// be when a receiver checks it.
+pub const ANCHOR_MAX_AGE_SECS: u64 = 30 * 60;
+
+/// Bids, price ceilings and the proven balance are in nAVAX (gwei), so a
+/// `u64` holds any real balance.
+pub const AMOUNT_UNIT_WEI: u64 = 1_000_000_000;
+
+/// A wei balance in nAVAX, as proofs carry it.
+fn balance_units(wei: U256) -> u64 {
+    u64::try_from(wei / U256::from(AMOUNT_UNIT_WEI)).unwrap_or(u64::MAX)
+}
+
+/// What ties a proof to one intent from one bidder, so it can't be lifted
+/// onto another intent, by another peer, or replayed later. All four are
+/// public inputs of the proof.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofBinding {
+    /// `intent_hash` of the intent payload the bid is for.
+    pub intent_hash: U256,
+    /// `IntentOpening::commitment` of the sealed intent sent with the proof.
+    pub commitment: U256,
+    /// Unix time after which receivers reject the proof.
+    pub expiry: u64,
+    /// The bidder's EVM address, which must sign the `BidProof`.
+    pub prover: Address,
+}
+
+impl ProofBinding {
+    pub fn new(opening: &IntentOpening, prover: Address) -> Self {
+        ProofBinding {
+            intent_hash: intent_hash(&opening.intent),
+            commitment: opening.commitment(),
+            expiry: Utc::now().timestamp() as u64 + PROOF_TTL_SECS,
+            prover,
+        }
+    }
+}
+
+/// keccak256 of an intent payload with the top byte cleared, so it fits in
+/// a BN254 field element.
+pub fn intent_hash(intent_payload: &str) -> U256 {
+    let mut hash = keccak256(intent_payload).0;
+    hash[0] = 0;
+    U256::from_be_bytes(hash)
+}
+
+/// The anchor block hash as two 128-bit halves, high first, since a whole
+/// hash doesn't fit in a BN254 field element.
+fn anchor_fields(block_hash: B256) -> [String; 2] {
+    [
+        U256::from_be_slice(&block_hash[..16]).to_string(),
+        U256::from_be_slice(&block_hash[16..]).to_string(),
+    ]
+}
+
+fn anchor_from_fields(hi: &str, lo: &str) -> Option<B256> {
+    let (hi, lo) = (hi.parse::<u128>().ok()?, lo.parse::<u128>().ok()?);
+    Some(B256::from_slice(&[hi.to_be_bytes(), lo.to_be_bytes()].concat()))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProofRequest {
-    pub balance: u64,        // private
+    pub balance: u64,        // public: the prover's balance at `anchor`
     pub bid_amount: u64,     // public
     pub price_ceiling: u64,  // private
+    pub binding: ProofBinding, // public
+    pub anchor: B256,        // public: block the balance was proven at
 }
 
+impl ProofRequest {
+    /// A request for the balance `anchor` proves on-chain.
+    pub fn anchored(anchor: &AccountProof, bid_amount: u64, price_ceiling: u64, binding: ProofBinding) -> Self {
+        ProofRequest {
+            balance: balance_units(anchor.balance),
+            bid_amount,
+            price_ceiling,
+            binding,
+            anchor: anchor.block_hash,
+        }
+    }
+
+    /// The circuit's public inputs, in order: bid, intent hash, expiry,
+    /// prover address, sealed-intent commitment, the anchor block hash's
+    /// two halves and the prover's balance at that block, each as a decimal
+    /// field element.
+    pub fn public_inputs(&self) -> [String; 8] {
+        let [anchor_hi, anchor_lo] = anchor_fields(self.anchor);
+        [
+            self.bid_amount.to_string(),
+            self.binding.intent_hash.to_string(),
+            self.binding.expiry.to_string(),
+            U256::from_be_slice(self.binding.prover.as_slice()).to_string(),
+            self.binding.commitment.to_string(),
+            anchor_hi,
+            anchor_lo,
+            self.balance.to_string(),
+        ]
+    }
+}
+
+/// A bid proof as attached to a mesh trade intent: the bid the sender
+/// claims, the proof that is supposed to back it, and an EIP-191 signature
+/// over both by the address the proof commits to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BidProof {
+    pub bid_amount: u64,
+    pub proof: ZKProof,
+    pub signature: String,
+}
+
+impl BidProof {
+    fn digest(bid_amount: u64, proof: &ZKProof) -> AppResult<[u8; 32]> {
+        Ok(keccak256(serde_json::to_vec(&(bid_amount, proof))?).0)
+    }
+
+    pub fn sign(bid_amount: u64, proof: ZKProof, signer: &PrivateKeySigner) -> AppResult<Self> {
+        let signature = signer.sign_message_sync(&Self::digest(bid_amount, &proof)?).map_err(AppError::crypto)?;
+        Ok(BidProof { bid_amount, proof, signature: signature.to_string() })
+    }
+
+    fn signer(&self) -> AppResult<Address> {
+        let signature = Signature::from_str(&self.signature).map_err(AppError::invalid_input)?;
+        signature
+            .recover_address_from_msg(Self::digest(self.bid_amount, &self.proof)?)
+            .map_err(AppError::invalid_input)
+    }
+
+    /// The block the proven balance was read at, from the public inputs.
+    pub fn anchor(&self) -> Option<B256> {
+        match self.proof.public_inputs.get(5..7)? {
+            [hi, lo] => anchor_from_fields(hi, lo),
+            _ => None,
+        }
+    }
+
+    fn public_input<T: FromStr>(&self, index: usize) -> Option<T> {
+        self.proof.public_inputs.get(index).and_then(|input| input.parse().ok())
+    }
+
+    /// The public inputs this bid proof must have to count for an intent
+    /// with `intent_payload`: everything is known to the receiver except the
+    /// expiry, commitment, anchor and balance, which are taken from the
+    /// proof and checked separately.
+    fn expected_public_inputs(&self, intent_payload: &str) -> AppResult<Option<[String; 8]>> {
+        let (Some(expiry), Some(commitment), Some(anchor), Some(balance)) =
+            (self.public_input(2), self.public_input(4), self.anchor(), self.public_input(7))
+        else {
+            return Ok(None);
+        };
+        let request = ProofRequest {
+            balance,
+            bid_amount: self.bid_amount,
+            price_ceiling: 0,
+            binding: ProofBinding { intent_hash: intent_hash(intent_payload), commitment, expiry, prover: self.signer()? },
+            anchor,
+        };
+        Ok(Some(request.public_inputs()))
+    }
+}
+
+/// Makes bid proofs with the backend picked by the `proving_backend`
+/// setting (read per call, so a change applies immediately), and checks
+/// received proofs with whichever backend made them, against the
+/// verification key pinned for their circuit, and their anchor blocks and
+/// balances against the chain.
 pub struct ZKHandler {
-    circuit_path: String,
+    settings: Arc<SettingsStore>,
+    bridge: Arc<BlockchainBridge>,
+    circuits: CircuitRegistry,
+    nargo: NargoBackend,
+    bulletproofs: BulletproofsBackend,
+    mock: MockBackend,
 }
 
 impl ZKHandler {
-    pub fn new(circuit_path: Option<String>) -> Self {
+    pub fn new(settings: Arc<SettingsStore>, bridge: Arc<BlockchainBridge>, circuits: CircuitRegistry) -> Self {
         ZKHandler {
-            circuit_path: circuit_path.unwrap_or_else(|| "./noir-circuit".to_string()),
+            settings,
+            bridge,
+            circuits,
+            nargo: NargoBackend::new(None),
+            bulletproofs: BulletproofsBackend,
+            mock: MockBackend,
+        }
+    }
+
+    /// The backend new proofs are made with.
+    pub fn proving_backend(&self) -> ProvingBackendKind {
+        self.settings.get().proving_backend
+    }
+
+    /// What `kind`'s proofs show; see `ProvingBackend::circuit_hash`.
+    pub fn circuit_hash(&self, kind: ProvingBackendKind) -> AppResult<B256> {
+        self.backend(kind).circuit_hash()
+    }
+
+    /// The registered circuit `kind` proves with now, compiling and
+    /// registering it first if this version is new.
+    pub async fn current_circuit(&self, kind: ProvingBackendKind) -> AppResult<CircuitRecord> {
+        let backend = self.backend(kind);
+        match self.circuits.get(&circuit_id(backend.circuit_name(), backend.circuit_hash()?)) {
+            Some(record) => Ok(record),
+            None => self.compile_circuit(kind, false).await,
+        }
+    }
+
+    /// Compiles `kind`'s current circuit and registers it. The result must
+    /// match a verification key already pinned for it, unless `repin`.
+    pub async fn compile_circuit(&self, kind: ProvingBackendKind, repin: bool) -> AppResult<CircuitRecord> {
+        let backend = self.backend(kind);
+        let source_hash = backend.circuit_hash()?;
+        let compiled = backend.compile().await?;
+        self.circuits.register(backend.circuit_name(), kind, source_hash, compiled, repin)
+    }
+
+    pub fn list_circuits(&self) -> Vec<CircuitRecord> {
+        self.circuits.list()
+    }
+
+    /// Rehashes one registered circuit's stored files, or every circuit's.
+    pub fn check_circuit_integrity(&self, id: Option<&str>) -> AppResult<Vec<CircuitIntegrity>> {
+        let records = match id {
+            Some(id) => vec![self.circuits.get(id).ok_or_else(|| AppError::NotFound(format!("circuit {}", id)))?],
+            None => self.circuits.list(),
+        };
+        Ok(records
+            .iter()
+            .map(|record| CircuitIntegrity {
+                source_current: self.backend(record.backend).circuit_hash().ok() == Some(record.source_hash),
+                ..self.circuits.check(record)
+            })
+            .collect())
+    }
+
+    /// The registered circuit a received proof names, if we know it. Our
+    /// own current circuit counts as known even before it's compiled here.
+    async fn known_circuit(&self, proof: &ZKProof) -> AppResult<Option<CircuitRecord>> {
+        if let Some(record) = self.circuits.get(&proof.circuit_id) {
+            return Ok(Some(record));
+        }
+        let backend = self.backend(proof.backend);
+        if proof.circuit_id != circuit_id(backend.circuit_name(), backend.circuit_hash()?) {
+            return Ok(None);
+        }
+        self.current_circuit(proof.backend).await.map(Some)
+    }
+
+    fn backend(&self, kind: ProvingBackendKind) -> &dyn ProvingBackend {
+        match kind {
+            ProvingBackendKind::Nargo => &self.nargo,
+            ProvingBackendKind::Bulletproofs => &self.bulletproofs,
+            ProvingBackendKind::Mock => &self.mock,
         }
     }
 
     /// Generate a zero-knowledge proof that:
     /// 1. balance >= bid_amount
     /// 2. bid_amount <= price_ceiling
-    pub async fn generate_proof(&self, request: ProofRequest) -> Result<ZKProof, Box<dyn Error>> {
-        println!("🔐 Generating Noir ZK-Proof...");
-        println!("   Balance (private): {}", request.balance);
-        println!("   Bid Amount (public): {}", request.bid_amount);
-        println!("   Price Ceiling (private): {}", request.price_ceiling);
+    ///
+    /// Only the public bid is recorded on the span; the price ceiling is a
+    /// private witness and is never logged.
+    #[tracing::instrument(skip_all, fields(bid_amount = request.bid_amount), err)]
+    pub async fn generate_proof(&self, request: ProofRequest, sealed: SealedIntent) -> AppResult<ZKProof> {
+        let backend = self.backend(self.proving_backend());
+        tracing::info!(backend = %backend.kind(), "generating ZK proof");
 
         // Verify locally before generating proof
         if request.balance < request.bid_amount {
-            return Err("Insufficient balance".into());
+            return Err(AppError::InsufficientFunds("balance is below the bid amount".to_string()));
         }
 
         if request.bid_amount > request.price_ceiling {
-            return Err("Bid exceeds price ceiling".into());
-        }
-
-        // Execute Noir build command in blocking task to avoid freezing async runtime
-        // Note: This requires nargo (Noir compiler) to be installed
-        let circuit_path = self.circuit_path.clone();
-        
-        let output_result = tokio::task::spawn_blocking(move || {
-            Command::new("nargo")
-                .args(&["prove", &circuit_path])
-                .output()
-        }).await?;
-
-        match output_result {
-            Ok(result) if result.status.success() => {
-                // Read the generated proof
-                let proof_data = std::fs::read_to_string(format!("{}/proofs/proof.hex", self.circuit_path))?;
-
-                Ok(ZKProof {
-                    proof: proof_data,
-                    public_inputs: vec![request.bid_amount.to_string()],
-                    encrypted_intent: format!(
-                        "{{\"bid\":{},\"verified\":true}}",
-                        request.bid_amount
-                    ),
-                })
-            }
-            _ => {
+            return Err(AppError::InvalidInput("bid exceeds price ceiling".to_string()));
+        }
+
+        if request.bid_amount == 0 {
+            return Err(AppError::InvalidInput("bid must be positive".to_string()));
+        }
 
-                let error_msg = "❌ Noir ZK Proof Generation Failed! Ensure 'nargo' is installed and circuit is compiled.";
-                eprintln!("{}", error_msg);
-                Err(error_msg.into())
+        let circuit = self.current_circuit(backend.kind()).await?;
+        let output = backend.prove(&request).await?;
+        if output.public_inputs != request.public_inputs() {
+            return Err(AppError::Proof(format!("proof commits to unexpected public inputs {:?}", output.public_inputs)));
+        }
+        tracing::info!(proof_bytes = output.proof.len(), "ZK proof generated");
+
+        Ok(ZKProof {
+            proof: hex::encode(output.proof),
+            public_inputs: output.public_inputs,
+            encrypted_intent: sealed,
+            backend: backend.kind(),
+            circuit_id: circuit.id,
+        })
+    }
+
+    /// Checks a bid proof attached to an intent with `intent_payload`: that
+    /// it commits to this bid, this payload and its signer's address, hasn't
+    /// expired and doesn't expire later than a fresh proof would, verifies with the backend that made it against its circuit's
+    /// pinned verification key, and is anchored at a
+    /// block our chain knows and that is at most `ANCHOR_MAX_AGE_SECS` old,
+    /// where our own `eth_getProof` shows the signer held the balance the
+    /// proof claims. If its intent is sealed to us, the opening must match too.
+    ///
+    /// `Ok(false)` means the proof doesn't hold for this intent: mismatched
+    /// inputs, a bad signature, expired or expiring too far out, bad encoding, a mock proof while we
+    /// aren't in mock mode, an unknown circuit or one of another backend,
+    /// rejected by the verifier, a sealed intent that doesn't open to what
+    /// was proven, an unknown or stale anchor, or a balance the chain
+    /// doesn't show. `Err` means it couldn't be checked here, e.g. `bb`
+    /// isn't installed, the circuit's pinned verification key is damaged,
+    /// the RPC is down or won't prove state at the anchor block, and the
+    /// proof must be treated as unverified.
+    #[tracing::instrument(skip_all, fields(claimed_bid = bid.bid_amount, backend = %bid.proof.backend), err)]
+    pub async fn verify_bid(&self, bid: &BidProof, intent_payload: &str) -> AppResult<bool> {
+        let expected = match bid.expected_public_inputs(intent_payload) {
+            Ok(Some(expected)) => expected,
+            Ok(None) | Err(_) => {
+                tracing::info!("bid proof is malformed or badly signed");
+                return Ok(false);
+            }
+        };
+        if bid.proof.public_inputs != expected {
+            tracing::info!(public_inputs = ?bid.proof.public_inputs, "proof is for a different bid, intent or prover");
+            return Ok(false);
+        }
+        let now = Utc::now().timestamp() as u64;
+        match expected[2].parse::<u64>() {
+            Ok(expiry) if expiry < now => {
+                tracing::info!("proof has expired");
+                return Ok(false);
+            }
+            Ok(expiry) if expiry > now + PROOF_TTL_SECS + EXPIRY_CLOCK_SKEW_SECS => {
+                tracing::info!(expiry, "proof expires further out than a fresh proof would");
+                return Ok(false);
+            }
+            _ => {}
+        }
+        if !self.verify_proof(&bid.proof).await? {
+            return Ok(false);
+        }
+        if self.sealed_to_us(bid) {
+            if let Err(e) = self.open_bid(bid) {
+                tracing::info!(error = %e, "sealed intent doesn't match the proof");
+                return Ok(false);
+            }
+        }
+        let anchor = bid.anchor().unwrap_or_default();
+        match self.bridge.block_age(anchor).await? {
+            Some(age) if age <= ANCHOR_MAX_AGE_SECS => {}
+            Some(age) => {
+                tracing::info!(%anchor, age, "proof is anchored at a stale block");
+                return Ok(false);
             }
+            None => {
+                tracing::info!(%anchor, "proof is anchored at a block the chain doesn't know");
+                return Ok(false);
             }
         }
+        let Some(account) = self.bridge.account_proof_at(bid.signer()?, anchor).await? else {
+            tracing::info!(%anchor, "proof is anchored at a block the chain doesn't know");
+            return Ok(false);
+        };
+        if bid.public_input::<u64>(7) != Some(balance_units(account.balance)) {
+            tracing::info!(%anchor, "proof claims a balance the chain doesn't show at its anchor");
+            return Ok(false);
+        }
+        Ok(true)
+    }
 
+    fn sealed_to_us(&self, bid: &BidProof) -> bool {
+        let ours = self.bridge.intent_public_key().ok();
+        ours.is_some() && parse_public_key(&bid.proof.encrypted_intent.recipient).ok() == ours
+    }
 
+    /// Decrypts a bid's sealed intent with our intent key and checks it
+    /// against the proof's public inputs: the same bid, intent hash and
+    /// commitment. Says nothing about the proof itself; see `verify_bid`.
+    pub fn open_bid(&self, bid: &BidProof) -> AppResult<IntentOpening> {
+        let opening = self.bridge.open_sealed_intent(&bid.proof.encrypted_intent)?;
+        let matches = opening.bid_amount == bid.bid_amount
+            && bid.public_input::<U256>(1) == Some(intent_hash(&opening.intent))
+            && bid.public_input::<U256>(4) == Some(opening.commitment());
+        if !matches {
+            return Err(AppError::Proof("sealed intent doesn't match what the proof commits to".to_string()));
+        }
+        Ok(opening)
+    }
 
+    async fn verify_proof(&self, proof: &ZKProof) -> AppResult<bool> {
+        let Ok(proof_bytes) = hex::decode(proof.proof.trim_start_matches("0x")) else {
+            tracing::info!("proof is not hex");
+            return Ok(false);
+        };
+        if proof.backend == ProvingBackendKind::Mock && self.settings.get().proving_backend != ProvingBackendKind::Mock {
+            tracing::info!("ignoring a mock proof outside mock mode");
+            return Ok(false);
+        }
+        let Some(circuit) = self.known_circuit(proof).await? else {
+            tracing::info!(circuit = %proof.circuit_id, "proof is from an unknown circuit");
+            return Ok(false);
+        };
+        if circuit.backend != proof.backend {
+            tracing::info!(circuit = %circuit.id, "proof's circuit belongs to another backend");
+            return Ok(false);
+        }
+        let verification_key = self.circuits.verification_key(&circuit)?;
+        self.backend(proof.backend).verify(&verification_key, &proof_bytes, &proof.public_inputs).await
+    }
 
-    pub fn verify_proof(&self, proof: &ZKProof) -> Result<bool, Box<dyn Error>> {
-        // In production, this would call Noir's verification
-        // For now, we validate the structure
-        Ok(!proof.proof.is_empty() && !proof.public_inputs.is_empty())
+    /// The configured backend, and why it can't prove here if it can't.
+    pub async fn status(&self) -> String {
+        let backend = self.backend(self.settings.get().proving_backend);
+        match backend.unavailable().await {
+            None => format!("Proving Backend: {} (ready)", backend.kind()),
+            Some(reason) => format!("Proving Backend: {} (unavailable: {})", backend.kind(), reason),
+        }
     }
 }
 
-// Noir circuit template (to be saved as circuits/main.nr)
-pub const NOIR_CIRCUIT_TEMPLATE: &str = r#"
-// CabalMesh - Privacy-Preserving Bid Verification Circuit
-// This proves: balance >= bid_amount AND bid_amount <= price_ceiling
-
-fn main(
-    balance: Field,        // private witness
-    bid_amount: pub Field, // public input
-    price_ceiling: Field   // private witness
-) {
-    // Constraint 1: User has sufficient balance
-    assert(balance >= bid_amount);
-    
-    // Constraint 2: Bid doesn't exceed the user's private limit
-    assert(bid_amount <= price_ceiling);
-    
-    // Additional constraint: Bid must be positive
-    assert(bid_amount > 0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_chain::{funded_bridge, test_data_dir, MockChain};
+    use alloy::primitives::utils::parse_ether;
+    use std::path::PathBuf;
+
+    /// A handler whose bridge has an identity holding 5 AVAX on `chain`.
+    fn handler(chain: &Arc<MockChain>, name: &str) -> (ZKHandler, Arc<BlockchainBridge>, PathBuf) {
+        let dir = test_data_dir(name);
+        let bridge = Arc::new(funded_bridge(chain, name, "5"));
+        let handler = ZKHandler::new(Arc::new(SettingsStore::load(&dir)), bridge.clone(), CircuitRegistry::load(dir.join("circuits")));
+        (handler, bridge, dir)
+    }
+
+    /// A 50 nAVAX bid for `opening`, sealed to `recipient`'s intent key.
+    async fn signed_bid(
+        handler: &ZKHandler,
+        bridge: &BlockchainBridge,
+        opening: &IntentOpening,
+        recipient: &BlockchainBridge,
+        binding: ProofBinding,
+    ) -> BidProof {
+        let anchor = bridge.anchored_balance().await.unwrap();
+        let request = ProofRequest::anchored(&anchor, 50, 60, binding);
+        let sealed = opening.seal(&recipient.intent_public_key().unwrap()).unwrap();
+        let proof = handler.generate_proof(request, sealed).await.unwrap();
+        let bid = bridge.sign_bid_proof(50, proof).unwrap();
+        assert_eq!(bid.anchor(), Some(anchor.block_hash));
+        bid
+    }
+
+    /// A bid proof holds only for the intent, bid and signer it was made
+    /// for, until it expires, while its anchor block is recent, and only for
+    /// the balance the signer held at that block.
+    #[tokio::test]
+    async fn bid_proofs_are_bound_to_intent_prover_expiry_and_anchor() {
+        let chain = Arc::new(MockChain::new());
+        let (handler, bridge, dir) = handler(&chain, "zk_bind");
+        let prover = bridge.get_primary_address().parse().unwrap();
+        let intent = r#"{"want":"lamp"}"#;
+        let opening = IntentOpening::new(intent, 50);
+
+        let anchor = bridge.anchored_balance().await.unwrap();
+        assert_eq!(anchor.balance, parse_ether("5").unwrap());
+        assert!(AccountProof { balance: anchor.balance + U256::from(1), ..anchor.clone() }.verify().is_err());
+        let request = ProofRequest::anchored(&anchor, 50, 60, ProofBinding::new(&opening, prover));
+        assert_eq!(request.balance, 5_000_000_000);
+
+        let bid = signed_bid(&handler, &bridge, &opening, &bridge, ProofBinding::new(&opening, prover)).await;
+        assert_eq!(bid.proof.backend, ProvingBackendKind::Bulletproofs);
+        assert!(handler.verify_bid(&bid, intent).await.unwrap());
+        assert!(!handler.verify_bid(&bid, r#"{"want":"desk"}"#).await.unwrap());
+
+        let inflated = BidProof { bid_amount: 10, ..bid.clone() };
+        assert!(!handler.verify_bid(&inflated, intent).await.unwrap());
+        let stolen = BidProof::sign(50, bid.proof.clone(), &PrivateKeySigner::random()).unwrap();
+        assert!(!handler.verify_bid(&stolen, intent).await.unwrap());
+
+        let expired_at = Utc::now().timestamp() as u64 - 1;
+        let binding = ProofBinding { expiry: expired_at, ..ProofBinding::new(&opening, prover) };
+        let expired = signed_bid(&handler, &bridge, &opening, &bridge, binding).await;
+        assert!(!handler.verify_bid(&expired, intent).await.unwrap());
+        let binding = ProofBinding { expiry: u64::MAX, ..ProofBinding::new(&opening, prover) };
+        let everlasting = signed_bid(&handler, &bridge, &opening, &bridge, binding).await;
+        assert!(!handler.verify_bid(&everlasting, intent).await.unwrap(), "expiry too far out");
+
+        let overstated = ProofRequest { balance: 9_000_000_000, ..ProofRequest::anchored(&anchor, 50, 60, ProofBinding::new(&opening, prover)) };
+        let sealed = opening.seal(&bridge.intent_public_key().unwrap()).unwrap();
+        let overstated = bridge.sign_bid_proof(50, handler.generate_proof(overstated, sealed).await.unwrap()).unwrap();
+        assert!(!handler.verify_bid(&overstated, intent).await.unwrap(), "balance isn't the one at the anchor");
+
+        let unknown = ProofRequest { anchor: B256::repeat_byte(9), ..request };
+        let sealed = opening.seal(&bridge.intent_public_key().unwrap()).unwrap();
+        let unanchored = bridge.sign_bid_proof(50, handler.generate_proof(unknown, sealed).await.unwrap()).unwrap();
+        assert!(!handler.verify_bid(&unanchored, intent).await.unwrap());
+
+        chain.advance_time(ANCHOR_MAX_AGE_SECS + 1);
+        assert!(!handler.verify_bid(&bid, intent).await.unwrap(), "anchor is now stale");
+        assert_ne!(bridge.anchored_balance().await.unwrap().block_hash, anchor.block_hash);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// Only the counterparty a bid's intent is sealed to can open it, and
+    /// it rejects a sealed intent the proof doesn't commit to, which third
+    /// parties can't tell apart.
+    #[tokio::test]
+    async fn sealed_intents_open_for_the_recipient_only() {
+        let chain = Arc::new(MockChain::new());
+        let (buyer, buyer_bridge, buyer_dir) = handler(&chain, "zk_buyer");
+        let (seller, seller_bridge, seller_dir) = handler(&chain, "zk_seller");
+        let prover = buyer_bridge.get_primary_address().parse().unwrap();
+        let intent = r#"{"want":"lamp"}"#;
+        let opening = IntentOpening::new(intent, 50);
+
+        let bid = signed_bid(&buyer, &buyer_bridge, &opening, &seller_bridge, ProofBinding::new(&opening, prover)).await;
+        assert!(!serde_json::to_string(&bid).unwrap().contains("lamp"));
+        assert_eq!(seller.open_bid(&bid).unwrap(), opening);
+        assert!(buyer.open_bid(&bid).is_err());
+        assert!(seller.verify_bid(&bid, intent).await.unwrap());
+        assert!(buyer.verify_bid(&bid, intent).await.unwrap());
+
+        let mut swapped = bid.proof.clone();
+        swapped.encrypted_intent = IntentOpening::new(intent, 50).seal(&seller_bridge.intent_public_key().unwrap()).unwrap();
+        let swapped = buyer_bridge.sign_bid_proof(50, swapped).unwrap();
+        assert!(matches!(seller.open_bid(&swapped), Err(AppError::Proof(_))));
+        assert!(!seller.verify_bid(&swapped, intent).await.unwrap());
+        assert!(buyer.verify_bid(&swapped, intent).await.unwrap());
+
+        std::fs::remove_dir_all(&buyer_dir).ok();
+        std::fs::remove_dir_all(&seller_dir).ok();
+    }
+
+    /// Proofs name the circuit version they were made with, and only verify
+    /// against a known circuit of their own backend with an intact key.
+    #[tokio::test]
+    async fn rejects_unknown_and_mismatched_circuits() {
+        let chain = Arc::new(MockChain::new());
+        let (handler, bridge, dir) = handler(&chain, "zk_circuits");
+        let prover = bridge.get_primary_address().parse().unwrap();
+        let opening = IntentOpening::new("intent", 50);
+
+        let bid = signed_bid(&handler, &bridge, &opening, &bridge, ProofBinding::new(&opening, prover)).await;
+        let circuit = handler.current_circuit(ProvingBackendKind::Bulletproofs).await.unwrap();
+        assert_eq!(bid.proof.circuit_id, circuit.id);
+        assert!(circuit.id.starts_with("cabalmesh_bid_range@"));
+        assert_eq!(handler.list_circuits(), vec![circuit.clone()]);
+        assert!(handler.verify_bid(&bid, "intent").await.unwrap());
+
+        let mock = handler.compile_circuit(ProvingBackendKind::Mock, false).await.unwrap();
+        for circuit_id in ["", "cabalmesh_bid_range@0000000000000000", mock.id.as_str()] {
+            let renamed = ZKProof { circuit_id: circuit_id.to_string(), ..bid.proof.clone() };
+            let renamed = bridge.sign_bid_proof(50, renamed).unwrap();
+            assert!(!handler.verify_bid(&renamed, "intent").await.unwrap(), "{:?}", circuit_id);
+        }
+
+        let integrity = handler.check_circuit_integrity(Some(&circuit.id)).unwrap();
+        assert!(integrity[0].verification_key_intact && integrity[0].source_current);
+        std::fs::write(dir.join("circuits").join(&circuit.id).join("vk"), b"swapped").unwrap();
+        assert!(!handler.check_circuit_integrity(None).unwrap().iter().any(|c| c.id == circuit.id && c.verification_key_intact));
+        assert!(matches!(handler.verify_bid(&bid, "intent").await, Err(AppError::Proof(_))));
+        assert!(matches!(handler.check_circuit_integrity(Some("nope@00")), Err(AppError::NotFound(_))));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// Mock proofs only count on nodes that are themselves in mock mode.
+    #[tokio::test]
+    async fn refuses_mock_proofs_outside_mock_mode() {
+        let chain = Arc::new(MockChain::new());
+        let (handler, bridge, dir) = handler(&chain, "zk_mock");
+        let prover = bridge.get_primary_address().parse().unwrap();
+        let opening = IntentOpening::new("intent", 50);
+        let mut mock_mode = handler.settings.get();
+        mock_mode.proving_backend = ProvingBackendKind::Mock;
+        handler.settings.update(mock_mode.clone()).unwrap();
+
+        let bid = signed_bid(&handler, &bridge, &opening, &bridge, ProofBinding::new(&opening, prover)).await;
+        assert_eq!(bid.proof.backend, ProvingBackendKind::Mock);
+        assert!(handler.verify_bid(&bid, "intent").await.unwrap());
+        let garbage = bridge.sign_bid_proof(50, ZKProof { proof: "x".to_string(), ..bid.proof.clone() }).unwrap();
+        assert!(!handler.verify_bid(&garbage, "intent").await.unwrap());
+
+        mock_mode.proving_backend = ProvingBackendKind::Bulletproofs;
+        handler.settings.update(mock_mode).unwrap();
+        assert!(!handler.verify_bid(&bid, "intent").await.unwrap());
+        assert_eq!(handler.status().await, "Proving Backend: bulletproofs (ready)");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
-"#;
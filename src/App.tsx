@@ -32,7 +32,8 @@ import { DealNotification } from "./components/DealNotification";
 import { DelegationCenter } from "./components/DelegationCenter";
 
 // Types
-import { Peer, MeshEvent, ViewState, MatchResult, AssetListingView, TxResult, QueuedTx, ContentRecord } from "./types";
+import { Peer, MeshEvent, ViewState, MatchResult, AssetListingView, TxResult, QueuedTx, ContentRecord, ConnectivityStatus } from "./types";
+import { errorMessage } from "./lib/errors";
 
 interface SharkNegotiation {
     user_price_ceiling: number;
@@ -146,6 +147,11 @@ function App() {
                     setIsProcessing(true);
                     setLogs([]);
                     addLog(`→ Encrypted intent received from peer`);
+                    if (meshEvent.proof_verified === true) {
+                        addLog(`→ Bid proof verified`);
+                    } else if (meshEvent.proof_verified === false) {
+                        addLog(`→ Bid proof did not verify, treating bid as unverified`);
+                    }
                     addLog(`→ Consulting local Shark Agent (Ollama)...`);
 
                     try {
@@ -167,7 +173,7 @@ function App() {
                         setView("notification");
                     } catch (e) {
                         console.error("Shark negotiation failed:", e);
-                        addLog(`→ Negotiation failed: ${e}`);
+                        addLog(`→ Negotiation failed: ${errorMessage(e)}`);
                         setIsProcessing(false);
                     }
                 } else {
@@ -215,10 +221,18 @@ function App() {
                 const queueId = meshEvent.queue_id || "";
                 const status = meshEvent.status || "failed";
                 const txHash = meshEvent.tx_hash;
-                console.log("📨 RelayConfirmed:", queueId, status, txHash);
-
-                invoke("mark_relay_tx_status", { queueId, status, txHash: txHash ?? null }).catch(console.error);
-                setOfflineQueue((prev) => prev.map((t) => (t.id === queueId ? { ...t, status: status as QueuedTx["status"], tx_hash: txHash ?? null } : t)));
+                console.log("📨 RelayConfirmed:", queueId, status, txHash, "from", meshEvent.relayer);
+
+                // The relay peer's claim is only a hint — the bridge checks it against
+                // the chain and returns the entry's real status.
+                invoke<QueuedTx>("verify_relay_confirmation", {
+                    queueId,
+                    status,
+                    txHash: txHash ?? null,
+                    relayer: meshEvent.relayer ?? null,
+                })
+                    .then((entry) => setOfflineQueue((prev) => prev.map((t) => (t.id === entry.id ? entry : t))))
+                    .catch(console.error);
 
             } else if (meshEvent.type === "ContentRequested") {
                 const tokenId = meshEvent.token_id;
@@ -273,15 +287,18 @@ function App() {
             }
         });
 
-        const handleOnline = () => setIsOnline(true);
-        const handleOffline = () => setIsOnline(false);
-        window.addEventListener("online", handleOnline);
-        window.addEventListener("offline", handleOffline);
+        // The backend probes the configured RPC endpoints; that's what
+        // decides online vs. offline-signed sends, so the LED follows it.
+        const unlistenConnectivity = listen<ConnectivityStatus>("connectivity-changed", (event) => {
+            setIsOnline(event.payload.state !== "offline");
+        });
+        invoke<ConnectivityStatus>("get_connectivity")
+            .then((c) => { if (c.state !== "unknown") setIsOnline(c.state === "online"); })
+            .catch(console.error);
 
         return () => {
             unlisten.then((fn) => fn());
-            window.removeEventListener("online", handleOnline);
-            window.removeEventListener("offline", handleOffline);
+            unlistenConnectivity.then((fn) => fn());
         };
     }, []);
 
@@ -341,7 +358,10 @@ function App() {
                 return;
             }
 
-            updateSearch(id, { status: "buying", message: `⚔️ Closing the deal on voucher #${match.token_id} — locking AVAX...` });
+            const staleNote = match.stale_since
+                ? ` (from a cached catalogue of ${new Date(match.stale_since).toLocaleString()} — the listing may have changed)`
+                : "";
+            updateSearch(id, { status: "buying", message: `⚔️ Closing the deal on voucher #${match.token_id}${staleNote} — locking AVAX...` });
 
             const result = await invoke<TxResult>("buy_listing", {
                 listingId: match.listing_id,
@@ -387,7 +407,7 @@ function App() {
         } catch (e) {
             console.error("AI matching / buy failed:", e);
             if (!dismissedSearchesRef.current.has(id)) {
-                updateSearch(id, { status: "error", message: `Failed: ${e}` });
+                updateSearch(id, { status: "error", message: `Failed: ${errorMessage(e)}` });
             }
         } finally {
             finishSearch();
@@ -1,7 +1,8 @@
 import React, { useState, useEffect, useCallback } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
-import { AssetListingView } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import { AssetListingView, ChainUpdate } from "../types";
 
 interface ActiveListingsCompactProps {
     refreshKey?: number;
@@ -20,10 +21,26 @@ export const ActiveListingsCompact: React.FC<ActiveListingsCompactProps> = ({ re
 
     useEffect(() => {
         load();
-        const interval = setInterval(load, 10000);
-        return () => clearInterval(interval);
     }, [load, refreshKey]);
 
+    // The backend refreshes listings in the background and sends only what changed.
+    useEffect(() => {
+        const unlisten = listen<ChainUpdate>("chain-update", (event) => {
+            const update = event.payload;
+            if (update.type !== "Listings") return;
+            const { added, updated, removed } = update.diff;
+            setListings((prev) => [
+                ...prev
+                    .filter((l) => !removed.includes(l.id))
+                    .map((l) => updated.find((u) => u.id === l.id) ?? l),
+                ...added.filter((a) => !prev.some((l) => l.id === a.id)),
+            ]);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
     return (
         <div className="relative">
             <button
@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from "react";
 import { motion } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../lib/errors";
 
 interface DelegationCenterProps {
     visible: boolean;
@@ -35,7 +36,7 @@ export const DelegationCenter: React.FC<DelegationCenterProps> = ({ visible, onC
             setTimeout(onComplete, 800); // Brief pause so the "Done" state is visible before closing
         } catch (e) {
             console.error("Delegation Failed", e);
-            alert("Delegation Failed: " + e);
+            alert("Delegation Failed: " + errorMessage(e));
             setStep("idle");
             setProgress(0);
         }
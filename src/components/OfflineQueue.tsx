@@ -34,9 +34,9 @@ export const OfflineQueue: React.FC<OfflineQueueProps> = ({ items, onDismiss })
                         <div className="flex-1 min-w-0">
                             <div className="text-slate-900 font-medium truncate">🗺️ Quest: {item.summary}</div>
                             <div className="text-slate-400 text-[11px] truncate">
-                                {item.status === "queued" && "Awaiting a fellow traveler with network to carry this onward..."}
+                                {item.status === "queued" && (item.revert_warning ? `Would be rejected on-chain right now: ${item.revert_warning}` : "Awaiting a fellow traveler with network to carry this onward...")}
                                 {item.status === "confirmed" && (item.tx_hash ? `⚔️ Delivered: ${item.tx_hash.slice(0, 10)}...` : "⚔️ Delivered on-chain")}
-                                {item.status === "failed" && "Delivery failed — try again once you have network"}
+                                {item.status === "failed" && (item.revert_warning ? `Reverted on-chain (last pre-flight: ${item.revert_warning})` : "Delivery failed — try again once you have network")}
                             </div>
                         </div>
 
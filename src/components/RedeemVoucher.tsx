@@ -3,6 +3,7 @@ import { motion } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
 import { VoucherView } from "../types";
 import { ItemCard } from "./ItemCard";
+import { errorMessage } from "../lib/errors";
 
 interface RedeemVoucherProps {
     visible: boolean;
@@ -33,7 +34,7 @@ export const RedeemVoucher: React.FC<RedeemVoucherProps> = ({ visible, onClose }
             }
         } catch (e) {
             console.error("Failed to load owned vouchers:", e);
-            setError("Failed to load your vouchers: " + e);
+            setError("Failed to load your vouchers: " + errorMessage(e));
         } finally {
             setLoading(false);
         }
@@ -73,7 +74,7 @@ export const RedeemVoucher: React.FC<RedeemVoucherProps> = ({ visible, onClose }
             setOwnerCheck(null);
         } catch (e) {
             console.error("Redeem failed:", e);
-            setError("On-chain redeem failed: " + e);
+            setError("On-chain redeem failed: " + errorMessage(e));
         } finally {
             setRedeeming(false);
         }
@@ -2,6 +2,7 @@ import React, { useState } from "react";
 import { motion } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
 import { ContentRecord } from "../types";
+import { errorMessage } from "../lib/errors";
 
 interface ServiceCreatorProps {
     onClose: () => void;
@@ -54,7 +55,7 @@ export const ServiceCreator: React.FC<ServiceCreatorProps> = ({ onClose, onDeplo
             setExtractedText(text);
         } catch (e) {
             console.error("Failed to extract PDF text:", e);
-            setError("Failed to read PDF: " + e);
+            setError("Failed to read PDF: " + errorMessage(e));
         } finally {
             setExtracting(false);
         }
@@ -95,7 +96,7 @@ export const ServiceCreator: React.FC<ServiceCreatorProps> = ({ onClose, onDeplo
             onDeploy(listingId);
         } catch (e) {
             console.error("Failed to create asset-backed listing:", e);
-            setError("On-chain listing failed: " + e);
+            setError("On-chain listing failed: " + errorMessage(e));
             setStep("idle");
         }
     };
@@ -3,6 +3,7 @@ import { motion } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
 import { ContentRecord } from "../types";
 import { PixelClassIcon } from "./icons/PixelClassIcon";
+import { errorMessage } from "../lib/errors";
 
 interface SmartEscrowProps {
     visible: boolean;
@@ -76,7 +77,7 @@ export const SmartEscrow: React.FC<SmartEscrowProps> = ({ visible, escrowId, dea
             }, 2000);
         } catch (e) {
             console.error("On-chain release failed:", e);
-            alert("On-chain release failed: " + e);
+            alert("On-chain release failed: " + errorMessage(e));
         } finally {
             setReleasing(false);
         }
@@ -105,7 +106,7 @@ export const SmartEscrow: React.FC<SmartEscrowProps> = ({ visible, escrowId, dea
             }, 2000);
         } catch (e) {
             console.error("On-chain refund failed:", e);
-            alert("On-chain refund failed: " + e);
+            alert("On-chain refund failed: " + errorMessage(e));
         } finally {
             setRefunding(false);
         }
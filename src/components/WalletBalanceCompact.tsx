@@ -1,6 +1,8 @@
 import React, { useState, useEffect, useCallback } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { ChainUpdate } from "../types";
 import { formatEther } from "ethers";
 
 interface CompressedAsset {
@@ -29,8 +31,13 @@ export const WalletBalanceCompact: React.FC = () => {
 
     useEffect(() => {
         load();
-        const interval = setInterval(load, 15000);
-        return () => clearInterval(interval);
+        // Each background balance refresh re-saves the snapshot; reload it then.
+        const unlisten = listen<ChainUpdate>("chain-update", (event) => {
+            if (event.payload.type === "Balance") load();
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
     }, [load]);
 
     const nativeBalance = snapshot?.assets?.find((a) => a.symbol === "AVAX");
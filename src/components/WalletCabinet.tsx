@@ -30,6 +30,8 @@ interface IdentityView {
     alias: string;
     emoji: string;
     address: string;
+    /** Public key counterparties seal bid intents to. */
+    intent_key: string;
 }
 
 export const WalletCabinet: React.FC<WalletCabinetProps> = ({ visible, onClose, onOpenConfig, onDelegate, peerCount = 0 }) => {
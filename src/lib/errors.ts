@@ -0,0 +1,11 @@
+import { AppError } from "../types";
+
+/** Narrows whatever `invoke` rejected with to the backend's structured error. */
+export function isAppError(e: unknown): e is AppError {
+    return typeof e === "object" && e !== null && "code" in e && "message" in e;
+}
+
+/** Human-readable text for any rejection — structured or not. */
+export function errorMessage(e: unknown): string {
+    return isAppError(e) ? e.message : String(e);
+}
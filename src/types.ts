@@ -46,6 +46,17 @@ export interface MatchResult {
     price_wei: string;
     token_id: number;
     reason: string;
+    /** Set when matched against a cached catalogue because the RPC was
+     * unreachable: when that catalogue was fetched (ISO 8601). */
+    stale_since?: string | null;
+}
+
+/** Active listings for matching, live or (when `stale`) from the cache. */
+export interface ListingCatalogue {
+    listings: AssetListingView[];
+    fetched_at: string;
+    source: { kind: "rpc" } | { kind: "mesh"; signer: string };
+    stale: boolean;
 }
 
 export interface MeshEvent {
@@ -58,12 +69,85 @@ export interface MeshEvent {
     summary?: string;
     status?: string;
     tx_hash?: string;
+    relayer?: string;
     token_id?: number;
     text?: string;
     signature?: string;
     signer_address?: string;
+    /** IntentReceived: null without a bid proof, else whether it verified. */
+    proof_verified?: boolean | null;
+}
+
+/** A bid proof as made by the proving backend. */
+export interface ZKProof {
+    proof: string;
+    public_inputs: string[];
+    encrypted_intent: SealedIntent;
+    backend: "bulletproofs" | "nargo" | "mock";
+    /** Registry id of the circuit version, `name@<source hash prefix>`. */
+    circuit_id: string;
+}
+
+/** A compiled circuit version, as listed by `list_circuits`. */
+export interface CircuitRecord {
+    id: string;
+    name: string;
+    backend: "bulletproofs" | "nargo" | "mock";
+    source_hash: string;
+    artifact_hash: string | null;
+    /** Pinned when first compiled; recompiling must reproduce it. */
+    vk_hash: string;
+    compiled_at: string;
+}
+
+/** Result of `check_circuit_integrity`, one per circuit. */
+export interface CircuitIntegrity {
+    id: string;
+    artifact_intact: boolean;
+    verification_key_intact: boolean;
+    /** Still the circuit this node proves with. */
+    source_current: boolean;
+}
+
+/** A bid's full intent, encrypted to one secp256k1 public key (hex). */
+export interface SealedIntent {
+    recipient: string;
+    ephemeral_key: string;
+    ciphertext: string;
+}
+
+/** `open_sealed_intent` result: what a sealed intent decrypts to. */
+export interface IntentOpening {
+    intent: string;
+    bid_amount: number;
+    salt: string;
 }
 
+/** Optional `bidProof` of `send_intent_to_mesh`, as returned by
+ * `generate_zk_proof`: the claimed bid, its proof and the prover's EIP-191
+ * signature over both. */
+export interface BidProof {
+    bid_amount: number;
+    proof: ZKProof;
+    signature: string;
+}
+
+/** Payload of the `proof-job` event and result of `submit_proof_job`,
+ * `get_proof_job` and `list_proof_jobs`. `cached` means an identical
+ * earlier proof was reused. */
+export type ProofJob = {
+    id: number;
+    bid_amount: number;
+    /** Unix seconds. */
+    submitted_at: number;
+} & (
+    | { state: "queued" }
+    | { state: "running"; stage: "anchoring" | "proving" | "signing" }
+    | { state: "done"; bid_proof: BidProof; cached: boolean }
+    | { state: "failed"; error: AppError }
+    | { state: "cancelled" }
+);
+
 /** Result of an action that normally hits the chain directly: either it went
  * through immediately, or the RPC was unreachable and it was signed offline
  * and queued for mesh relay instead. */
@@ -78,6 +162,10 @@ export interface QueuedTx {
     created_at: string;
     status: "queued" | "confirmed" | "failed";
     tx_hash: string | null;
+    /** Why the last pre-flight simulation reverted; the tx stays queued and is re-checked. */
+    revert_warning?: string | null;
+    /** Relay peers' "confirmed" claims the RPC couldn't see yet, one per relayer, re-checked on each pre-flight. */
+    unseen_claims?: { relayer: string | null; checks: number }[];
 }
 
 /** A piece of content (e.g. a book page) committed to by its seller: a real
@@ -102,3 +190,110 @@ export type ViewState =
     | "escrow"
     | "redeem"
     | "notification";
+
+/** Structured error every Tauri command rejects with. `code` is stable and
+ * safe to branch on; `message` is for display only. */
+export type AppErrorCode =
+    | "not_configured"
+    | "no_identity"
+    | "identity_locked"
+    | "invalid_input"
+    | "not_found"
+    | "rpc_unreachable"
+    | "insufficient_funds"
+    | "contract_reverted"
+    | "rpc_rejected"
+    | "not_ready"
+    | "mesh_error"
+    | "model_unavailable"
+    | "proof_failed"
+    | "storage_error"
+    | "crypto_error";
+
+export interface AppError {
+    code: AppErrorCode;
+    message: string;
+    details:
+        | { call: string; reason: { kind: string; [key: string]: unknown } }
+        | { subsystem: Subsystem }
+        | null;
+}
+
+export type Subsystem = "mesh" | "bridge_sync" | "ollama";
+
+/** Payload of the `node-state` event and `get_node_state` command. */
+export type NodeState =
+    | { state: "booting" }
+    | { state: "degraded"; reasons: { subsystem: Subsystem; message: string }[] }
+    | { state: "ready" }
+    | { state: "shutting_down" };
+
+/** Changes found by a background refresh, keyed by listing/deal id. */
+export interface ListDiff<T> {
+    added: T[];
+    updated: T[];
+    removed: number[];
+}
+
+/** Payload of the `chain-update` event; only sent when something changed. */
+export type ChainUpdate =
+    | { type: "Balance"; address: string; balance_wei: string; previous_wei: string | null }
+    | { type: "Listings"; diff: ListDiff<AssetListingView> }
+    | { type: "Deals"; address: string; diff: ListDiff<DealView> };
+
+/** Payload of the `connectivity-changed` event and `get_connectivity` command. */
+export interface ConnectivityStatus {
+    state: "unknown" | "online" | "offline";
+    since: string;
+    /** The RPC endpoint that answered the latest successful probe. */
+    endpoint: string | null;
+}
+
+/** Contents of `settings.json`, as returned by `get_settings`. */
+export interface Settings {
+    rpc_urls: string[];
+    escrow_contract_address: string | null;
+    marketplace_contract_address: string | null;
+    voucher_contract_address: string | null;
+    ollama_url: string;
+    ollama_model: string;
+    /** Per-agent model and Ollama server; null falls back to the two above. */
+    negotiation_model: string | null;
+    negotiation_url: string | null;
+    matching_model: string | null;
+    matching_url: string | null;
+    mesh_topic: string;
+    mesh_listen_addr: string;
+    session_expiry_secs: number;
+    relay_fee_avax: string;
+    control_api_enabled: boolean;
+    control_api_port: number;
+    /** Background refresh intervals while online, in seconds; 0 disables. */
+    refresh_balance_secs: number;
+    refresh_listings_secs: number;
+    refresh_deals_secs: number;
+    /** Addresses whose mesh catalogue snapshots are accepted for offline matching. */
+    catalogue_peers: string[];
+    /** Use the in-memory simulated chain instead of `rpc_urls` (restart required). */
+    demo_chain: boolean;
+    /** How bid proofs are made; reported by `get_bridge_status`. */
+    proving_backend: "bulletproofs" | "nargo" | "mock";
+    /** Key bid intents are sealed to when no `recipientKey` is given. */
+    intent_committee_key: string | null;
+    /** Bid proofs generated at once, 1-16 (restart required). */
+    proof_workers: number;
+}
+
+/** Result of `update_settings`: which changed fields are live already and
+ * which only take effect after an app restart. */
+export interface SettingsUpdate {
+    settings: Settings;
+    applied: (keyof Settings)[];
+    requires_restart: (keyof Settings)[];
+}
+
+/** The node instance this window belongs to (`get_profile`). */
+export interface Profile {
+    name: string | null;
+    data_dir: string;
+}